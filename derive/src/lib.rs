@@ -1,3 +1,6 @@
+// darling expands `#[darling(default)]` into code that trips this lint
+#![allow(clippy::manual_unwrap_or_default)]
+
 use darling::{FromDeriveInput, FromField, FromVariant, ToTokens};
 use proc_macro::TokenStream;
 use syn::*;
@@ -5,6 +8,8 @@ use syn::*;
 #[macro_use]
 extern crate quote;
 
+mod visitors;
+
 #[derive(FromDeriveInput, Debug)]
 #[darling(attributes(weedle))]
 struct MacroTopArgs {
@@ -12,6 +17,8 @@ struct MacroTopArgs {
     cut: Option<String>,
     #[darling(default)]
     context: bool,
+    /// Resumes at the given `crate::recovery::Boundary` when parsing fails
+    recover: Option<String>,
 }
 
 #[derive(FromField, Debug)]
@@ -36,6 +43,11 @@ struct MacroVariantArgs {
     post_check: Option<String>,
     #[darling(default)]
     generic_into: bool,
+    /// Excludes the variant from parsing, e.g. for nodes built by error recovery
+    #[darling(default)]
+    skip: bool,
+    /// Only parses the variant when the `crate::dialect::ParseOptions` field is set
+    extension: Option<String>,
 }
 
 fn string_to_tokens<T: syn::parse::Parse + ToTokens>(
@@ -185,6 +197,9 @@ fn generate_enum(data_enum: &DataEnum) -> Result<proc_macro2::TokenStream> {
         .iter()
         .map(|variant| {
             let args = MacroVariantArgs::from_variant(variant).map_err(syn::Error::from)?;
+            if args.skip {
+                return Ok(None);
+            }
 
             let variant_id = &variant.ident;
             let fields = match &variant.fields {
@@ -203,16 +218,24 @@ fn generate_enum(data_enum: &DataEnum) -> Result<proc_macro2::TokenStream> {
             if let Some(post_check) = args.post_check {
                 parser = get_post_check(post_check, &parser)?;
             }
+            if let Some(extension) = args.extension {
+                let extension = string_to_tokens::<Ident>(extension)?;
+                parser = quote! {
+                    crate::dialect::extension(|options| options.#extension, #parser)
+                };
+            }
 
-            Ok(parser)
+            Ok(Some(parser))
         })
-        .collect::<Result<Vec<_>>>()?;
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten();
 
     let result = quote! {
         use nom::Parser;
         alt!(
             #(#field_parsers,)*
-        )(input)
+        ).parse(input)
     };
 
     // eprintln!("\n***\nglobal_impl: {}\n---\n", result);
@@ -220,6 +243,278 @@ fn generate_enum(data_enum: &DataEnum) -> Result<proc_macro2::TokenStream> {
     Ok(result)
 }
 
+fn get_field_members(data_struct: &DataStruct) -> Vec<proc_macro2::TokenStream> {
+    data_struct
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(id) => quote! { #id },
+            None => {
+                let index = Index::from(i);
+                quote! { #index }
+            }
+        })
+        .collect()
+}
+
+/// Generates `impl Trait for Type` where every type parameter is bound by the trait
+fn generate_trait_impl(
+    ast: &syn::DeriveInput,
+    trait_path: proc_macro2::TokenStream,
+    items: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let id = &ast.ident;
+    let generics = &ast.generics;
+    let type_param_ids = generics.type_params().map(|p| &p.ident).collect::<Vec<_>>();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #trait_path for #id #ty_generics
+        where
+            #(#type_param_ids: #trait_path,)*
+        {
+            #items
+        }
+    }
+}
+
+fn generate_spanned(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let body = match &ast.data {
+        syn::Data::Struct(data_struct) => {
+            let members = get_field_members(data_struct);
+            quote! {
+                crate::span::Span::default()
+                    #(.join(crate::span::Spanned::span(&self.#members)))*
+            }
+        }
+        syn::Data::Enum(data_enum) => {
+            let variant_ids = data_enum.variants.iter().map(|variant| &variant.ident);
+            quote! {
+                match self {
+                    #(Self::#variant_ids(inner) => crate::span::Spanned::span(inner),)*
+                }
+            }
+        }
+        syn::Data::Union(_) => panic!("Unions not supported"),
+    };
+
+    generate_trait_impl(
+        ast,
+        quote! { crate::span::Spanned },
+        quote! {
+            fn span(&self) -> crate::span::Span {
+                #body
+            }
+        },
+    )
+}
+
+fn generate_terminals(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let body = match &ast.data {
+        syn::Data::Struct(data_struct) => {
+            let members = get_field_members(data_struct);
+            quote! {
+                #(crate::lossless::Terminals::for_each_terminal(&self.#members, f);)*
+            }
+        }
+        syn::Data::Enum(data_enum) => {
+            let variant_ids = data_enum.variants.iter().map(|variant| &variant.ident);
+            quote! {
+                match self {
+                    #(Self::#variant_ids(inner) => crate::lossless::Terminals::for_each_terminal(inner, f),)*
+                }
+            }
+        }
+        syn::Data::Union(_) => panic!("Unions not supported"),
+    };
+
+    generate_trait_impl(
+        ast,
+        quote! { crate::lossless::Terminals },
+        quote! {
+            fn for_each_terminal(&self, f: &mut dyn FnMut(&str, crate::span::Span)) {
+                #body
+            }
+        },
+    )
+}
+
+fn generate_shift(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let body = match &ast.data {
+        syn::Data::Struct(data_struct) => {
+            let members = get_field_members(data_struct);
+            quote! {
+                #(crate::span::Shift::shift(&mut self.#members, delta);)*
+            }
+        }
+        syn::Data::Enum(data_enum) => {
+            let variant_ids = data_enum.variants.iter().map(|variant| &variant.ident);
+            quote! {
+                match self {
+                    #(Self::#variant_ids(inner) => crate::span::Shift::shift(inner, delta),)*
+                }
+            }
+        }
+        syn::Data::Union(_) => panic!("Unions not supported"),
+    };
+
+    generate_trait_impl(
+        ast,
+        quote! { crate::span::Shift },
+        quote! {
+            fn shift(&mut self, delta: isize) {
+                #body
+            }
+        },
+    )
+}
+
+fn to_snake_case(id: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in id.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+/// Returns the names of the `Visit` and `VisitMut` methods of a node
+fn visit_methods(id: &Ident) -> (Ident, Ident) {
+    let name = to_snake_case(&id.to_string());
+    (
+        format_ident!("visit_{}", name),
+        format_ident!("visit_{}_mut", name),
+    )
+}
+
+/// Whether a node has methods in the visitors. Generic wrappers such as `Braced<T>` and
+/// private helpers of the parser have none and only walk their fields.
+fn has_visit_methods(vis: &Visibility, generics: &Generics) -> bool {
+    generics.type_params().next().is_none() && matches!(vis, Visibility::Public(_))
+}
+
+/// Generates `impl Walk`, visiting the node with the `Visit` method named after the type
+fn generate_walk(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let walk_fields = |method: proc_macro2::TokenStream, reference: proc_macro2::TokenStream| {
+        match &ast.data {
+            syn::Data::Struct(data_struct) => {
+                let members = get_field_members(data_struct);
+                quote! {
+                    #(crate::visit::Walk::#method(#reference self.#members, visitor);)*
+                }
+            }
+            syn::Data::Enum(data_enum) => {
+                let variant_ids = data_enum.variants.iter().map(|variant| &variant.ident);
+                quote! {
+                    match self {
+                        #(Self::#variant_ids(inner) => crate::visit::Walk::#method(inner, visitor),)*
+                    }
+                }
+            }
+            syn::Data::Union(_) => panic!("Unions not supported"),
+        }
+    };
+    let walk = walk_fields(quote! { accept }, quote! { & });
+    let walk_mut = walk_fields(quote! { accept_mut }, quote! { &mut });
+
+    let id = &ast.ident;
+    let generics = &ast.generics;
+    let type_param_ids = generics.type_params().map(|p| &p.ident).collect::<Vec<_>>();
+    let (accept, accept_mut) = if has_visit_methods(&ast.vis, generics) {
+        let (visit, visit_mut) = visit_methods(id);
+        (
+            quote! { visitor.#visit(self) },
+            quote! { visitor.#visit_mut(self) },
+        )
+    } else {
+        (
+            quote! { crate::visit::Walk::walk(self, visitor) },
+            quote! { crate::visit::Walk::walk_mut(self, visitor) },
+        )
+    };
+
+    quote! {
+        impl<'a, #(#type_param_ids),*> crate::visit::Walk<'a> for #id #generics
+        where
+            #(#type_param_ids: crate::visit::Walk<'a>,)*
+        {
+            fn accept<V: crate::visit::Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+                #accept
+            }
+
+            fn accept_mut<V: crate::visit::VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+                #accept_mut
+            }
+
+            fn walk<V: crate::visit::Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+                #walk
+            }
+
+            fn walk_mut<V: crate::visit::VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+                #walk_mut
+            }
+        }
+    }
+}
+
+/// Generates `impl IntoOwned`, converting every field. The owned type has `'static` in place
+/// of the lifetime and the owned counterpart of every type parameter.
+fn generate_into_owned(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let id = &ast.ident;
+    let body = match &ast.data {
+        syn::Data::Struct(data_struct) => {
+            let members = get_field_members(data_struct);
+            quote! {
+                #id {
+                    #(#members: crate::owned::IntoOwned::into_owned(self.#members),)*
+                }
+            }
+        }
+        syn::Data::Enum(data_enum) => {
+            let variant_ids = data_enum.variants.iter().map(|variant| &variant.ident);
+            quote! {
+                match self {
+                    #(Self::#variant_ids(inner) => {
+                        #id::#variant_ids(crate::owned::IntoOwned::into_owned(inner))
+                    })*
+                }
+            }
+        }
+        syn::Data::Union(_) => panic!("Unions not supported"),
+    };
+
+    let generics = &ast.generics;
+    let type_param_ids = generics.type_params().map(|p| &p.ident).collect::<Vec<_>>();
+    let owned_params = generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(_) => quote! { 'static },
+        GenericParam::Type(param) => {
+            let id = &param.ident;
+            quote! { <#id as crate::owned::IntoOwned>::Owned }
+        }
+        GenericParam::Const(param) => {
+            let id = &param.ident;
+            quote! { #id }
+        }
+    });
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics crate::owned::IntoOwned for #id #ty_generics
+        where
+            #(#type_param_ids: crate::owned::IntoOwned,)*
+        {
+            type Owned = #id<#(#owned_params),*>;
+
+            fn into_owned(self) -> Self::Owned {
+                #body
+            }
+        }
+    }
+}
+
 fn generate(ast: &syn::DeriveInput) -> Result<TokenStream> {
     let args = MacroTopArgs::from_derive_input(ast).map_err(syn::Error::from)?;
 
@@ -247,15 +542,43 @@ fn generate(ast: &syn::DeriveInput) -> Result<TokenStream> {
         };
     }
 
+    if let Some(recover) = args.recover {
+        let boundary = string_to_tokens::<Ident>(recover)?;
+        impl_body = quote! {
+            crate::recovery::recover(
+                crate::recovery::Boundary::#boundary,
+                input,
+                |input| { #impl_body },
+                Self::Invalid,
+            )
+        };
+    }
+
     let impl_head = quote! { impl<'a,#(#type_param_ids),*> };
     let impl_tail = quote! { for #id #generics #impl_bound };
 
+    let spanned = generate_spanned(ast);
+    let terminals = generate_terminals(ast);
+    let shift = generate_shift(ast);
+    let walk = generate_walk(ast);
+    let into_owned = generate_into_owned(ast);
+
     Ok(quote! {
         #impl_head crate::Parse<'a> #impl_tail {
             fn parse_tokens<'slice>(input: crate::tokens::Tokens<'slice, 'a>) -> crate::VerboseResult<crate::tokens::Tokens<'slice, 'a>, Self> {
                 #impl_body
             }
         }
+
+        #spanned
+
+        #terminals
+
+        #shift
+
+        #walk
+
+        #into_owned
     }
     .into())
 }
@@ -271,3 +594,14 @@ pub fn weedle(input: TokenStream) -> TokenStream {
         Err(e) => e.to_compile_error().into(),
     }
 }
+
+/// Generates the `Visit` and `VisitMut` traits, with a method for every public node deriving
+/// `Weedle` without type parameters, and for the nodes given as input, whose `Walk` is
+/// implemented by hand
+#[proc_macro]
+pub fn visitors(input: TokenStream) -> TokenStream {
+    match visitors::generate(input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
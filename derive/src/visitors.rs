@@ -0,0 +1,226 @@
+//! The `Visit` and `VisitMut` traits, with a method for every node deriving `Weedle`
+//!
+//! A derive only sees the type it is applied to, so the nodes are collected from the sources
+//! of the crate instead, following its `mod` declarations and their `#[path]` attributes from
+//! `src/lib.rs`. Every file read is also included in the expansion with `include_bytes!`, so
+//! Cargo rebuilds the crate when one of them changes and a new node gets its methods for free.
+//!
+//! The nodes defined by a `macro_rules!` macro are not seen, as the macro is not expanded; they
+//! are listed in the invocation with the nodes walked by hand.
+
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Span, TokenStream};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::*;
+
+use crate::{has_visit_methods, visit_methods};
+
+/// A node with a method in the visitors
+struct Node {
+    /// The `#[cfg]` attributes of the node and of its enclosing modules
+    cfgs: Vec<Attribute>,
+    ty: TokenStream,
+    id: Ident,
+}
+
+pub fn generate(input: proc_macro::TokenStream) -> Result<TokenStream> {
+    // The nodes walked by hand, named by the caller
+    let walked_by_hand = Punctuated::<Type, Token![,]>::parse_terminated.parse(input)?;
+    let mut nodes = walked_by_hand
+        .iter()
+        .map(|ty| {
+            let id = match ty {
+                Type::Path(path) => path.path.segments.last().map(|segment| &segment.ident),
+                _ => None,
+            };
+            let id = id.ok_or_else(|| Error::new_spanned(ty, "expected the path of a node"))?;
+            Ok(Node {
+                cfgs: vec![],
+                ty: quote! { #ty },
+                id: id.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let root = std::env::var("CARGO_MANIFEST_DIR")
+        .map(|dir| PathBuf::from(dir).join("src"))
+        .map_err(|err| Error::new(Span::call_site(), err))?;
+    let mut files = vec![];
+    collect_file(
+        &root.join("lib.rs"),
+        &root,
+        &[],
+        &[],
+        &mut nodes,
+        &mut files,
+    )?;
+    let files = files.iter().map(|file| file.to_string_lossy());
+
+    let visit = nodes.iter().map(|node| {
+        let Node { cfgs, ty, id } = node;
+        let (visit, _) = visit_methods(id);
+        quote! {
+            #(#cfgs)*
+            fn #visit(&mut self, node: &#ty) {
+                node.walk(self)
+            }
+        }
+    });
+    let visit_mut = nodes.iter().map(|node| {
+        let Node { cfgs, ty, id } = node;
+        let (_, visit_mut) = visit_methods(id);
+        quote! {
+            #(#cfgs)*
+            fn #visit_mut(&mut self, node: &mut #ty) {
+                node.walk_mut(self)
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(const _: &[u8] = include_bytes!(#files);)*
+
+        /// Visits the nodes of the syntax tree by reference
+        pub trait Visit<'a> {
+            #(#visit)*
+        }
+
+        /// Visits the nodes of the syntax tree by mutable reference
+        pub trait VisitMut<'a> {
+            #(#visit_mut)*
+        }
+    })
+}
+
+/// Collects the nodes of the module in `file`, whose submodules are in `dir`
+fn collect_file(
+    file: &Path,
+    dir: &Path,
+    module: &[Ident],
+    cfgs: &[Attribute],
+    nodes: &mut Vec<Node>,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let source = std::fs::read_to_string(file).map_err(|err| {
+        Error::new(
+            Span::call_site(),
+            format!("cannot read {}: {err}", file.display()),
+        )
+    })?;
+    files.push(file.to_owned());
+    let items = syn::parse_file(&source)?.items;
+    // A `#[path]` is relative to the directory of the file
+    let path_dir = file.parent().unwrap_or(dir);
+    collect_items(&items, dir, path_dir, module, cfgs, nodes, files)
+}
+
+/// Collects the nodes of `items`, whose submodules are in `dir`, or in `path_dir` joined with
+/// their `#[path]`
+fn collect_items(
+    items: &[Item],
+    dir: &Path,
+    path_dir: &Path,
+    module: &[Ident],
+    cfgs: &[Attribute],
+    nodes: &mut Vec<Node>,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for item in items {
+        let (attrs, vis, id, generics) = match item {
+            Item::Struct(item) => (&item.attrs, &item.vis, &item.ident, &item.generics),
+            Item::Enum(item) => (&item.attrs, &item.vis, &item.ident, &item.generics),
+            Item::Mod(item) => {
+                if is_test(&item.attrs) {
+                    continue;
+                }
+                let cfgs = with_cfgs(cfgs, &item.attrs);
+                let mut module = module.to_vec();
+                module.push(item.ident.clone());
+                let sub_dir = dir.join(item.ident.to_string());
+                let path = path_attr(&item.attrs)?;
+                match (&item.content, path) {
+                    (Some((_, items)), path) => {
+                        let sub_dir = path.map_or(sub_dir, |path| path_dir.join(path));
+                        collect_items(items, &sub_dir, &sub_dir, &module, &cfgs, nodes, files)?;
+                    }
+                    (None, Some(path)) => {
+                        let file = path_dir.join(path);
+                        let sub_dir = file.parent().unwrap_or(dir).to_owned();
+                        collect_file(&file, &sub_dir, &module, &cfgs, nodes, files)?;
+                    }
+                    (None, None) => {
+                        let file = dir.join(format!("{}.rs", item.ident));
+                        let file = match file.exists() {
+                            true => file,
+                            false => sub_dir.join("mod.rs"),
+                        };
+                        collect_file(&file, &sub_dir, &module, &cfgs, nodes, files)?;
+                    }
+                }
+                continue;
+            }
+            // The items of a macro invocation written out in its body, e.g. `cfg_if!`
+            Item::Macro(item) if item.ident.is_none() => {
+                if let Ok(file) = syn::parse2::<File>(item.mac.tokens.clone()) {
+                    let cfgs = with_cfgs(cfgs, &item.attrs);
+                    collect_items(&file.items, dir, path_dir, module, &cfgs, nodes, files)?;
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        if derives_weedle(attrs) && has_visit_methods(vis, generics) {
+            nodes.push(Node {
+                cfgs: with_cfgs(cfgs, attrs),
+                ty: quote! { crate #(::#module)*::#id #generics },
+                id: id.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The value of the `#[path = "..."]` attribute of a module
+fn path_attr(attrs: &[Attribute]) -> Result<Option<String>> {
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("path")) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+    match attr.parse_meta()? {
+        Meta::NameValue(MetaNameValue {
+            lit: Lit::Str(path),
+            ..
+        }) => Ok(Some(path.value())),
+        meta => Err(Error::new_spanned(meta, "expected `#[path = \"...\"]`")),
+    }
+}
+
+fn derives_weedle(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| {
+            path.segments
+                .last()
+                .is_some_and(|last| last.ident == "Weedle")
+        })
+}
+
+fn is_test(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path.is_ident("cfg") && attr.parse_args::<Ident>().is_ok_and(|arg| arg == "test")
+    })
+}
+
+fn with_cfgs(cfgs: &[Attribute], attrs: &[Attribute]) -> Vec<Attribute> {
+    let own = attrs.iter().filter(|attr| attr.path.is_ident("cfg"));
+    cfgs.iter().chain(own).cloned().collect()
+}
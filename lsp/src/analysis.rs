@@ -0,0 +1,632 @@
+//! The language features, computed from the text of the open documents on every request
+
+use lsp_types::{
+    DiagnosticSeverity, DocumentSymbol, FormattingOptions, Hover, HoverContents, Location,
+    MarkupContent, MarkupKind, Position, SymbolKind, TextEdit, Uri,
+};
+use weedle::common::Identifier;
+use weedle::diagnostic::{Diagnostic, Severity};
+use weedle::dialect::ParseOptions;
+use weedle::dictionary::DictionaryMember;
+use weedle::fmt::{format_source_file, FormatOptions};
+use weedle::interface::{CallbackInterfaceMember, Inheritance, InterfaceMember};
+use weedle::legacy::ExceptionMember;
+use weedle::lossless::SourceFile;
+use weedle::members::Modifier;
+use weedle::mixin::MixinMember;
+use weedle::namespace::NamespaceMember;
+use weedle::span::{Span, Spanned};
+use weedle::symbols::{Symbol, SymbolTable};
+use weedle::types::{ConstType, DistinguishableType, SingleType, Type};
+use weedle::visit::{Visit, Walk};
+use weedle::{Definition, IncludesStatementDefinition};
+
+use crate::document::{Document, Documents};
+
+/// Returns the syntax errors of the document, or the validation errors if there are none
+pub fn diagnostics(document: &Document, options: &ParseOptions) -> Vec<lsp_types::Diagnostic> {
+    let (definitions, errors) = weedle::parse_recovering_with(&document.text, options);
+    let diagnostics = if errors.is_empty() {
+        weedle::validate::validate(&definitions)
+    } else {
+        errors.iter().map(Diagnostic::from).collect()
+    };
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut message = diagnostic.message.clone();
+            for line in diagnostic.label.iter().chain(&diagnostic.notes) {
+                message.push('\n');
+                message.push_str(line);
+            }
+            lsp_types::Diagnostic {
+                range: document.range(diagnostic.span),
+                severity: Some(match diagnostic.severity {
+                    Severity::Error => DiagnosticSeverity::ERROR,
+                    Severity::Warning => DiagnosticSeverity::WARNING,
+                }),
+                source: Some("weedle".to_owned()),
+                message,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Returns the definitions of the document, with their members as children
+pub fn document_symbols(document: &Document, options: &ParseOptions) -> Vec<DocumentSymbol> {
+    let (definitions, _) = weedle::parse_recovering_with(&document.text, options);
+    definitions
+        .iter()
+        .filter_map(|definition| definition_symbol(document, definition))
+        .collect()
+}
+
+fn definition_symbol(document: &Document, definition: &Definition<'_>) -> Option<DocumentSymbol> {
+    let (detail, kind, identifier, children) = match definition {
+        Definition::Interface(def) => (
+            "interface",
+            SymbolKind::INTERFACE,
+            &def.identifier,
+            interface_members(document, &def.members.body),
+        ),
+        Definition::PartialInterface(def) => (
+            "partial interface",
+            SymbolKind::INTERFACE,
+            &def.identifier,
+            interface_members(document, &def.members.body),
+        ),
+        Definition::CallbackInterface(def) => (
+            "callback interface",
+            SymbolKind::INTERFACE,
+            &def.identifier,
+            def.members
+                .body
+                .iter()
+                .filter_map(|member| match member {
+                    CallbackInterfaceMember::Const(member) => {
+                        Some(named(document, member, &member.identifier, "const"))
+                    }
+                    CallbackInterfaceMember::Operation(member) => {
+                        Some(operation(document, member, &member.identifier, None))
+                    }
+                    CallbackInterfaceMember::Invalid(_) => None,
+                })
+                .collect(),
+        ),
+        Definition::InterfaceMixin(def) => (
+            "interface mixin",
+            SymbolKind::INTERFACE,
+            &def.identifier,
+            mixin_members(document, &def.members.body),
+        ),
+        Definition::PartialInterfaceMixin(def) => (
+            "partial interface mixin",
+            SymbolKind::INTERFACE,
+            &def.identifier,
+            mixin_members(document, &def.members.body),
+        ),
+        Definition::Namespace(def) => (
+            "namespace",
+            SymbolKind::NAMESPACE,
+            &def.identifier,
+            namespace_members(document, &def.members.body),
+        ),
+        Definition::PartialNamespace(def) => (
+            "partial namespace",
+            SymbolKind::NAMESPACE,
+            &def.identifier,
+            namespace_members(document, &def.members.body),
+        ),
+        Definition::Dictionary(def) => (
+            "dictionary",
+            SymbolKind::STRUCT,
+            &def.identifier,
+            dictionary_members(document, &def.members.body),
+        ),
+        Definition::PartialDictionary(def) => (
+            "partial dictionary",
+            SymbolKind::STRUCT,
+            &def.identifier,
+            dictionary_members(document, &def.members.body),
+        ),
+        Definition::Enum(def) => (
+            "enum",
+            SymbolKind::ENUM,
+            &def.identifier,
+            def.values
+                .body
+                .list
+                .iter()
+                .map(|value| {
+                    symbol(
+                        document,
+                        value.0.to_string(),
+                        None,
+                        SymbolKind::ENUM_MEMBER,
+                        value.span(),
+                        value.span(),
+                        None,
+                    )
+                })
+                .collect(),
+        ),
+        Definition::Callback(def) => ("callback", SymbolKind::FUNCTION, &def.identifier, vec![]),
+        Definition::Typedef(def) => (
+            "typedef",
+            SymbolKind::TYPE_PARAMETER,
+            &def.identifier,
+            vec![],
+        ),
+        Definition::Exception(def) => (
+            "exception",
+            SymbolKind::STRUCT,
+            &def.identifier,
+            def.members
+                .body
+                .iter()
+                .map(|member| match member {
+                    ExceptionMember::Const(member) => {
+                        named(document, member, &member.identifier, "const")
+                    }
+                    ExceptionMember::Field(member) => {
+                        named(document, member, &member.identifier, "field")
+                    }
+                })
+                .collect(),
+        ),
+        Definition::IncludesStatement(_) | Definition::Implements(_) | Definition::Invalid(_) => {
+            return None
+        }
+    };
+    Some(symbol(
+        document,
+        identifier.0.to_string(),
+        Some(detail),
+        kind,
+        definition.span(),
+        identifier.span(),
+        Some(children),
+    ))
+}
+
+fn interface_members(document: &Document, members: &[InterfaceMember<'_>]) -> Vec<DocumentSymbol> {
+    let unnamed = |member: &dyn Spanned, name: &str, kind| {
+        let span = member.span();
+        symbol(document, name.to_owned(), None, kind, span, span, None)
+    };
+    members
+        .iter()
+        .filter_map(|member| {
+            Some(match member {
+                InterfaceMember::Const(member) => {
+                    named(document, member, &member.identifier, "const")
+                }
+                InterfaceMember::Attribute(member) => {
+                    named(document, member, &member.identifier, "attribute")
+                }
+                InterfaceMember::Operation(member) => {
+                    operation(document, member, &member.identifier, member.modifier)
+                }
+                InterfaceMember::Constructor(member) => {
+                    unnamed(member, "constructor", SymbolKind::CONSTRUCTOR)
+                }
+                InterfaceMember::Iterable(member) => {
+                    unnamed(member, "iterable", SymbolKind::PROPERTY)
+                }
+                InterfaceMember::AsyncIterable(member) => {
+                    unnamed(member, "async iterable", SymbolKind::PROPERTY)
+                }
+                InterfaceMember::Maplike(member) => {
+                    unnamed(member, "maplike", SymbolKind::PROPERTY)
+                }
+                InterfaceMember::Setlike(member) => {
+                    unnamed(member, "setlike", SymbolKind::PROPERTY)
+                }
+                InterfaceMember::Stringifier(member) => {
+                    unnamed(member, "stringifier", SymbolKind::METHOD)
+                }
+                InterfaceMember::LegacyIterable(member) => {
+                    unnamed(member, "legacyiterable", SymbolKind::PROPERTY)
+                }
+                InterfaceMember::Invalid(_) => return None,
+            })
+        })
+        .collect()
+}
+
+fn mixin_members(document: &Document, members: &[MixinMember<'_>]) -> Vec<DocumentSymbol> {
+    members
+        .iter()
+        .filter_map(|member| {
+            Some(match member {
+                MixinMember::Const(member) => named(document, member, &member.identifier, "const"),
+                MixinMember::Attribute(member) => {
+                    named(document, member, &member.identifier, "attribute")
+                }
+                MixinMember::Operation(member) => {
+                    operation(document, member, &member.identifier, None)
+                }
+                MixinMember::Stringifier(member) => {
+                    let span = member.span();
+                    let kind = SymbolKind::METHOD;
+                    symbol(document, "stringifier".into(), None, kind, span, span, None)
+                }
+                MixinMember::Invalid(_) => return None,
+            })
+        })
+        .collect()
+}
+
+fn namespace_members(document: &Document, members: &[NamespaceMember<'_>]) -> Vec<DocumentSymbol> {
+    members
+        .iter()
+        .filter_map(|member| {
+            Some(match member {
+                NamespaceMember::Const(member) => {
+                    named(document, member, &member.identifier, "const")
+                }
+                NamespaceMember::Attribute(member) => {
+                    named(document, member, &member.identifier, "attribute")
+                }
+                NamespaceMember::Operation(member) => {
+                    operation(document, member, &member.identifier, None)
+                }
+                NamespaceMember::Invalid(_) => return None,
+            })
+        })
+        .collect()
+}
+
+fn dictionary_members(
+    document: &Document,
+    members: &[DictionaryMember<'_>],
+) -> Vec<DocumentSymbol> {
+    members
+        .iter()
+        .filter_map(|member| match member {
+            DictionaryMember::Field(member) => {
+                Some(named(document, member, &member.identifier, "field"))
+            }
+            DictionaryMember::Invalid(_) => None,
+        })
+        .collect()
+}
+
+/// Returns the symbol of a constant, an attribute or a dictionary field
+fn named(
+    document: &Document,
+    member: &dyn Spanned,
+    identifier: &Identifier<'_>,
+    detail: &'static str,
+) -> DocumentSymbol {
+    let kind = match detail {
+        "const" => SymbolKind::CONSTANT,
+        "field" => SymbolKind::FIELD,
+        _ => SymbolKind::PROPERTY,
+    };
+    let name = identifier.0.to_string();
+    symbol(
+        document,
+        name,
+        Some(detail),
+        kind,
+        member.span(),
+        identifier.span(),
+        None,
+    )
+}
+
+/// Returns the symbol of an operation, named after its special keyword if it has no name
+fn operation(
+    document: &Document,
+    member: &dyn Spanned,
+    identifier: &Option<Identifier<'_>>,
+    modifier: Option<Modifier>,
+) -> DocumentSymbol {
+    let kind = SymbolKind::METHOD;
+    match identifier {
+        Some(identifier) => {
+            let name = identifier.0.to_string();
+            symbol(
+                document,
+                name,
+                Some("operation"),
+                kind,
+                member.span(),
+                identifier.span(),
+                None,
+            )
+        }
+        None => {
+            let name = match modifier {
+                Some(Modifier::Getter(_)) => "getter",
+                Some(Modifier::Setter(_)) => "setter",
+                Some(Modifier::Deleter(_)) => "deleter",
+                Some(Modifier::Static(_)) | None => "operation",
+            };
+            let span = member.span();
+            symbol(document, name.to_owned(), None, kind, span, span, None)
+        }
+    }
+}
+
+fn symbol(
+    document: &Document,
+    name: String,
+    detail: Option<&str>,
+    kind: SymbolKind,
+    span: Span,
+    selection_span: Span,
+    children: Option<Vec<DocumentSymbol>>,
+) -> DocumentSymbol {
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail: detail.map(str::to_owned),
+        kind,
+        tags: None,
+        deprecated: None,
+        range: document.range(span),
+        selection_range: document.range(selection_span),
+        children,
+    }
+}
+
+/// Returns the definition of the name at the position, searching every open document
+pub fn definition(
+    documents: &Documents,
+    uri: &Uri,
+    position: Position,
+    options: &ParseOptions,
+) -> Option<Location> {
+    let files = parse_all(documents, uri, options)?;
+    let current = &files[0];
+    let offset = current.document.offset(position);
+    let name = Targets::find(&current.file.definitions, offset).reference?;
+
+    let (file, symbol) = lookup(&files, name.as_str())?;
+    Some(Location::new(
+        file.document.uri.clone(),
+        file.document.range(symbol.identifier().span()),
+    ))
+}
+
+/// Describes the definition of the name at the position, or the type at the position with its
+/// names resolved and its typedefs expanded
+pub fn hover(
+    documents: &Documents,
+    uri: &Uri,
+    position: Position,
+    options: &ParseOptions,
+) -> Option<Hover> {
+    let files = parse_all(documents, uri, options)?;
+    let current = &files[0];
+    let offset = current.document.offset(position);
+    let targets = Targets::find(&current.file.definitions, offset);
+
+    let all: Vec<Definition<'_>> = files
+        .iter()
+        .flat_map(|file| file.file.definitions.iter().cloned())
+        .collect();
+    let symbols = SymbolTable::new(&all);
+
+    let found = targets
+        .reference
+        .as_ref()
+        .and_then(|name| Some((name, lookup(&files, name.as_str())?)));
+    let (value, span) = if let Some((name, (file, symbol))) = found {
+        let mut value = format!("```webidl\n{}\n```", declaration(&symbols, file, symbol));
+        if let Some(doc) = doc_comment(&file.file, symbol) {
+            value.push_str("\n\n");
+            value.push_str(&doc);
+        }
+        (value, name.span())
+    } else {
+        let type_ = targets.type_?;
+        let resolved = symbols.expand_lenient(&type_).ok()?;
+        (format!("```webidl\n{resolved}\n```"), type_.span())
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: Some(current.document.range(span)),
+    })
+}
+
+/// Formats the whole document, or returns `None` if it does not parse
+pub fn formatting(
+    document: &Document,
+    options: &FormattingOptions,
+    parse_options: &ParseOptions,
+) -> Option<Vec<TextEdit>> {
+    let source_file = weedle::lossless::parse_with(&document.text, parse_options).ok()?;
+    let indent = if options.insert_spaces {
+        " ".repeat(options.tab_size as usize)
+    } else {
+        "\t".to_owned()
+    };
+    let options = FormatOptions {
+        indent,
+        ..FormatOptions::default()
+    };
+
+    let formatted = format_source_file(&source_file, &options);
+    if formatted == document.text {
+        return Some(vec![]);
+    }
+    let range = document.range(Span::new(0, document.text.len()));
+    Some(vec![TextEdit::new(range, formatted)])
+}
+
+struct ParsedFile<'w> {
+    document: &'w Document,
+    file: SourceFile<'w>,
+}
+
+/// Parses the open documents, starting with the one at `uri`
+fn parse_all<'w>(
+    documents: &'w Documents,
+    uri: &Uri,
+    options: &ParseOptions,
+) -> Option<Vec<ParsedFile<'w>>> {
+    let current = documents.get(uri.as_str())?;
+    let mut others: Vec<_> = documents
+        .values()
+        .filter(|other| other.uri != current.uri)
+        .collect();
+    others.sort_by_key(|other| &other.uri);
+
+    let files = std::iter::once(current)
+        .chain(others)
+        .map(|document| ParsedFile {
+            document,
+            file: weedle::lossless::parse_recovering_with(&document.text, options).0,
+        })
+        .collect();
+    Some(files)
+}
+
+/// Returns the first definition with the name, in the order of the files
+fn lookup<'f, 'w>(
+    files: &'f [ParsedFile<'w>],
+    name: &str,
+) -> Option<(&'f ParsedFile<'w>, Symbol<'f, 'w>)> {
+    files.iter().find_map(|file| {
+        let symbol = SymbolTable::new(&file.file.definitions).get(name)?;
+        Some((file, symbol))
+    })
+}
+
+/// Writes the definition of a symbol as a short WebIDL declaration
+fn declaration<'w>(
+    symbols: &SymbolTable<'_, 'w>,
+    file: &ParsedFile<'w>,
+    symbol: Symbol<'_, 'w>,
+) -> String {
+    let name = symbol.identifier().as_str();
+    let inheritance = |inheritance: &Option<Inheritance<'_>>| match inheritance {
+        Some(inheritance) => format!(" : {}", inheritance.identifier.as_str()),
+        None => String::new(),
+    };
+    match symbol {
+        Symbol::Interface(def) => format!("interface {name}{}", inheritance(&def.inheritance)),
+        Symbol::CallbackInterface(_) => format!("callback interface {name}"),
+        Symbol::Dictionary(def) => format!("dictionary {name}{}", inheritance(&def.inheritance)),
+        Symbol::Enum(_) => format!("enum {name}"),
+        Symbol::Callback(def) => {
+            let signature =
+                &file.document.text[def.return_type.span().start..def.arguments.span().end];
+            format!("callback {name} = {signature}")
+        }
+        Symbol::Typedef(def) => {
+            let type_ = &def.type_.type_;
+            match symbols.expand_lenient(type_) {
+                Ok(resolved) => format!("typedef {resolved} {name}"),
+                // A recursive typedef is shown as written
+                Err(_) => format!(
+                    "typedef {} {name}",
+                    &file.document.text[type_.span().range()]
+                ),
+            }
+        }
+        Symbol::InterfaceMixin(_) => format!("interface mixin {name}"),
+        Symbol::Namespace(_) => format!("namespace {name}"),
+    }
+}
+
+fn doc_comment(file: &SourceFile<'_>, symbol: Symbol<'_, '_>) -> Option<String> {
+    match symbol {
+        Symbol::Interface(def) => file.doc_comment(def),
+        Symbol::CallbackInterface(def) => file.doc_comment(def),
+        Symbol::Dictionary(def) => file.doc_comment(def),
+        Symbol::Enum(def) => file.doc_comment(def),
+        Symbol::Callback(def) => file.doc_comment(def),
+        Symbol::Typedef(def) => file.doc_comment(def),
+        Symbol::InterfaceMixin(def) => file.doc_comment(def),
+        Symbol::Namespace(def) => file.doc_comment(def),
+    }
+}
+
+/// The nodes at an offset that the language features act on
+#[derive(Default)]
+struct Targets<'a> {
+    offset: usize,
+    /// The name of a definition used by a type, an inheritance or an includes statement
+    reference: Option<Identifier<'a>>,
+    /// The innermost type
+    type_: Option<Type<'a>>,
+}
+
+impl<'a> Targets<'a> {
+    fn find(definitions: &[Definition<'a>], offset: usize) -> Self {
+        let mut targets = Targets {
+            offset,
+            ..Targets::default()
+        };
+        for definition in definitions {
+            if contains(definition.span(), offset) {
+                definition.accept(&mut targets);
+            }
+        }
+        targets
+    }
+
+    fn reference(&mut self, identifier: &Identifier<'a>) {
+        if contains(identifier.span(), self.offset) {
+            self.reference = Some(identifier.clone());
+        }
+    }
+
+    fn type_(&mut self, type_: Type<'a>) {
+        let span = type_.span();
+        let inner = self
+            .type_
+            .as_ref()
+            .is_none_or(|outer| span.len() <= outer.span().len());
+        if contains(span, self.offset) && inner {
+            self.type_ = Some(type_);
+        }
+    }
+}
+
+/// Returns whether the span contains the offset, including the end so that a cursor right
+/// after a name is on the name
+fn contains(span: Span, offset: usize) -> bool {
+    !span.is_empty() && span.start <= offset && offset <= span.end
+}
+
+impl<'a> Visit<'a> for Targets<'a> {
+    fn visit_type(&mut self, node: &Type<'a>) {
+        self.type_(node.clone());
+        node.walk(self);
+    }
+
+    fn visit_distinguishable_type(&mut self, node: &DistinguishableType<'a>) {
+        if let DistinguishableType::Identifier(type_) = node {
+            self.reference(&type_.type_);
+        }
+        // Union members are not `Type`s
+        self.type_(Type::Single(SingleType::Distinguishable(node.clone())));
+        node.walk(self);
+    }
+
+    fn visit_const_type(&mut self, node: &ConstType<'a>) {
+        if let ConstType::Identifier(identifier) = node {
+            self.reference(identifier);
+        }
+        node.walk(self);
+    }
+
+    fn visit_inheritance(&mut self, node: &Inheritance<'a>) {
+        self.reference(&node.identifier);
+    }
+
+    fn visit_includes_statement_definition(&mut self, node: &IncludesStatementDefinition<'a>) {
+        self.reference(&node.lhs_identifier);
+        self.reference(&node.rhs_identifier);
+    }
+}
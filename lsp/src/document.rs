@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use lsp_types::{Position, Range, Uri};
+use weedle::span::Span;
+
+/// The open documents, by URI
+///
+/// Keyed by the URI string, as `Uri` caches parts of itself in cells.
+pub type Documents = HashMap<String, Document>;
+
+/// An open text document
+#[derive(Clone, Debug)]
+pub struct Document {
+    pub uri: Uri,
+    pub text: String,
+    line_index: LineIndex,
+}
+
+impl Document {
+    pub fn new(uri: Uri, text: String) -> Self {
+        let line_index = LineIndex::new(&text);
+        Document {
+            uri,
+            text,
+            line_index,
+        }
+    }
+
+    pub fn range(&self, span: Span) -> Range {
+        Range::new(self.position(span.start), self.position(span.end))
+    }
+
+    /// Converts a byte offset to a position, counting characters in UTF-16 code units
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_index.line(offset);
+        let start = self.line_index.starts[line];
+        let character = self.text[start..offset].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    /// Converts a position to a byte offset, clamping it to the line and the text
+    pub fn offset(&self, position: Position) -> usize {
+        let Some(&start) = self.line_index.starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+        let end = self
+            .line_index
+            .starts
+            .get(position.line as usize + 1)
+            .map_or(self.text.len(), |next| next - 1);
+
+        let mut units = 0;
+        for (i, c) in self.text[start..end].char_indices() {
+            if units >= position.character as usize {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        end
+    }
+}
+
+/// The byte offsets at which lines start
+#[derive(Clone, Debug)]
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(text: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { starts }
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset) - 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document(text: &str) -> Document {
+        Document::new("file:///test.webidl".parse().unwrap(), text.to_owned())
+    }
+
+    #[test]
+    fn should_convert_offsets_to_positions() {
+        let document = document("a\n\u{1F600}b\r\nc");
+
+        assert_eq!(document.position(0), Position::new(0, 0));
+        assert_eq!(document.position(2), Position::new(1, 0));
+        assert_eq!(document.position(6), Position::new(1, 2));
+        assert_eq!(document.position(9), Position::new(2, 0));
+        assert_eq!(document.position(100), Position::new(2, 1));
+    }
+
+    #[test]
+    fn should_convert_positions_to_offsets() {
+        let document = document("a\n\u{1F600}b\r\nc");
+
+        assert_eq!(document.offset(Position::new(0, 0)), 0);
+        assert_eq!(document.offset(Position::new(0, 5)), 1);
+        assert_eq!(document.offset(Position::new(1, 2)), 6);
+        assert_eq!(document.offset(Position::new(1, 3)), 7);
+        assert_eq!(document.offset(Position::new(2, 1)), 10);
+        assert_eq!(document.offset(Position::new(7, 0)), 10);
+    }
+}
@@ -0,0 +1,210 @@
+//! A language server for WebIDL, speaking the Language Server Protocol over stdio
+//!
+//! It publishes the syntax and validation errors of the open documents, and answers document
+//! symbol, go-to-definition, hover and formatting requests. Names are resolved across all the
+//! open documents.
+//!
+//! `--dialect <name>` parses the documents as another dialect than Blink: `webidl`, `gecko` or
+//! `uniffi`.
+
+mod analysis;
+mod document;
+
+use std::error::Error;
+use std::process::ExitCode;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationTrait, PublishDiagnostics,
+};
+use lsp_types::request::{
+    DocumentSymbolRequest, Formatting, GotoDefinition, HoverRequest, Request as RequestTrait,
+};
+use lsp_types::{
+    DocumentSymbolResponse, GotoDefinitionResponse, HoverProviderCapability, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    Uri,
+};
+
+use weedle::dialect::{Dialect, ParseOptions};
+
+use crate::document::{Document, Documents};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("weedle-lsp: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let options = parse_args(std::env::args().skip(1))?;
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    Server {
+        connection: &connection,
+        documents: Documents::new(),
+        options,
+    }
+    .run()?;
+
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Reads the parse options from the command line
+fn parse_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<ParseOptions, Box<dyn Error + Send + Sync>> {
+    let mut options = ParseOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dialect" => {
+                let name = args.next().ok_or("`--dialect` needs a dialect name")?;
+                options = name.parse::<Dialect>()?.into();
+            }
+            _ => return Err(format!("unknown option `{arg}`").into()),
+        }
+    }
+    Ok(options)
+}
+
+struct Server<'c> {
+    connection: &'c Connection,
+    documents: Documents,
+    options: ParseOptions,
+}
+
+impl Server<'_> {
+    fn run(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for message in &self.connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.request(request);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => self.notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            DocumentSymbolRequest::METHOD => handle::<DocumentSymbolRequest>(request, |params| {
+                let document = self.documents.get(params.text_document.uri.as_str())?;
+                let symbols = analysis::document_symbols(document, &self.options);
+                Some(DocumentSymbolResponse::Nested(symbols))
+            }),
+            GotoDefinition::METHOD => handle::<GotoDefinition>(request, |params| {
+                let position = params.text_document_position_params;
+                let location = analysis::definition(
+                    &self.documents,
+                    &position.text_document.uri,
+                    position.position,
+                    &self.options,
+                )?;
+                Some(GotoDefinitionResponse::Scalar(location))
+            }),
+            HoverRequest::METHOD => handle::<HoverRequest>(request, |params| {
+                let position = params.text_document_position_params;
+                analysis::hover(
+                    &self.documents,
+                    &position.text_document.uri,
+                    position.position,
+                    &self.options,
+                )
+            }),
+            Formatting::METHOD => handle::<Formatting>(request, |params| {
+                let document = self.documents.get(params.text_document.uri.as_str())?;
+                analysis::formatting(document, &params.options, &self.options)
+            }),
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request `{method}`"),
+            ),
+        }
+    }
+
+    fn notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)?;
+                let document = Document::new(params.text_document.uri, params.text_document.text);
+                self.open(document)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = notification.extract::<lsp_types::DidChangeTextDocumentParams>(
+                    DidChangeTextDocument::METHOD,
+                )?;
+                let uri = params.text_document.uri;
+                // With full synchronization, the last change holds the whole text
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(());
+                };
+                self.open(Document::new(uri, change.text))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = notification.extract::<lsp_types::DidCloseTextDocumentParams>(
+                    DidCloseTextDocument::METHOD,
+                )?;
+                self.documents.remove(params.text_document.uri.as_str());
+                self.publish_diagnostics(params.text_document.uri, vec![])?;
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        let diagnostics = analysis::diagnostics(&self.documents[uri.as_str()], &self.options);
+        self.publish_diagnostics(uri, diagnostics)
+    }
+
+    /// Stores the document, replacing its previous version, and returns its URI
+    fn open(&mut self, document: Document) -> Uri {
+        let uri = document.uri.clone();
+        self.documents.insert(uri.as_str().to_owned(), document);
+        uri
+    }
+
+    fn publish_diagnostics(
+        &self,
+        uri: Uri,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+}
+
+/// Answers a request with the result of `f`, or an error if its parameters are invalid
+fn handle<R: RequestTrait>(request: Request, f: impl FnOnce(R::Params) -> R::Result) -> Response {
+    match serde_json::from_value(request.params) {
+        Ok(params) => Response::new_ok(request.id, f(params)),
+        Err(err) => Response::new_err(request.id, ErrorCode::InvalidParams as i32, err.to_string()),
+    }
+}
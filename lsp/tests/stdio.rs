@@ -0,0 +1,302 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use serde_json::{json, Value};
+
+/// A client speaking JSON-RPC to the server over its standard input and output
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Client {
+    fn start() -> Self {
+        Client::start_with(&[])
+    }
+
+    fn start_with(args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_weedle-lsp"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut client = Client {
+            child,
+            stdin,
+            stdout,
+            next_id: 0,
+        };
+
+        let result = client.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(result["capabilities"]["hoverProvider"], true);
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn send(&mut self, message: Value) {
+        let body = message.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut length = None;
+        loop {
+            let mut line = String::new();
+            self.stdout.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Content-Length: ") {
+                length = Some(value.parse().unwrap());
+            }
+        }
+        let mut body = vec![0; length.expect("missing Content-Length")];
+        self.stdout.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    /// Sends a request and returns its result, skipping the notifications sent meanwhile
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        loop {
+            let message = self.receive();
+            if message["id"] == id {
+                assert_eq!(message["error"], Value::Null, "{method} failed");
+                return message["result"].clone();
+            }
+        }
+    }
+
+    /// Waits for the diagnostics of a document
+    fn diagnostics(&mut self, uri: &str) -> Vec<Value> {
+        loop {
+            let message = self.receive();
+            if message["method"] == "textDocument/publishDiagnostics"
+                && message["params"]["uri"] == uri
+            {
+                return message["params"]["diagnostics"].as_array().unwrap().clone();
+            }
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: &str) -> Vec<Value> {
+        let document = json!({ "uri": uri, "languageId": "webidl", "version": 1, "text": text });
+        self.notify("textDocument/didOpen", json!({ "textDocument": document }));
+        self.diagnostics(uri)
+    }
+
+    fn at(&mut self, method: &str, uri: &str, line: u32, character: u32) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": uri },
+                "position": { "line": line, "character": character },
+            }),
+        )
+    }
+
+    fn shutdown(mut self) {
+        assert_eq!(self.request("shutdown", Value::Null), Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+const DOM: &str = "file:///dom.webidl";
+const HTML: &str = "file:///html.webidl";
+
+const DOM_TEXT: &str = "\
+/// The base of the tree
+interface Node {};
+typedef (Node or DOMString)? NodeOrString;
+";
+
+const HTML_TEXT: &str = "\
+interface Element : Node {
+  attribute NodeOrString child;
+  sequence<unsigned long long> sizes();
+};
+dictionary Options { Element target; };
+";
+
+#[test]
+fn should_publish_diagnostics() {
+    let mut client = Client::start();
+
+    let diagnostics = client.open(HTML, "interface A {\n  attribute long;\n};\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({ "start": { "line": 1, "character": 16 }, "end": { "line": 1, "character": 17 } })
+    );
+    assert!(diagnostics[0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Missing name\n"));
+
+    let change = json!({
+        "textDocument": { "uri": HTML, "version": 2 },
+        "contentChanges": [{ "text": "enum E { \"a\", \"a\" };\n" }],
+    });
+    client.notify("textDocument/didChange", change);
+    let diagnostics = client.diagnostics(HTML);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+
+    client.notify(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": HTML } }),
+    );
+    assert!(client.diagnostics(HTML).is_empty());
+    client.shutdown();
+}
+
+#[test]
+fn should_parse_with_the_dialect() {
+    let text = "Node implements EventTarget;\n";
+
+    let mut client = Client::start();
+    assert_eq!(client.open(DOM, text).len(), 1);
+    client.shutdown();
+
+    let mut client = Client::start_with(&["--dialect", "gecko"]);
+    assert!(client.open(DOM, text).is_empty());
+    client.shutdown();
+}
+
+#[test]
+fn should_list_document_symbols() {
+    let mut client = Client::start();
+    assert!(client.open(HTML, HTML_TEXT).is_empty());
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": HTML } }),
+    );
+    let symbols = symbols.as_array().unwrap();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0]["name"], "Element");
+    assert_eq!(symbols[0]["kind"], 11);
+    assert_eq!(
+        symbols[0]["selectionRange"],
+        json!({ "start": { "line": 0, "character": 10 }, "end": { "line": 0, "character": 17 } })
+    );
+    let children: Vec<_> = symbols[0]["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|child| {
+            (
+                child["name"].as_str().unwrap(),
+                child["kind"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(children, [("child", 7), ("sizes", 6)]);
+    assert_eq!(symbols[1]["name"], "Options");
+    assert_eq!(symbols[1]["children"][0]["name"], "target");
+    client.shutdown();
+}
+
+#[test]
+fn should_go_to_definitions_in_open_documents() {
+    let mut client = Client::start();
+    client.open(DOM, DOM_TEXT);
+    client.open(HTML, HTML_TEXT);
+
+    // The inheritance target
+    let location = client.at("textDocument/definition", HTML, 0, 21);
+    assert_eq!(location["uri"], DOM);
+    assert_eq!(
+        location["range"],
+        json!({ "start": { "line": 1, "character": 10 }, "end": { "line": 1, "character": 14 } })
+    );
+
+    // A type, with the cursor right after its name
+    let location = client.at("textDocument/definition", HTML, 4, 28);
+    assert_eq!(location["uri"], HTML);
+    assert_eq!(
+        location["range"]["start"],
+        json!({ "line": 0, "character": 10 })
+    );
+
+    // Not a name
+    let location = client.at("textDocument/definition", HTML, 2, 5);
+    assert_eq!(location, Value::Null);
+    client.shutdown();
+}
+
+#[test]
+fn should_hover_resolved_types() {
+    let mut client = Client::start();
+    client.open(DOM, DOM_TEXT);
+    client.open(HTML, HTML_TEXT);
+
+    let hover = client.at("textDocument/hover", HTML, 1, 15);
+    assert_eq!(
+        hover["contents"]["value"],
+        "```webidl\ntypedef (Node or DOMString)? NodeOrString\n```"
+    );
+    assert_eq!(
+        hover["range"],
+        json!({ "start": { "line": 1, "character": 12 }, "end": { "line": 1, "character": 24 } })
+    );
+
+    let hover = client.at("textDocument/hover", HTML, 0, 21);
+    assert_eq!(
+        hover["contents"]["value"],
+        "```webidl\ninterface Node\n```\n\nThe base of the tree"
+    );
+
+    let hover = client.at("textDocument/hover", HTML, 2, 3);
+    assert_eq!(
+        hover["contents"]["value"],
+        "```webidl\nsequence<unsigned long long>\n```"
+    );
+    let hover = client.at("textDocument/hover", HTML, 2, 12);
+    assert_eq!(
+        hover["contents"]["value"],
+        "```webidl\nunsigned long long\n```"
+    );
+    client.shutdown();
+}
+
+#[test]
+fn should_format_documents() {
+    let mut client = Client::start();
+    client.open(HTML, "interface   A{attribute long x;};");
+
+    let options = json!({ "tabSize": 4, "insertSpaces": true });
+    let edits = client.request(
+        "textDocument/formatting",
+        json!({ "textDocument": { "uri": HTML }, "options": options }),
+    );
+    assert_eq!(
+        edits,
+        json!([{
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 33 } },
+            "newText": "interface A {\n    attribute long x;\n};\n",
+        }])
+    );
+
+    client.open(DOM, "interface {");
+    let edits = client.request(
+        "textDocument/formatting",
+        json!({ "textDocument": { "uri": DOM }, "options": options }),
+    );
+    assert_eq!(edits, Value::Null);
+    client.shutdown();
+}
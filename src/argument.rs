@@ -2,20 +2,21 @@ use weedle_derive::Weedle;
 
 use crate::attribute::ExtendedAttributeList;
 use crate::common::{Default, Identifier, Punctuated};
+use crate::error::ParseError;
 use crate::tokens::Tokens;
 use crate::types::{AttributedType, Type};
-use crate::{Parse, VerboseResult};
+use crate::{Expected, Parse, VerboseResult};
 
 /// Parses a list of argument. Ex: `double v1, double v2, double v3, optional double alpha`
 pub type ArgumentList<'a> = Punctuated<Argument<'a>, term!(,)>;
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-struct ArgumentName<'a>(&'a str);
+struct ArgumentName<'a>(Identifier<'a>);
 
 impl<'a> Parse<'a> for ArgumentName<'a> {
     fn parse_tokens<'slice>(input: Tokens<'slice, 'a>) -> VerboseResult<Tokens<'slice, 'a>, Self> {
         if let Ok((tokens, result)) = eat!(Identifier)(input) {
-            return Ok((tokens, ArgumentName(result.0)));
+            return Ok((tokens, ArgumentName(result)));
         }
         try_eat_keys!(
             ArgumentName,
@@ -46,13 +47,16 @@ impl<'a> Parse<'a> for ArgumentName<'a> {
             Typedef,
             Unrestricted
         );
-        nom::combinator::fail(input)
+        Err(nom::Err::Error(ParseError::expected(
+            input,
+            Expected::Identifier,
+        )))
     }
 }
 
 impl<'a> From<ArgumentName<'a>> for Identifier<'a> {
     fn from(value: ArgumentName<'a>) -> Self {
-        Self(value.0)
+        value.0
     }
 }
 
@@ -60,6 +64,7 @@ impl<'a> From<ArgumentName<'a>> for Identifier<'a> {
 ///
 /// Note: `= default` is only allowed if `optional` is present
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SingleArgument<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub optional: Option<term!(optional)>,
@@ -72,6 +77,7 @@ pub struct SingleArgument<'a> {
 
 /// Parses `[attributes]? type... identifier`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VariadicArgument<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub type_: Type<'a>,
@@ -81,6 +87,7 @@ pub struct VariadicArgument<'a> {
 
 /// Parses an argument. Ex: `double v1|double... v1s`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub enum Argument<'a> {
     Single(SingleArgument<'a>),
@@ -126,7 +133,7 @@ mod test {
         identifier.0 == "a";
         default == Some(Default {
             assign: term!(=),
-            value: DefaultValue::Integer(IntegerLit::Dec(DecLit("5"))),
+            value: DefaultValue::Integer(IntegerLit::Dec(DecLit::new("5"))),
         });
     });
 
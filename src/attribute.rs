@@ -3,10 +3,38 @@ use weedle_derive::Weedle;
 use crate::argument::ArgumentList;
 use crate::common::{Bracketed, Identifier, Parenthesized, ParenthesizedNonEmpty, Punctuated};
 use crate::literal::{FloatLit, IntegerLit, StringLit};
+use crate::registry::KnownAttribute;
 
 /// Parses a list of attributes. Ex: `[ attribute1, attribute2 ]`
 pub type ExtendedAttributeList<'a> = Bracketed<Punctuated<ExtendedAttribute<'a>, term!(,)>>;
 
+impl<'a> ExtendedAttributeList<'a> {
+    /// Returns the first attribute of the name
+    pub fn find(&self, name: &str) -> Option<&ExtendedAttribute<'a>> {
+        self.body
+            .list
+            .iter()
+            .find(|attribute| attribute.identifier().0 == name)
+    }
+
+    /// Returns the value of the first attribute named [`KnownAttribute::NAME`], if it is written
+    /// in a form `T` accepts
+    ///
+    /// ```
+    /// use weedle::registry::Exposed;
+    /// use weedle::Definition;
+    ///
+    /// let definitions = weedle::parse("[Exposed=(Window,Worker)] interface Blob {};").unwrap();
+    /// let Definition::Interface(interface) = &definitions[0] else { unreachable!() };
+    ///
+    /// let exposed = interface.attributes.as_ref().and_then(|list| list.get::<Exposed>());
+    /// assert_eq!(exposed, Some(Exposed::Globals(vec!["Window", "Worker"])));
+    /// ```
+    pub fn get<'b, T: KnownAttribute<'b>>(&'b self) -> Option<T> {
+        T::from_attribute(self.find(T::NAME)?)
+    }
+}
+
 /// Matches comma separated identifier list
 pub type IdentifierList<'a> = Punctuated<Identifier<'a>, term!(,)>;
 pub type StringList<'a> = Punctuated<StringLit<'a>, term!(,)>;
@@ -17,6 +45,7 @@ pub type IntegerList<'a> = Punctuated<IntegerLit<'a>, term!(,)>;
 ///
 /// (( )) means ( ) chars
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeArgList<'a> {
     pub identifier: Identifier<'a>,
     pub args: Parenthesized<ArgumentList<'a>>,
@@ -26,6 +55,7 @@ pub struct ExtendedAttributeArgList<'a> {
 ///
 /// (( )) means ( ) chars
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeNamedArgList<'a> {
     pub lhs_identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -37,6 +67,7 @@ pub struct ExtendedAttributeNamedArgList<'a> {
 ///
 /// (( )) means ( ) chars
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeIdentList<'a> {
     pub identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -45,7 +76,8 @@ pub struct ExtendedAttributeIdentList<'a> {
 }
 
 /// Parses an attribute with an identifier. Ex: `PutForwards=name`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeIdent<'a> {
     pub lhs_identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -53,7 +85,8 @@ pub struct ExtendedAttributeIdent<'a> {
 }
 
 /// Parses an attribute with a wildcard. Ex: `Exposed=*`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeWildcard<'a> {
     pub lhs_identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -61,12 +94,13 @@ pub struct ExtendedAttributeWildcard<'a> {
 }
 
 // Things that are not used by the standard Web IDL, but still allowed
-// and used by others e.g. Blink and JSDOM
+// and used by others e.g. Blink and JSDOM, see `ParseOptions::extended_attribute_values`
 // https://github.com/w3c/webidl2.js/issues/256
 // https://github.com/w3c/webidl2.js/issues/455
 
 /// Parses an attribute with a string. E: `ReflectOnly="on"`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeString<'a> {
     pub lhs_identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -74,13 +108,15 @@ pub struct ExtendedAttributeString<'a> {
 }
 
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeStringList<'a> {
     pub identifier: Identifier<'a>,
     pub assign: term!(=),
     #[weedle(from = "ParenthesizedNonEmpty<StringList<'a>>")]
     pub list: Parenthesized<StringList<'a>>,
 }
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeFloat<'a> {
     pub lhs_identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -88,6 +124,7 @@ pub struct ExtendedAttributeFloat<'a> {
 }
 
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeFloatList<'a> {
     pub identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -95,7 +132,8 @@ pub struct ExtendedAttributeFloatList<'a> {
     pub list: Parenthesized<FloatList<'a>>,
 }
 
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeInteger<'a> {
     pub lhs_identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -103,6 +141,7 @@ pub struct ExtendedAttributeInteger<'a> {
 }
 
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeIntegerList<'a> {
     pub identifier: Identifier<'a>,
     pub assign: term!(=),
@@ -111,11 +150,13 @@ pub struct ExtendedAttributeIntegerList<'a> {
 }
 
 /// Parses a plain attribute. Ex: `Replaceable`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedAttributeNoArgs<'a>(pub Identifier<'a>);
 
 /// Parses on of the forms of attribute
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub enum ExtendedAttribute<'a> {
     ArgList(ExtendedAttributeArgList<'a>),
@@ -123,15 +164,41 @@ pub enum ExtendedAttribute<'a> {
     IdentList(ExtendedAttributeIdentList<'a>),
     Ident(ExtendedAttributeIdent<'a>),
     Wildcard(ExtendedAttributeWildcard<'a>),
+    #[weedle(extension = "extended_attribute_values")]
     String(ExtendedAttributeString<'a>),
+    #[weedle(extension = "extended_attribute_values")]
     StringList(ExtendedAttributeStringList<'a>),
+    #[weedle(extension = "extended_attribute_values")]
     Float(ExtendedAttributeFloat<'a>),
+    #[weedle(extension = "extended_attribute_values")]
     FloatList(ExtendedAttributeFloatList<'a>),
+    #[weedle(extension = "extended_attribute_values")]
     Integer(ExtendedAttributeInteger<'a>),
+    #[weedle(extension = "extended_attribute_values")]
     IntegerList(ExtendedAttributeIntegerList<'a>),
     NoArgs(ExtendedAttributeNoArgs<'a>),
 }
 
+impl<'a> ExtendedAttribute<'a> {
+    /// Returns the name of the attribute, on the left of `=` if any
+    pub fn identifier(&self) -> &Identifier<'a> {
+        match self {
+            ExtendedAttribute::ArgList(attribute) => &attribute.identifier,
+            ExtendedAttribute::NamedArgList(attribute) => &attribute.lhs_identifier,
+            ExtendedAttribute::IdentList(attribute) => &attribute.identifier,
+            ExtendedAttribute::Ident(attribute) => &attribute.lhs_identifier,
+            ExtendedAttribute::Wildcard(attribute) => &attribute.lhs_identifier,
+            ExtendedAttribute::String(attribute) => &attribute.lhs_identifier,
+            ExtendedAttribute::StringList(attribute) => &attribute.identifier,
+            ExtendedAttribute::Float(attribute) => &attribute.lhs_identifier,
+            ExtendedAttribute::FloatList(attribute) => &attribute.identifier,
+            ExtendedAttribute::Integer(attribute) => &attribute.lhs_identifier,
+            ExtendedAttribute::IntegerList(attribute) => &attribute.identifier,
+            ExtendedAttribute::NoArgs(attribute) => &attribute.0,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -139,7 +206,7 @@ mod test {
 
     test!(should_parse_attribute_no_args { "Replaceable" =>
         "";
-        ExtendedAttributeNoArgs => ExtendedAttributeNoArgs(Identifier("Replaceable"))
+        ExtendedAttributeNoArgs => ExtendedAttributeNoArgs(Identifier::new("Replaceable"))
     });
 
     test!(should_parse_attribute_arg_list { "Constructor(double x, double y)" =>
@@ -153,7 +220,7 @@ mod test {
         "";
         ExtendedAttributeIdent;
         lhs_identifier.0 == "PutForwards";
-        rhs == Identifier("name");
+        rhs == Identifier::new("name");
     });
 
     test!(should_parse_ident_list { "Exposed=(Window,Worker)" =>
@@ -182,7 +249,7 @@ mod test {
         "";
         ExtendedAttributeFloat;
         lhs_identifier.0 == "FloatAttr";
-        rhs == FloatLit::Value(FloatValueLit("3.14"));
+        rhs == FloatLit::Value(FloatValueLit::new("3.14"));
     });
 
     test!(should_parse_extattr_list { "[IntAttr=0, FloatAttr=3.14]" =>
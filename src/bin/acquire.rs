@@ -5,19 +5,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use nom::error::{convert_error, VerboseError};
-
-fn convert_err(content: &str, err: nom::Err<VerboseError<&str>>) -> String {
-    use nom::Err::*;
-    match err {
-        Error(e) | Failure(e) => convert_error(content, e),
-        Incomplete(_) => "Unexpected incomplete error".to_owned(),
-    }
-}
+use weedle::diagnostic::Diagnostic;
 
 fn iterate<F>(path: &str, func: F) -> std::io::Result<()>
 where
-    F: Fn(&DirEntry, Result<Vec<weedle::Definition>, String>) -> std::io::Result<()>,
+    F: Fn(&DirEntry, Result<Vec<weedle::Definition>, weedle::Error>) -> std::io::Result<()>,
 {
     let read_dir = std::fs::read_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join(path)).unwrap();
 
@@ -25,7 +17,7 @@ where
         let entry = entry_result?;
 
         let content = std::fs::read_to_string(entry.path()).unwrap();
-        let result = weedle::parse(&content).map_err(|err| convert_err(&content, err));
+        let result = weedle::parse(&content);
 
         func(&entry, result)?;
     }
@@ -62,8 +54,12 @@ fn main() -> std::io::Result<()> {
             Err(err) => err,
         };
 
+        let content = std::fs::read_to_string(entry.path())?;
+        let file_name = entry.file_name();
+        let rendered = Diagnostic::from(&err).render(&file_name.to_string_lossy(), &content);
+
         let out_file_path = get_out_path(&out_dir_path, entry);
-        std::fs::write(out_file_path, err)?;
+        std::fs::write(out_file_path, rendered)?;
         Ok(())
     })?;
 
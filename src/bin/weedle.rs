@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Read, Write};
+use std::process::ExitCode;
+
+use weedle::diagnostic::Diagnostic;
+use weedle::dialect::{Dialect, ParseOptions, UnknownDialect};
+use weedle::fmt::{format_source_file, FormatOptions};
+use weedle::interface::{CallbackInterfaceMember, InterfaceMember};
+use weedle::legacy::ExceptionMember;
+use weedle::mixin::MixinMember;
+use weedle::namespace::NamespaceMember;
+use weedle::Definition;
+
+const USAGE: &str = "\
+Usage: weedle <command> [options] <file>...
+
+Commands:
+  check              Parse and validate files, reporting every error
+  fmt [--check]      Format files in place, or list the files that are not formatted
+  dump [--json]      Print the syntax tree of a file
+  stats              Count the definitions and members of files
+  highlight [--html] Print files with ANSI colours, or as HTML
+
+Options:
+  --dialect <name>   Accept the extensions of a dialect when parsing: webidl, blink (the
+                     default), gecko or uniffi
+
+A file named `-` is read from the standard input. `fmt -` writes to the standard output.
+";
+
+/// An error that stops the command, as opposed to diagnostics about the input
+struct Fatal(String);
+
+impl From<std::io::Error> for Fatal {
+    fn from(err: std::io::Error) -> Self {
+        Fatal(err.to_string())
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(Fatal(message)) => {
+            eprintln!("weedle: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs a command, returning whether the input is free of errors
+fn run(args: &[String]) -> Result<bool, Fatal> {
+    let Some((command, rest)) = args.split_first() else {
+        eprint!("{USAGE}");
+        return Err(Fatal("missing command".into()));
+    };
+    let mut flags = vec![];
+    let mut files = vec![];
+    let mut options = ParseOptions::default();
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        if arg == "--dialect" {
+            let name = rest
+                .next()
+                .ok_or_else(|| Fatal("`--dialect` needs a dialect name".into()))?;
+            let dialect: Dialect = name
+                .parse()
+                .map_err(|err: UnknownDialect| Fatal(err.to_string()))?;
+            options = dialect.into();
+        }
+        if arg.starts_with("--") {
+            flags.push(arg.as_str());
+        } else {
+            files.push(arg.as_str());
+        }
+    }
+
+    let allowed: &[&str] = match command.as_str() {
+        "check" | "stats" => &["--dialect"],
+        "fmt" => &["--check", "--dialect"],
+        "dump" => &["--json", "--dialect"],
+        "highlight" => &["--html"],
+        "help" | "--help" | "-h" => {
+            print!("{USAGE}");
+            return Ok(true);
+        }
+        _ => return Err(Fatal(format!("unknown command `{command}`"))),
+    };
+    if let Some(flag) = flags.iter().find(|flag| !allowed.contains(flag)) {
+        return Err(Fatal(format!("unknown option `{flag}` for `{command}`")));
+    }
+    if files.is_empty() {
+        return Err(Fatal(format!("`{command}` needs at least one file")));
+    }
+
+    match command.as_str() {
+        "check" => check(&files, &options),
+        "fmt" => fmt(&files, flags.contains(&"--check"), &options),
+        "dump" => dump(&files, flags.contains(&"--json"), &options),
+        "stats" => stats(&files, &options),
+        "highlight" => highlight(&files, flags.contains(&"--html")),
+        _ => unreachable!(),
+    }
+}
+
+fn read(file: &str) -> Result<String, Fatal> {
+    if file == "-" {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        return Ok(content);
+    }
+    std::fs::read_to_string(file).map_err(|err| Fatal(format!("cannot read {file}: {err}")))
+}
+
+fn report(diagnostics: &[Diagnostic], file: &str, content: &str) {
+    let name = if file == "-" { "<stdin>" } else { file };
+    let ansi = std::io::stderr().is_terminal();
+    for diagnostic in diagnostics {
+        let rendered = if ansi {
+            diagnostic.render_ansi(name, content)
+        } else {
+            diagnostic.render(name, content)
+        };
+        eprintln!("{rendered}");
+    }
+}
+
+/// Parses a file, reporting the syntax errors
+fn parse<'a>(
+    file: &str,
+    content: &'a str,
+    options: &ParseOptions,
+) -> Option<weedle::Definitions<'a>> {
+    match weedle::parse_with(content, options) {
+        Ok(definitions) => Some(definitions),
+        Err(_) => {
+            let (_, errors) = weedle::parse_recovering_with(content, options);
+            let diagnostics: Vec<_> = errors.iter().map(Diagnostic::from).collect();
+            report(&diagnostics, file, content);
+            None
+        }
+    }
+}
+
+fn check(files: &[&str], options: &ParseOptions) -> Result<bool, Fatal> {
+    let mut ok = true;
+    for file in files {
+        let content = read(file)?;
+        let Some(definitions) = parse(file, &content, options) else {
+            ok = false;
+            continue;
+        };
+        let diagnostics = weedle::validate::validate(&definitions);
+        report(&diagnostics, file, &content);
+        ok &= diagnostics.is_empty();
+    }
+    Ok(ok)
+}
+
+fn fmt(files: &[&str], check: bool, parse_options: &ParseOptions) -> Result<bool, Fatal> {
+    let options = FormatOptions::default();
+    let mut ok = true;
+    for file in files {
+        let content = read(file)?;
+        let source_file = match weedle::lossless::parse_with(&content, parse_options) {
+            Ok(source_file) => source_file,
+            Err(err) => {
+                report(&[Diagnostic::from(&err)], file, &content);
+                ok = false;
+                continue;
+            }
+        };
+        let formatted = format_source_file(&source_file, &options);
+        if *file == "-" && !check {
+            std::io::stdout().write_all(formatted.as_bytes())?;
+        } else if formatted != content {
+            if check {
+                println!("{file}");
+                ok = false;
+            } else {
+                std::fs::write(file, formatted)
+                    .map_err(|err| Fatal(format!("cannot write {file}: {err}")))?;
+            }
+        }
+    }
+    Ok(ok)
+}
+
+fn dump(files: &[&str], json: bool, options: &ParseOptions) -> Result<bool, Fatal> {
+    let mut ok = true;
+    for file in files {
+        let content = read(file)?;
+        let Some(definitions) = parse(file, &content, options) else {
+            ok = false;
+            continue;
+        };
+        if json {
+            println!("{}", to_json(&definitions)?);
+        } else {
+            println!("{definitions:#?}");
+        }
+    }
+    Ok(ok)
+}
+
+fn to_json(definitions: &weedle::Definitions<'_>) -> Result<String, Fatal> {
+    serde_json::to_string_pretty(definitions).map_err(|err| Fatal(err.to_string()))
+}
+
+/// Prints the files highlighted, even if they have syntax errors
+fn highlight(files: &[&str], html: bool) -> Result<bool, Fatal> {
+    let mut stdout = std::io::stdout().lock();
+    for file in files {
+        let content = read(file)?;
+        let highlighted = if html {
+            weedle::highlight::to_html(&content)
+        } else {
+            weedle::highlight::to_ansi(&content)
+        };
+        stdout.write_all(highlighted.as_bytes())?;
+    }
+    Ok(true)
+}
+
+fn stats(files: &[&str], options: &ParseOptions) -> Result<bool, Fatal> {
+    let mut ok = true;
+    let mut definitions = BTreeMap::new();
+    let mut members = BTreeMap::new();
+    for file in files {
+        let content = read(file)?;
+        let Some(parsed) = parse(file, &content, options) else {
+            ok = false;
+            continue;
+        };
+        for definition in &parsed {
+            *definitions.entry(definition_kind(definition)).or_insert(0) += 1;
+            for member in definition_members(definition) {
+                *members.entry(member).or_insert(0) += 1;
+            }
+        }
+    }
+
+    for (title, counts) in [("Definitions", definitions), ("Members", members)] {
+        println!("{title}");
+        for (kind, count) in &counts {
+            println!("  {kind:<24}{count:>6}");
+        }
+        println!("  {:<24}{:>6}", "total", counts.values().sum::<usize>());
+    }
+    Ok(ok)
+}
+
+fn definition_kind(definition: &Definition<'_>) -> &'static str {
+    match definition {
+        Definition::CallbackInterface(_) => "callback interface",
+        Definition::Callback(_) => "callback",
+        Definition::InterfaceMixin(_) => "interface mixin",
+        Definition::Interface(_) => "interface",
+        Definition::Namespace(_) => "namespace",
+        Definition::Dictionary(_) => "dictionary",
+        Definition::PartialInterfaceMixin(_) => "partial interface mixin",
+        Definition::PartialInterface(_) => "partial interface",
+        Definition::PartialDictionary(_) => "partial dictionary",
+        Definition::PartialNamespace(_) => "partial namespace",
+        Definition::Enum(_) => "enum",
+        Definition::Typedef(_) => "typedef",
+        Definition::IncludesStatement(_) => "includes",
+        Definition::Implements(_) => "implements",
+        Definition::Exception(_) => "exception",
+        Definition::Invalid(_) => "invalid",
+    }
+}
+
+fn definition_members(definition: &Definition<'_>) -> Vec<&'static str> {
+    match definition {
+        Definition::Interface(def) => def.members.body.iter().map(interface_member_kind).collect(),
+        Definition::PartialInterface(def) => {
+            def.members.body.iter().map(interface_member_kind).collect()
+        }
+        Definition::InterfaceMixin(def) => def.members.body.iter().map(mixin_member_kind).collect(),
+        Definition::PartialInterfaceMixin(def) => {
+            def.members.body.iter().map(mixin_member_kind).collect()
+        }
+        Definition::Namespace(def) => def.members.body.iter().map(namespace_member_kind).collect(),
+        Definition::PartialNamespace(def) => {
+            def.members.body.iter().map(namespace_member_kind).collect()
+        }
+        Definition::CallbackInterface(def) => def
+            .members
+            .body
+            .iter()
+            .map(|member| match member {
+                CallbackInterfaceMember::Const(_) => "const",
+                CallbackInterfaceMember::Operation(_) => "operation",
+                CallbackInterfaceMember::Invalid(_) => "invalid",
+            })
+            .collect(),
+        Definition::Dictionary(def) => vec!["field"; def.members.body.len()],
+        Definition::PartialDictionary(def) => vec!["field"; def.members.body.len()],
+        Definition::Enum(def) => vec!["enum value"; def.values.body.list.len()],
+        Definition::Exception(def) => def
+            .members
+            .body
+            .iter()
+            .map(|member| match member {
+                ExceptionMember::Const(_) => "const",
+                ExceptionMember::Field(_) => "field",
+            })
+            .collect(),
+        Definition::Callback(_)
+        | Definition::Typedef(_)
+        | Definition::IncludesStatement(_)
+        | Definition::Implements(_)
+        | Definition::Invalid(_) => vec![],
+    }
+}
+
+fn interface_member_kind(member: &InterfaceMember<'_>) -> &'static str {
+    match member {
+        InterfaceMember::Const(_) => "const",
+        InterfaceMember::Attribute(_) => "attribute",
+        InterfaceMember::Constructor(_) => "constructor",
+        InterfaceMember::Operation(_) => "operation",
+        InterfaceMember::Iterable(_) => "iterable",
+        InterfaceMember::AsyncIterable(_) => "async iterable",
+        InterfaceMember::Maplike(_) => "maplike",
+        InterfaceMember::Setlike(_) => "setlike",
+        InterfaceMember::Stringifier(_) => "stringifier",
+        InterfaceMember::LegacyIterable(_) => "legacyiterable",
+        InterfaceMember::Invalid(_) => "invalid",
+    }
+}
+
+fn mixin_member_kind(member: &MixinMember<'_>) -> &'static str {
+    match member {
+        MixinMember::Const(_) => "const",
+        MixinMember::Operation(_) => "operation",
+        MixinMember::Attribute(_) => "attribute",
+        MixinMember::Stringifier(_) => "stringifier",
+        MixinMember::Invalid(_) => "invalid",
+    }
+}
+
+fn namespace_member_kind(member: &NamespaceMember<'_>) -> &'static str {
+    match member {
+        NamespaceMember::Operation(_) => "operation",
+        NamespaceMember::Attribute(_) => "attribute",
+        NamespaceMember::Const(_) => "const",
+        NamespaceMember::Invalid(_) => "invalid",
+    }
+}
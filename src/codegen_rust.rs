@@ -0,0 +1,944 @@
+//! Generates Rust bindings from WebIDL definitions.
+//!
+//! [`generate`] writes the Rust source of the definitions of a resolved [`Model`], so that
+//! partial definitions and mixins are merged into the interfaces using them:
+//!
+//! - dictionaries become structs, with `Option` fields for the members that are not required
+//!   and the members of inherited dictionaries first
+//! - enumerations become enums, converted from and to their strings by `FromStr`, `Display` and
+//!   `as_str`
+//! - interfaces and callback interfaces become traits, with methods for the operations,
+//!   getters and setters for the attributes and `new` functions for the constructors, and
+//!   their constants are associated with the trait objects
+//! - callback functions become boxed `Fn` types, and typedefs type aliases
+//!
+//! Types map to Rust types as follows:
+//!
+//! | WebIDL                                      | Rust                                 |
+//! |---------------------------------------------|--------------------------------------|
+//! | `T?`                                        | `Option<T>`                          |
+//! | `byte`, `short`, `long`, `long long`        | `i8`, `i16`, `i32`, `i64`            |
+//! | `octet` and the `unsigned` integer types    | `u8`, `u16`, `u32`, `u64`            |
+//! | `float`, `double`                           | `f32`, `f64`                         |
+//! | `bigint`                                    | `i128`                               |
+//! | `boolean`                                   | `bool`                               |
+//! | `DOMString`, `USVString`                    | `String`                             |
+//! | `ByteString`, `ArrayBuffer`, `DataView`     | `Vec<u8>`                            |
+//! | typed arrays, e.g. `Float32Array`           | vectors, e.g. `Vec<f32>`             |
+//! | `sequence<T>`, `FrozenArray<T>`, ...        | `Vec<T>`                             |
+//! | `record<K, V>`                              | `HashMap<K, V>`                      |
+//! | `undefined`                                 | `()`                                 |
+//! | interfaces and callback interfaces          | `Rc<dyn Interface>`                  |
+//! | unions, e.g. `(Node or DOMString)`          | generated enums, e.g. `NodeOrDOMString` |
+//! | `any`, `object`, `symbol`, `Promise<T>`     | `Any`, `Object`, `Symbol`, `Promise<T>` |
+//!
+//! The last types have no Rust counterpart, so the generated code expects them to be in scope.
+//! Names are converted to the Rust conventions, and escaped when they are Rust keywords.
+//! Overloaded operations get the names of their arguments appended after the first one, and
+//! enumeration values mapping to the variant of an earlier value get a number, e.g. `Open2`
+//! for `"Open"` after `"open"`.
+//! Namespaces, iterable, maplike and setlike declarations are not generated.
+//!
+//! ### Example
+//!
+//! ```
+//! let definitions = weedle::parse("
+//!     enum Mode { \"open\", \"closed\" };
+//!     dictionary Init { required Mode mode; boolean? delegatesFocus; };
+//! ").unwrap();
+//! let (model, _) = weedle::resolve::resolve(&[definitions]);
+//!
+//! let source = weedle::codegen_rust::generate(&model);
+//! assert!(source.contains("pub struct Init {\n    pub mode: Mode,\n    pub delegates_focus: Option<Option<bool>>,\n}\n"));
+//! assert!(source.contains("            \"closed\" => Ok(Mode::Closed),\n"));
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
+use crate::argument::{Argument, ArgumentList};
+use crate::dictionary::DictionaryMember;
+use crate::interface::{
+    AsyncIterableInterfaceMember, CallbackInterfaceMember, InterfaceMember, IterableInterfaceMember,
+};
+use crate::literal::{ConstValue, FloatLit, IntegerLit};
+use crate::lossless::Terminals;
+use crate::members::{ConstMember, Modifier, StringifierOrInheritOrStatic};
+use crate::resolve::{Dictionary, Model};
+use crate::types::{
+    ConstType, DistinguishableType, FloatingPointType, IntegerType, RecordKeyType, SingleType,
+    Type, UnionMemberType, UnionType,
+};
+use crate::{CallbackDefinition, CallbackInterfaceDefinition, EnumDefinition, InterfaceDefinition};
+
+/// Returns the Rust source of the definitions of the model
+pub fn generate(model: &Model<'_>) -> String {
+    let mut generator = Generator {
+        model,
+        unions: BTreeMap::new(),
+        items: vec![],
+    };
+    for (name, typedef) in &model.typedefs {
+        let type_ = generator.type_(&typedef.type_.type_);
+        generator
+            .items
+            .push(format!("pub type {} = {type_};\n", type_name(name)));
+    }
+    for enum_ in model.enums.values() {
+        generator.enum_(enum_);
+    }
+    for dictionary in model.dictionaries.values() {
+        generator.dictionary(dictionary);
+    }
+    for callback in model.callbacks.values() {
+        generator.callback(callback);
+    }
+    for callback_interface in model.callback_interfaces.values() {
+        generator.callback_interface(callback_interface);
+    }
+    for interface in model.interfaces.values() {
+        generator.interface(&interface.definition);
+    }
+    let unions = std::mem::take(&mut generator.unions);
+    for (name, variants) in unions {
+        let mut item = format!("pub enum {name} {{\n");
+        for (variant, type_) in variants {
+            let _ = writeln!(item, "    {variant}({type_}),");
+        }
+        item.push_str("}\n");
+        generator.items.push(item);
+    }
+    generator.items.join("\n")
+}
+
+struct Generator<'m, 'a> {
+    model: &'m Model<'a>,
+    /// The enums generated for the union types, with their variants and the types they hold
+    unions: BTreeMap<String, Vec<(String, String)>>,
+    items: Vec<String>,
+}
+
+impl Generator<'_, '_> {
+    fn enum_(&mut self, enum_: &EnumDefinition<'_>) {
+        let name = type_name(&enum_.identifier.0);
+        let values = &enum_.values.body.list;
+        // Values differing only in case or punctuation get the same name, so the names after
+        // the first are numbered, skipping the names of the other values
+        let names: Vec<_> = values.iter().map(|value| variant_name(&value.0)).collect();
+        let mut taken: HashSet<_> = names.iter().cloned().collect();
+        let mut seen = HashSet::new();
+        let values: Vec<_> = names
+            .into_iter()
+            .zip(values)
+            .map(|(base, value)| {
+                if seen.insert(base.clone()) {
+                    return (base, &value.0);
+                }
+                let mut suffix = 2;
+                let mut variant = base.clone();
+                while !taken.insert(variant.clone()) {
+                    variant = format!("{}{suffix}", base.trim_start_matches("r#"));
+                    suffix += 1;
+                }
+                (variant, &value.0)
+            })
+            .collect();
+
+        let mut item = String::from("#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]\n");
+        let _ = writeln!(item, "pub enum {name} {{");
+        for (variant, _) in &values {
+            let _ = writeln!(item, "    {variant},");
+        }
+        let _ = write!(
+            item,
+            "}}\n\nimpl {name} {{\n    pub fn as_str(&self) -> &'static str {{\n        match self {{\n"
+        );
+        for (variant, value) in &values {
+            let _ = writeln!(item, "            {name}::{variant} => {value:?},");
+        }
+        let _ = write!(
+            item,
+            "        }}\n    }}\n}}\n\n\
+             impl std::str::FromStr for {name} {{\n    type Err = String;\n\n    \
+             fn from_str(s: &str) -> Result<Self, Self::Err> {{\n        match s {{\n"
+        );
+        for (variant, value) in &values {
+            let _ = writeln!(item, "            {value:?} => Ok({name}::{variant}),");
+        }
+        let _ = write!(
+            item,
+            "            _ => Err(s.to_owned()),\n        }}\n    }}\n}}\n\n\
+             impl std::fmt::Display for {name} {{\n    \
+             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n        \
+             f.write_str(self.as_str())\n    }}\n}}\n"
+        );
+        self.items.push(item);
+    }
+
+    fn dictionary(&mut self, dictionary: &Dictionary<'_>) {
+        let mut fields = String::new();
+        self.dictionary_fields(dictionary, &mut fields, &mut HashSet::new());
+        let name = type_name(&dictionary.definition.identifier.0);
+        self.items
+            .push(format!("pub struct {name} {{\n{fields}}}\n"));
+    }
+
+    /// Writes the fields of the dictionary, after the ones of the dictionaries it inherits
+    fn dictionary_fields(
+        &mut self,
+        dictionary: &Dictionary<'_>,
+        fields: &mut String,
+        visited: &mut HashSet<String>,
+    ) {
+        let definition = &dictionary.definition;
+        if !visited.insert(definition.identifier.0.to_string()) {
+            return;
+        }
+        if let Some(inheritance) = &definition.inheritance {
+            let parent = &inheritance.identifier.0;
+            match self.model.dictionaries.get(parent.as_ref()) {
+                Some(parent) => self.dictionary_fields(parent, fields, visited),
+                None => {
+                    let _ = writeln!(
+                        fields,
+                        "    pub {}: {},",
+                        member_name(parent),
+                        type_name(parent)
+                    );
+                }
+            }
+        }
+        for member in &definition.members.body {
+            let DictionaryMember::Field(member) = member else {
+                continue;
+            };
+            let mut type_ = self.type_(&member.type_);
+            if member.required.is_none() {
+                type_ = format!("Option<{type_}>");
+            }
+            let _ = writeln!(
+                fields,
+                "    pub {}: {type_},",
+                member_name(&member.identifier.0)
+            );
+        }
+    }
+
+    fn callback(&mut self, callback: &CallbackDefinition<'_>) {
+        let arguments: Vec<_> = callback
+            .arguments
+            .body
+            .list
+            .iter()
+            .map(|argument| self.argument(argument).1)
+            .collect();
+        let return_type = self.return_type(&callback.return_type);
+        self.items.push(format!(
+            "pub type {} = Box<dyn Fn({}){return_type}>;\n",
+            type_name(&callback.identifier.0),
+            arguments.join(", "),
+        ));
+    }
+
+    fn callback_interface(&mut self, callback_interface: &CallbackInterfaceDefinition<'_>) {
+        let mut body = String::new();
+        let mut consts = String::new();
+        let mut methods = HashSet::new();
+        for member in &callback_interface.members.body {
+            match member {
+                CallbackInterfaceMember::Const(member) => self.const_(member, &mut consts),
+                CallbackInterfaceMember::Operation(operation) => {
+                    let name = operation
+                        .identifier
+                        .as_ref()
+                        .map_or("call".into(), |identifier| member_name(&identifier.0));
+                    let signature = self.method(
+                        &name,
+                        Receiver::Ref,
+                        &operation.args.body,
+                        Some(&operation.return_type),
+                        &mut methods,
+                    );
+                    let _ = writeln!(body, "    {signature};");
+                }
+                CallbackInterfaceMember::Invalid(_) => {}
+            }
+        }
+        let name = type_name(&callback_interface.identifier.0);
+        self.items.push(trait_item(&name, "", &body, &consts));
+    }
+
+    fn interface(&mut self, interface: &InterfaceDefinition<'_>) {
+        let mut body = String::new();
+        let mut consts = String::new();
+        let mut methods = HashSet::new();
+        for member in &interface.members.body {
+            match member {
+                InterfaceMember::Const(member) => self.const_(member, &mut consts),
+                InterfaceMember::Attribute(attribute) => {
+                    let is_static = matches!(
+                        attribute.modifier,
+                        Some(StringifierOrInheritOrStatic::Static(_))
+                    );
+                    let (receiver, bound) = match is_static {
+                        true => ("", " where Self: Sized"),
+                        false => ("&self", ""),
+                    };
+                    let name = member_name(&attribute.identifier.0);
+                    let type_ = self.type_(&attribute.type_.type_);
+                    methods.insert(name.clone());
+                    let _ = writeln!(body, "    fn {name}({receiver}) -> {type_}{bound};");
+                    if attribute.readonly.is_none() {
+                        let setter = format!("set_{}", name.trim_start_matches("r#"));
+                        let separator = if is_static { "" } else { ", " };
+                        let _ = writeln!(
+                            body,
+                            "    fn {setter}({receiver}{separator}value: {type_}){bound};"
+                        );
+                        methods.insert(setter);
+                    }
+                }
+                InterfaceMember::Constructor(constructor) => {
+                    let signature = self.method(
+                        "new",
+                        Receiver::Constructor,
+                        &constructor.args.body,
+                        None,
+                        &mut methods,
+                    );
+                    let _ = writeln!(body, "    {signature};");
+                }
+                InterfaceMember::Operation(operation) => {
+                    let name = match (&operation.identifier, &operation.modifier) {
+                        (Some(identifier), _) => member_name(&identifier.0),
+                        (None, Some(Modifier::Getter(_))) => "get".into(),
+                        (None, Some(Modifier::Setter(_))) => "set".into(),
+                        (None, Some(Modifier::Deleter(_))) => "delete".into(),
+                        (None, _) => "call".into(),
+                    };
+                    let receiver = match operation.modifier {
+                        Some(Modifier::Static(_)) => Receiver::Static,
+                        _ => Receiver::Ref,
+                    };
+                    let signature = self.method(
+                        &name,
+                        receiver,
+                        &operation.args.body,
+                        Some(&operation.return_type),
+                        &mut methods,
+                    );
+                    let _ = writeln!(body, "    {signature};");
+                }
+                InterfaceMember::Stringifier(_) => {
+                    let _ = writeln!(body, "    fn stringify(&self) -> String;");
+                }
+                InterfaceMember::Iterable(iterable) => {
+                    let declaration = match iterable {
+                        IterableInterfaceMember::Single(_) => "iterable<V>",
+                        IterableInterfaceMember::Double(_) => "iterable<K, V>",
+                    };
+                    let _ = writeln!(body, "    // {declaration} is not generated");
+                }
+                InterfaceMember::AsyncIterable(iterable) => {
+                    let declaration = match iterable {
+                        AsyncIterableInterfaceMember::Single(_) => "async iterable<V>",
+                        AsyncIterableInterfaceMember::Double(_) => "async iterable<K, V>",
+                    };
+                    let _ = writeln!(body, "    // {declaration} is not generated");
+                }
+                InterfaceMember::Maplike(_) => {
+                    let _ = writeln!(body, "    // maplike<K, V> is not generated");
+                }
+                InterfaceMember::Setlike(_) => {
+                    let _ = writeln!(body, "    // setlike<V> is not generated");
+                }
+                InterfaceMember::LegacyIterable(_) => {
+                    let _ = writeln!(body, "    // legacyiterable<V> is not generated");
+                }
+                InterfaceMember::Invalid(_) => {}
+            }
+        }
+
+        let name = type_name(&interface.identifier.0);
+        let supertrait = match &interface.inheritance {
+            Some(inheritance) => format!(": {}", type_name(&inheritance.identifier.0)),
+            None => String::new(),
+        };
+        self.items
+            .push(trait_item(&name, &supertrait, &body, &consts));
+    }
+
+    fn const_(&mut self, member: &ConstMember<'_>, consts: &mut String) {
+        let type_ = match &member.const_type {
+            ConstType::Integer(integer) => integer_type(integer).to_owned(),
+            ConstType::FloatingPoint(float) => float_type(float).to_owned(),
+            ConstType::Boolean(_) => "bool".to_owned(),
+            ConstType::Byte(_) => "i8".to_owned(),
+            ConstType::Octet(_) => "u8".to_owned(),
+            ConstType::Bigint(_) => "i128".to_owned(),
+            ConstType::Identifier(identifier) => type_name(&identifier.0),
+        };
+        let float = match &member.const_type {
+            ConstType::FloatingPoint(float) => float_type(float),
+            _ => "f64",
+        };
+        let value = match &member.const_value {
+            ConstValue::Boolean(boolean) => boolean.value().to_string(),
+            ConstValue::Integer(integer) => integer_literal(integer),
+            ConstValue::Float(FloatLit::Value(value)) => float_literal(&value.0),
+            ConstValue::Float(FloatLit::Infinity(_)) => format!("{float}::INFINITY"),
+            ConstValue::Float(FloatLit::NegInfinity(_)) => format!("{float}::NEG_INFINITY"),
+            ConstValue::Float(FloatLit::NaN(_)) => format!("{float}::NAN"),
+        };
+        let name = escape(
+            member
+                .identifier
+                .0
+                .trim_start_matches('_')
+                .replace('-', "_"),
+        );
+        let _ = writeln!(consts, "    pub const {name}: {type_} = {value};");
+    }
+
+    /// Returns the signature of a method, named after its arguments when the name is taken
+    fn method(
+        &mut self,
+        name: &str,
+        receiver: Receiver,
+        arguments: &ArgumentList<'_>,
+        return_type: Option<&Type<'_>>,
+        methods: &mut HashSet<String>,
+    ) -> String {
+        let arguments: Vec<_> = arguments
+            .list
+            .iter()
+            .map(|argument| self.argument(argument))
+            .collect();
+
+        let mut name = name.to_owned();
+        if methods.contains(&name) {
+            let names: Vec<_> = arguments
+                .iter()
+                .map(|(argument, _)| argument.trim_start_matches("r#"))
+                .collect();
+            name = match names.is_empty() {
+                true => format!("{}_0", name.trim_start_matches("r#")),
+                false => format!(
+                    "{}_with_{}",
+                    name.trim_start_matches("r#"),
+                    names.join("_and_")
+                ),
+            };
+            let base = name.clone();
+            let mut suffix = 2;
+            while methods.contains(&name) {
+                name = format!("{base}_{suffix}");
+                suffix += 1;
+            }
+        }
+        methods.insert(name.clone());
+
+        let mut parameters = vec![];
+        if let Receiver::Ref = receiver {
+            parameters.push("&self".to_owned());
+        }
+        for (argument, type_) in arguments {
+            parameters.push(format!("{argument}: {type_}"));
+        }
+        let return_type = match return_type {
+            Some(return_type) => self.return_type(return_type),
+            None => " -> Self".to_owned(),
+        };
+        let bound = match receiver {
+            Receiver::Ref => "",
+            Receiver::Static | Receiver::Constructor => " where Self: Sized",
+        };
+        format!("fn {name}({}){return_type}{bound}", parameters.join(", "))
+    }
+
+    /// Returns the name and the type of an argument
+    fn argument(&mut self, argument: &Argument<'_>) -> (String, String) {
+        match argument {
+            Argument::Single(argument) => {
+                let mut type_ = self.type_(&argument.type_.type_);
+                if argument.optional.is_some() {
+                    type_ = format!("Option<{type_}>");
+                }
+                (member_name(&argument.identifier.0), type_)
+            }
+            Argument::Variadic(argument) => {
+                let type_ = self.type_(&argument.type_);
+                (member_name(&argument.identifier.0), format!("Vec<{type_}>"))
+            }
+        }
+    }
+
+    fn return_type(&mut self, type_: &Type<'_>) -> String {
+        match self.type_(type_).as_str() {
+            "()" => String::new(),
+            type_ => format!(" -> {type_}"),
+        }
+    }
+
+    fn type_(&mut self, type_: &Type<'_>) -> String {
+        match type_ {
+            Type::Single(SingleType::Any(_)) => "Any".to_owned(),
+            Type::Single(SingleType::Promise(promise)) => {
+                format!("Promise<{}>", self.type_(&promise.generics.body))
+            }
+            Type::Single(SingleType::Distinguishable(type_)) => {
+                let (type_, nullable) = self.distinguishable(type_);
+                optional(type_, nullable)
+            }
+            Type::Union(union) => {
+                let (type_, nullable) = self.union(&union.type_);
+                optional(type_, nullable || union.q_mark.is_some())
+            }
+        }
+    }
+
+    /// Returns the name of the enum generated for the union, and whether a member is nullable
+    fn union(&mut self, union: &UnionType<'_>) -> (String, bool) {
+        let mut variants = vec![];
+        let mut nullable = false;
+        self.union_variants(union, &mut variants, &mut nullable);
+        let name = variants
+            .iter()
+            .map(|(variant, _)| variant.as_str())
+            .collect::<Vec<_>>()
+            .join("Or");
+        self.unions.entry(name.clone()).or_insert(variants);
+        (name, nullable)
+    }
+
+    fn union_variants(
+        &mut self,
+        union: &UnionType<'_>,
+        variants: &mut Vec<(String, String)>,
+        nullable: &mut bool,
+    ) {
+        for member in &union.body.list {
+            match member {
+                UnionMemberType::Single(member) => {
+                    let (type_, is_nullable) = self.distinguishable(&member.type_);
+                    *nullable |= is_nullable;
+                    variants.push((distinguishable_name(&member.type_), type_));
+                }
+                UnionMemberType::Union(member) => {
+                    *nullable |= member.q_mark.is_some();
+                    self.union_variants(&member.type_, variants, nullable);
+                }
+            }
+        }
+    }
+
+    /// Returns the Rust type, without the `Option` of a nullable type, and whether the type is
+    /// nullable
+    fn distinguishable(&mut self, type_: &DistinguishableType<'_>) -> (String, bool) {
+        macro_rules! simple {
+            ($type_:expr, $rust:expr) => {
+                ($rust.to_owned(), $type_.q_mark.is_some())
+            };
+        }
+        match type_ {
+            DistinguishableType::Integer(t) => simple!(t, integer_type(&t.type_)),
+            DistinguishableType::FloatingPoint(t) => simple!(t, float_type(&t.type_)),
+            DistinguishableType::Boolean(t) => simple!(t, "bool"),
+            DistinguishableType::Byte(t) => simple!(t, "i8"),
+            DistinguishableType::Octet(t) => simple!(t, "u8"),
+            DistinguishableType::Bigint(t) => simple!(t, "i128"),
+            DistinguishableType::ByteString(t) => simple!(t, "Vec<u8>"),
+            DistinguishableType::DOMString(t) => simple!(t, "String"),
+            DistinguishableType::USVString(t) => simple!(t, "String"),
+            DistinguishableType::Object(t) => simple!(t, "Object"),
+            DistinguishableType::Symbol(t) => simple!(t, "Symbol"),
+            DistinguishableType::ArrayBuffer(t) => simple!(t, "Vec<u8>"),
+            DistinguishableType::DataView(t) => simple!(t, "Vec<u8>"),
+            DistinguishableType::Int8Array(t) => simple!(t, "Vec<i8>"),
+            DistinguishableType::Int16Array(t) => simple!(t, "Vec<i16>"),
+            DistinguishableType::Int32Array(t) => simple!(t, "Vec<i32>"),
+            DistinguishableType::Uint8Array(t) => simple!(t, "Vec<u8>"),
+            DistinguishableType::Uint16Array(t) => simple!(t, "Vec<u16>"),
+            DistinguishableType::Uint32Array(t) => simple!(t, "Vec<u32>"),
+            DistinguishableType::Uint8ClampedArray(t) => simple!(t, "Vec<u8>"),
+            DistinguishableType::BigInt64Array(t) => simple!(t, "Vec<i64>"),
+            DistinguishableType::BigUint64Array(t) => simple!(t, "Vec<u64>"),
+            DistinguishableType::Float32Array(t) => simple!(t, "Vec<f32>"),
+            DistinguishableType::Float64Array(t) => simple!(t, "Vec<f64>"),
+            DistinguishableType::Undefined(t) => simple!(t, "()"),
+            DistinguishableType::Sequence(t) => {
+                let inner = self.type_(&t.type_.generics.body.type_);
+                (format!("Vec<{inner}>"), t.q_mark.is_some())
+            }
+            DistinguishableType::FrozenArrayType(t) => {
+                let inner = self.type_(&t.type_.generics.body.type_);
+                (format!("Vec<{inner}>"), t.q_mark.is_some())
+            }
+            DistinguishableType::ObservableArrayType(t) => {
+                let inner = self.type_(&t.type_.generics.body.type_);
+                (format!("Vec<{inner}>"), t.q_mark.is_some())
+            }
+            DistinguishableType::RecordType(t) => {
+                let (key, _, value) = &t.type_.generics.body;
+                let key = match key {
+                    RecordKeyType::Byte(_) => "Vec<u8>",
+                    RecordKeyType::DOM(_) | RecordKeyType::USV(_) => "String",
+                };
+                let value = self.type_(&value.type_);
+                (
+                    format!("std::collections::HashMap<{key}, {value}>"),
+                    t.q_mark.is_some(),
+                )
+            }
+            DistinguishableType::Identifier(t) => {
+                let name = t.type_.0.as_ref();
+                let is_interface = self.model.interfaces.contains_key(name)
+                    || self.model.callback_interfaces.contains_key(name);
+                let type_ = match is_interface {
+                    true => format!("std::rc::Rc<dyn {}>", type_name(name)),
+                    false => type_name(name),
+                };
+                (type_, t.q_mark.is_some())
+            }
+        }
+    }
+}
+
+/// Returns a trait, followed by its constants in an implementation for the trait object, as
+/// traits with associated constants cannot be made into objects
+fn trait_item(name: &str, supertrait: &str, body: &str, consts: &str) -> String {
+    let mut item = match body.is_empty() {
+        true => format!("pub trait {name}{supertrait} {{}}\n"),
+        false => format!("pub trait {name}{supertrait} {{\n{body}}}\n"),
+    };
+    if !consts.is_empty() {
+        let _ = write!(item, "\nimpl dyn {name} {{\n{consts}}}\n");
+    }
+    item
+}
+
+#[derive(Copy, Clone)]
+enum Receiver {
+    /// A method taking `&self`
+    Ref,
+    /// An associated function
+    Static,
+    /// A constructor, returning `Self`
+    Constructor,
+}
+
+fn optional(type_: String, nullable: bool) -> String {
+    match nullable {
+        true => format!("Option<{type_}>"),
+        false => type_,
+    }
+}
+
+fn integer_type(type_: &IntegerType) -> &'static str {
+    match type_ {
+        IntegerType::Short(t) if t.unsigned.is_some() => "u16",
+        IntegerType::Short(_) => "i16",
+        IntegerType::Long(t) if t.unsigned.is_some() => "u32",
+        IntegerType::Long(_) => "i32",
+        IntegerType::LongLong(t) if t.unsigned.is_some() => "u64",
+        IntegerType::LongLong(_) => "i64",
+    }
+}
+
+fn float_type(type_: &FloatingPointType) -> &'static str {
+    match type_ {
+        FloatingPointType::Float(_) => "f32",
+        FloatingPointType::Double(_) => "f64",
+    }
+}
+
+/// The name of a union member type, used for the variants and the names of union enums
+fn distinguishable_name(type_: &DistinguishableType<'_>) -> String {
+    let inner_name = |type_: &Type<'_>| match type_ {
+        Type::Single(SingleType::Distinguishable(type_)) => distinguishable_name(type_),
+        Type::Single(SingleType::Any(_)) => "Any".to_owned(),
+        Type::Single(SingleType::Promise(_)) => "Promise".to_owned(),
+        Type::Union(_) => "Union".to_owned(),
+    };
+    match type_ {
+        DistinguishableType::Integer(t) => match &t.type_ {
+            IntegerType::Short(t) if t.unsigned.is_some() => "UnsignedShort".to_owned(),
+            IntegerType::Short(_) => "Short".to_owned(),
+            IntegerType::Long(t) if t.unsigned.is_some() => "UnsignedLong".to_owned(),
+            IntegerType::Long(_) => "Long".to_owned(),
+            IntegerType::LongLong(t) if t.unsigned.is_some() => "UnsignedLongLong".to_owned(),
+            IntegerType::LongLong(_) => "LongLong".to_owned(),
+        },
+        DistinguishableType::FloatingPoint(t) => match &t.type_ {
+            FloatingPointType::Float(t) if t.unrestricted.is_some() => "UnrestrictedFloat",
+            FloatingPointType::Float(_) => "Float",
+            FloatingPointType::Double(t) if t.unrestricted.is_some() => "UnrestrictedDouble",
+            FloatingPointType::Double(_) => "Double",
+        }
+        .to_owned(),
+        DistinguishableType::Boolean(_) => "Boolean".to_owned(),
+        DistinguishableType::Byte(_) => "Byte".to_owned(),
+        DistinguishableType::Octet(_) => "Octet".to_owned(),
+        DistinguishableType::Bigint(_) => "Bigint".to_owned(),
+        DistinguishableType::ByteString(_) => "ByteString".to_owned(),
+        DistinguishableType::DOMString(_) => "DOMString".to_owned(),
+        DistinguishableType::USVString(_) => "USVString".to_owned(),
+        DistinguishableType::Object(_) => "Object".to_owned(),
+        DistinguishableType::Symbol(_) => "Symbol".to_owned(),
+        DistinguishableType::ArrayBuffer(_) => "ArrayBuffer".to_owned(),
+        DistinguishableType::DataView(_) => "DataView".to_owned(),
+        DistinguishableType::Int8Array(_) => "Int8Array".to_owned(),
+        DistinguishableType::Int16Array(_) => "Int16Array".to_owned(),
+        DistinguishableType::Int32Array(_) => "Int32Array".to_owned(),
+        DistinguishableType::Uint8Array(_) => "Uint8Array".to_owned(),
+        DistinguishableType::Uint16Array(_) => "Uint16Array".to_owned(),
+        DistinguishableType::Uint32Array(_) => "Uint32Array".to_owned(),
+        DistinguishableType::Uint8ClampedArray(_) => "Uint8ClampedArray".to_owned(),
+        DistinguishableType::BigInt64Array(_) => "BigInt64Array".to_owned(),
+        DistinguishableType::BigUint64Array(_) => "BigUint64Array".to_owned(),
+        DistinguishableType::Float32Array(_) => "Float32Array".to_owned(),
+        DistinguishableType::Float64Array(_) => "Float64Array".to_owned(),
+        DistinguishableType::Undefined(_) => "Undefined".to_owned(),
+        DistinguishableType::Sequence(t) => {
+            format!("{}Sequence", inner_name(&t.type_.generics.body.type_))
+        }
+        DistinguishableType::FrozenArrayType(t) => {
+            format!("{}FrozenArray", inner_name(&t.type_.generics.body.type_))
+        }
+        DistinguishableType::ObservableArrayType(t) => {
+            format!(
+                "{}ObservableArray",
+                inner_name(&t.type_.generics.body.type_)
+            )
+        }
+        DistinguishableType::RecordType(t) => {
+            format!("{}Record", inner_name(&t.type_.generics.body.2.type_))
+        }
+        DistinguishableType::Identifier(t) => type_name(&t.type_.0),
+    }
+}
+
+/// Returns the value in decimal, or the source text if it does not fit in an `i128`, which no
+/// Rust integer type holds either, so that the compiler reports it
+fn integer_literal(integer: &IntegerLit<'_>) -> String {
+    match integer.value() {
+        Ok(value) => value.to_string(),
+        Err(_) => {
+            let mut text = String::new();
+            integer.for_each_terminal(&mut |terminal, _| text.push_str(terminal));
+            text
+        }
+    }
+}
+
+fn float_literal(value: &str) -> String {
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", value),
+    };
+    // Rust needs digits before the point, and a point or an exponent after integers
+    let digits = match digits.starts_with('.') {
+        true => format!("0{digits}"),
+        false => digits.to_owned(),
+    };
+    match digits.contains(['.', 'e', 'E']) {
+        true => format!("{sign}{digits}"),
+        false => format!("{sign}{digits}.0"),
+    }
+}
+
+/// Converts a WebIDL identifier to a Rust type name
+fn type_name(identifier: &str) -> String {
+    escape(identifier.trim_start_matches('_').replace('-', "_"))
+}
+
+/// Converts a WebIDL identifier to a snake case Rust name, e.g. `innerHTML` to `inner_html`
+fn member_name(identifier: &str) -> String {
+    let chars: Vec<char> = identifier.trim_start_matches('_').chars().collect();
+    let mut name = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' {
+            name.push('_');
+            continue;
+        }
+        if c.is_ascii_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lowercase = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+            let starts_word = previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_is_lowercase);
+            if starts_word && !name.ends_with('_') {
+                name.push('_');
+            }
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    escape(name)
+}
+
+/// Converts an enumeration value to a Rust variant name, e.g. `no-referrer` to `NoReferrer`
+fn variant_name(value: &str) -> String {
+    let mut name = String::new();
+    for word in value.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    match name.chars().next() {
+        None => "Empty".to_owned(),
+        Some(first) if first.is_ascii_digit() => format!("Value{name}"),
+        Some(_) => escape(name),
+    }
+}
+
+/// Escapes the Rust keywords
+fn escape(name: String) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+    match name.as_str() {
+        // Cannot be raw identifiers
+        "crate" | "self" | "super" | "Self" => format!("{name}_"),
+        _ if KEYWORDS.contains(&name.as_str()) => format!("r#{name}"),
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn generate_source(source: &str) -> String {
+        let definitions = crate::parse(source).unwrap();
+        let (model, errors) = crate::resolve::resolve(&[definitions]);
+        assert!(errors.is_empty());
+        generate(&model)
+    }
+
+    #[test]
+    fn should_convert_names() {
+        assert_eq!(member_name("innerHTML"), "inner_html");
+        assert_eq!(member_name("getElementById"), "get_element_by_id");
+        assert_eq!(member_name("HTMLElement"), "html_element");
+        assert_eq!(member_name("_type"), "r#type");
+        assert_eq!(member_name("self"), "self_");
+        assert_eq!(
+            variant_name("no-referrer-when-downgrade"),
+            "NoReferrerWhenDowngrade"
+        );
+        assert_eq!(variant_name(""), "Empty");
+        assert_eq!(variant_name("2d"), "Value2d");
+        assert_eq!(float_literal("-.5e3"), "-0.5e3");
+        assert_eq!(float_literal("5"), "5.0");
+    }
+
+    #[test]
+    fn should_number_clashing_variants() {
+        let source = generate_source(r#"enum E { "open", "Open", "open2", "a-b", "a_b" };"#);
+
+        assert!(source
+            .contains("pub enum E {\n    Open,\n    Open3,\n    Open2,\n    AB,\n    AB2,\n}\n"));
+        assert!(source.contains("            \"Open\" => Ok(E::Open3),\n"));
+        assert!(source.contains("            \"open2\" => Ok(E::Open2),\n"));
+        assert!(source.contains("            E::AB2 => \"a_b\",\n"));
+    }
+
+    #[test]
+    fn should_generate_interfaces() {
+        let source = generate_source(
+            "
+            interface Node {
+                const unsigned short ELEMENT_NODE = 1;
+                const unsigned short DOCUMENT_POSITION_CONTAINS = 0x08;
+                const short MODE = -010;
+                readonly attribute DOMString nodeName;
+                attribute DOMString? textContent;
+                Node appendChild(Node node);
+            };
+            interface Element : Node {
+                constructor();
+                static Element create(optional DOMString tag);
+                undefined append((Node or DOMString)... nodes);
+                undefined append(Node node);
+                getter Element? (unsigned long index);
+            };
+            ",
+        );
+
+        assert_eq!(
+            source,
+            "\
+pub trait Element: Node {
+    fn new() -> Self where Self: Sized;
+    fn create(tag: Option<String>) -> std::rc::Rc<dyn Element> where Self: Sized;
+    fn append(&self, nodes: Vec<NodeOrDOMString>);
+    fn append_with_node(&self, node: std::rc::Rc<dyn Node>);
+    fn get(&self, index: u32) -> Option<std::rc::Rc<dyn Element>>;
+}
+
+pub trait Node {
+    fn node_name(&self) -> String;
+    fn text_content(&self) -> Option<String>;
+    fn set_text_content(&self, value: Option<String>);
+    fn append_child(&self, node: std::rc::Rc<dyn Node>) -> std::rc::Rc<dyn Node>;
+}
+
+impl dyn Node {
+    pub const ELEMENT_NODE: u16 = 1;
+    pub const DOCUMENT_POSITION_CONTAINS: u16 = 8;
+    pub const MODE: i16 = -8;
+}
+
+pub enum NodeOrDOMString {
+    Node(std::rc::Rc<dyn Node>),
+    DOMString(String),
+}
+"
+        );
+    }
+
+    #[test]
+    fn should_generate_dictionaries_and_callbacks() {
+        let source = generate_source(
+            "
+            dictionary EventInit { boolean bubbles = false; };
+            dictionary CustomEventInit : EventInit {
+                required any detail;
+                record<DOMString, sequence<long>> counts;
+            };
+            callback Listener = boolean (CustomEventInit init, optional long? delay);
+            typedef (long or double)? Number;
+            ",
+        );
+
+        assert_eq!(
+            source,
+            "\
+pub type Number = Option<LongOrDouble>;
+
+pub struct CustomEventInit {
+    pub bubbles: Option<bool>,
+    pub detail: Any,
+    pub counts: Option<std::collections::HashMap<String, Vec<i32>>>,
+}
+
+pub struct EventInit {
+    pub bubbles: Option<bool>,
+}
+
+pub type Listener = Box<dyn Fn(CustomEventInit, Option<Option<i32>>) -> bool>;
+
+pub enum LongOrDouble {
+    Long(i32),
+    Double(f64),
+}
+"
+        );
+    }
+}
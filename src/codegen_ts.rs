@@ -0,0 +1,771 @@
+//! Generates TypeScript declarations from WebIDL definitions.
+//!
+//! [`generate`] writes the declarations of the definitions of a resolved [`Model`], so that
+//! partial definitions and mixins are merged into the interfaces using them, much like the
+//! generator of the TypeScript DOM library:
+//!
+//! - interfaces become interfaces extending the inherited interface, with a `declare var` for
+//!   the interface object holding the constructors, the constants and the static members
+//! - callback interfaces become interfaces, with a `declare var` for their constants if they
+//!   have any
+//! - dictionaries become interfaces extending the inherited dictionary, with optional fields
+//!   for the members that are not required
+//! - enumerations become unions of string literals
+//! - callback functions become function types, and typedefs type aliases
+//! - namespaces become `declare namespace` blocks
+//!
+//! Iterable declarations add the iteration methods, and maplike and setlike declarations extend
+//! `Map` and `Set`, or `ReadonlyMap` and `ReadonlySet` when they are readonly.
+//!
+//! Types map to TypeScript types as follows:
+//!
+//! | WebIDL                                          | TypeScript                           |
+//! |-------------------------------------------------|--------------------------------------|
+//! | `T?`                                            | `T \| null`                          |
+//! | the integer and floating point types            | `number`                             |
+//! | `bigint`, `boolean`, `object`, `symbol`, `any`  | `bigint`, `boolean`, `object`, ...   |
+//! | `DOMString`, `USVString`, `ByteString`          | `string`                             |
+//! | `undefined`                                     | `undefined`, or `void` when returned |
+//! | `sequence<T>`, `ObservableArray<T>`             | `T[]`                                |
+//! | `FrozenArray<T>`                                | `ReadonlyArray<T>`                   |
+//! | `record<K, V>`                                  | `Record<string, V>`                  |
+//! | `Promise<T>`                                    | `Promise<T>`                         |
+//! | `(A or B)`                                      | `A \| B`                             |
+//! | buffer source types and identifiers             | the same name                        |
+//!
+//! ### Example
+//!
+//! ```
+//! let definitions = weedle::parse("
+//!     enum Mode { \"open\", \"closed\" };
+//!     dictionary Init { required Mode mode; boolean? delegatesFocus; };
+//! ").unwrap();
+//! let (model, _) = weedle::resolve::resolve(&[definitions]);
+//!
+//! assert_eq!(
+//!     weedle::codegen_ts::generate(&model),
+//!     "\
+//! type Mode = \"open\" | \"closed\";
+//!
+//! interface Init {
+//!     mode: Mode;
+//!     delegatesFocus?: boolean | null;
+//! }
+//! "
+//! );
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::argument::{Argument, ArgumentList};
+use crate::dictionary::DictionaryMember;
+use crate::interface::{
+    AsyncIterableInterfaceMember, CallbackInterfaceMember, InterfaceMember, IterableInterfaceMember,
+};
+use crate::literal::ConstValue;
+use crate::members::{ConstMember, Modifier, StringifierOrInheritOrStatic};
+use crate::namespace::NamespaceMember;
+use crate::registry::LegacyNoInterfaceObject;
+use crate::resolve::{Dictionary, Model};
+use crate::types::{
+    AttributedType, ConstType, DistinguishableType, SingleType, Type, UnionMemberType, UnionType,
+};
+use crate::{
+    CallbackDefinition, CallbackInterfaceDefinition, EnumDefinition, InterfaceDefinition,
+    NamespaceDefinition,
+};
+
+/// Returns the TypeScript declarations of the definitions of the model
+pub fn generate(model: &Model<'_>) -> String {
+    let mut items = vec![];
+    for (name, typedef) in &model.typedefs {
+        items.push(format!(
+            "type {} = {};\n",
+            name.trim_start_matches('_'),
+            type_(&typedef.type_.type_)
+        ));
+    }
+    for enum_ in model.enums.values() {
+        items.push(enum_item(enum_));
+    }
+    for dictionary in model.dictionaries.values() {
+        items.push(dictionary_item(dictionary));
+    }
+    for callback in model.callbacks.values() {
+        items.push(callback_item(callback));
+    }
+    for callback_interface in model.callback_interfaces.values() {
+        items.push(callback_interface_item(callback_interface));
+    }
+    for interface in model.interfaces.values() {
+        items.push(interface_item(&interface.definition));
+    }
+    for namespace in model.namespaces.values() {
+        items.push(namespace_item(&namespace.definition));
+    }
+    items.join("\n")
+}
+
+fn enum_item(enum_: &EnumDefinition<'_>) -> String {
+    let values: Vec<_> = enum_
+        .values
+        .body
+        .list
+        .iter()
+        .map(|value| format!("{:?}", value.0))
+        .collect();
+    format!(
+        "type {} = {};\n",
+        enum_.identifier.0.trim_start_matches('_'),
+        values.join(" | ")
+    )
+}
+
+fn dictionary_item(dictionary: &Dictionary<'_>) -> String {
+    let definition = &dictionary.definition;
+    let mut item = format!(
+        "interface {}",
+        definition.identifier.0.trim_start_matches('_')
+    );
+    if let Some(inheritance) = &definition.inheritance {
+        let _ = write!(
+            item,
+            " extends {}",
+            inheritance.identifier.0.trim_start_matches('_')
+        );
+    }
+    let mut body = String::new();
+    for member in &definition.members.body {
+        let DictionaryMember::Field(member) = member else {
+            continue;
+        };
+        let optional = if member.required.is_some() { "" } else { "?" };
+        let _ = writeln!(
+            body,
+            "    {}{optional}: {};",
+            property_name(&member.identifier.0),
+            type_(&member.type_)
+        );
+    }
+    block(item, &body)
+}
+
+fn callback_item(callback: &CallbackDefinition<'_>) -> String {
+    format!(
+        "type {} = ({}) => {};\n",
+        callback.identifier.0.trim_start_matches('_'),
+        arguments(&callback.arguments.body),
+        return_type(&callback.return_type)
+    )
+}
+
+fn callback_interface_item(callback_interface: &CallbackInterfaceDefinition<'_>) -> String {
+    let name = callback_interface.identifier.0.trim_start_matches('_');
+    let mut body = String::new();
+    let mut consts = String::new();
+    for member in &callback_interface.members.body {
+        match member {
+            CallbackInterfaceMember::Const(member) => const_(member, &mut consts),
+            CallbackInterfaceMember::Operation(operation) => {
+                let name = operation.identifier.as_ref().map_or("", |i| &i.0);
+                let _ = writeln!(
+                    body,
+                    "    {}({}): {};",
+                    property_name(name),
+                    arguments(&operation.args.body),
+                    return_type(&operation.return_type)
+                );
+            }
+            CallbackInterfaceMember::Invalid(_) => {}
+        }
+    }
+    let mut item = block(format!("interface {name}"), &body);
+    if !consts.is_empty() {
+        item.push('\n');
+        let _ = write!(item, "declare var {name}: {{\n{consts}}};\n");
+    }
+    item
+}
+
+fn interface_item(interface: &InterfaceDefinition<'_>) -> String {
+    let name = interface.identifier.0.trim_start_matches('_');
+    let mut extends = vec![];
+    if let Some(inheritance) = &interface.inheritance {
+        extends.push(inheritance.identifier.0.trim_start_matches('_').to_owned());
+    }
+    // The members of the interface, and of its interface object
+    let mut body = String::new();
+    let mut statics = format!("    prototype: {name};\n");
+    for member in &interface.members.body {
+        match member {
+            InterfaceMember::Const(member) => {
+                const_(member, &mut body);
+                const_(member, &mut statics);
+            }
+            InterfaceMember::Attribute(attribute) => {
+                let readonly = if attribute.readonly.is_some() {
+                    "readonly "
+                } else {
+                    ""
+                };
+                let members = match attribute.modifier {
+                    Some(StringifierOrInheritOrStatic::Static(_)) => &mut statics,
+                    _ => &mut body,
+                };
+                let _ = writeln!(
+                    members,
+                    "    {readonly}{}: {};",
+                    property_name(&attribute.identifier.0),
+                    type_(&attribute.type_.type_)
+                );
+                if let Some(StringifierOrInheritOrStatic::Stringifier(_)) = attribute.modifier {
+                    body.push_str("    toString(): string;\n");
+                }
+            }
+            InterfaceMember::Constructor(constructor) => {
+                let _ = writeln!(
+                    statics,
+                    "    new({}): {name};",
+                    arguments(&constructor.args.body)
+                );
+            }
+            InterfaceMember::Operation(operation) => {
+                let arguments = &operation.args.body;
+                let returned = return_type(&operation.return_type);
+                if let (Some(Modifier::Getter(_)), [Argument::Single(key)]) =
+                    (&operation.modifier, arguments.list.as_slice())
+                {
+                    if is_integer(&key.type_) {
+                        let _ = writeln!(
+                            body,
+                            "    readonly [{}: number]: {returned};",
+                            key.identifier.0.trim_start_matches('_')
+                        );
+                    }
+                }
+                // The special operations without a name are only reachable as indexed or
+                // named properties
+                let Some(identifier) = &operation.identifier else {
+                    continue;
+                };
+                let members = match operation.modifier {
+                    Some(Modifier::Static(_)) => &mut statics,
+                    _ => &mut body,
+                };
+                let _ = writeln!(
+                    members,
+                    "    {}({}): {returned};",
+                    property_name(&identifier.0),
+                    self::arguments(arguments)
+                );
+            }
+            InterfaceMember::Stringifier(_) => body.push_str("    toString(): string;\n"),
+            InterfaceMember::Iterable(iterable) => {
+                let (key, value) = match iterable {
+                    IterableInterfaceMember::Single(iterable) => {
+                        ("number".to_owned(), type_(&iterable.generics.body.type_))
+                    }
+                    IterableInterfaceMember::Double(iterable) => {
+                        let (key, _, value) = &iterable.generics.body;
+                        (type_(&key.type_), type_(&value.type_))
+                    }
+                };
+                let pair = format!("[{key}, {value}]");
+                let iterated = match iterable {
+                    IterableInterfaceMember::Single(_) => &value,
+                    IterableInterfaceMember::Double(_) => &pair,
+                };
+                let _ = write!(
+                    body,
+                    "    [Symbol.iterator](): IterableIterator<{iterated}>;\n    \
+                     entries(): IterableIterator<{pair}>;\n    \
+                     keys(): IterableIterator<{key}>;\n    \
+                     values(): IterableIterator<{value}>;\n    \
+                     forEach(callbackfn: (value: {value}, key: {key}, parent: {name}) => void, \
+                     thisArg?: any): void;\n"
+                );
+            }
+            InterfaceMember::AsyncIterable(iterable) => match iterable {
+                AsyncIterableInterfaceMember::Single(iterable) => {
+                    let value = type_(&iterable.generics.body.type_);
+                    let _ = write!(
+                        body,
+                        "    [Symbol.asyncIterator](): AsyncIterableIterator<{value}>;\n    \
+                         values(): AsyncIterableIterator<{value}>;\n"
+                    );
+                }
+                AsyncIterableInterfaceMember::Double(iterable) => {
+                    let (key, _, value) = &iterable.generics.body;
+                    let (key, value) = (type_(&key.type_), type_(&value.type_));
+                    let _ = write!(
+                        body,
+                        "    [Symbol.asyncIterator](): AsyncIterableIterator<[{key}, {value}]>;\n    \
+                         entries(): AsyncIterableIterator<[{key}, {value}]>;\n    \
+                         keys(): AsyncIterableIterator<{key}>;\n    \
+                         values(): AsyncIterableIterator<{value}>;\n"
+                    );
+                }
+            },
+            InterfaceMember::Maplike(maplike) => {
+                let (key, _, value) = &maplike.generics.body;
+                let map = if maplike.readonly.is_some() {
+                    "ReadonlyMap"
+                } else {
+                    "Map"
+                };
+                extends.push(format!(
+                    "{map}<{}, {}>",
+                    type_(&key.type_),
+                    type_(&value.type_)
+                ));
+            }
+            InterfaceMember::Setlike(setlike) => {
+                let set = if setlike.readonly.is_some() {
+                    "ReadonlySet"
+                } else {
+                    "Set"
+                };
+                extends.push(format!("{set}<{}>", type_(&setlike.generics.body.type_)));
+            }
+            InterfaceMember::LegacyIterable(iterable) => {
+                let value = type_(&iterable.generics.body.type_);
+                let _ = writeln!(body, "    [Symbol.iterator](): IterableIterator<{value}>;");
+            }
+            InterfaceMember::Invalid(_) => {}
+        }
+    }
+
+    let mut header = format!("interface {name}");
+    if !extends.is_empty() {
+        let _ = write!(header, " extends {}", extends.join(", "));
+    }
+    let mut item = block(header, &body);
+    let legacy_no_interface_object = interface
+        .attributes
+        .as_ref()
+        .and_then(|list| list.get::<LegacyNoInterfaceObject>());
+    if legacy_no_interface_object.is_none() {
+        item.push('\n');
+        let _ = write!(item, "declare var {name}: {{\n{statics}}};\n");
+    }
+    item
+}
+
+fn namespace_item(namespace: &NamespaceDefinition<'_>) -> String {
+    let mut body = String::new();
+    for member in &namespace.members.body {
+        match member {
+            NamespaceMember::Const(member) => {
+                let _ = writeln!(
+                    body,
+                    "    const {}: {};",
+                    member.identifier.0.trim_start_matches('_'),
+                    const_type(member)
+                );
+            }
+            NamespaceMember::Attribute(attribute) => {
+                let _ = writeln!(
+                    body,
+                    "    const {}: {};",
+                    attribute.identifier.0.trim_start_matches('_'),
+                    type_(&attribute.type_.type_)
+                );
+            }
+            NamespaceMember::Operation(operation) => {
+                let Some(identifier) = &operation.identifier else {
+                    continue;
+                };
+                let _ = writeln!(
+                    body,
+                    "    function {}({}): {};",
+                    identifier.0.trim_start_matches('_'),
+                    arguments(&operation.args.body),
+                    return_type(&operation.return_type)
+                );
+            }
+            NamespaceMember::Invalid(_) => {}
+        }
+    }
+    let name = namespace.identifier.0.trim_start_matches('_');
+    block(format!("declare namespace {name}"), &body)
+}
+
+/// Returns a declaration with a body in braces
+fn block(header: String, body: &str) -> String {
+    match body.is_empty() {
+        true => format!("{header} {{}}\n"),
+        false => format!("{header} {{\n{body}}}\n"),
+    }
+}
+
+fn const_(member: &ConstMember<'_>, members: &mut String) {
+    let _ = writeln!(
+        members,
+        "    readonly {}: {};",
+        property_name(&member.identifier.0),
+        const_type(member)
+    );
+}
+
+/// Returns the literal type of the value of an integer or boolean constant, or the type of
+/// the other constants
+/// The largest integer a JavaScript number holds exactly, `Number.MAX_SAFE_INTEGER`
+const MAX_SAFE_INTEGER: u128 = (1 << 53) - 1;
+
+fn const_type(member: &ConstMember<'_>) -> String {
+    match (&member.const_type, &member.const_value) {
+        (ConstType::Bigint(_), _) => "bigint".to_owned(),
+        (_, ConstValue::Boolean(boolean)) => boolean.value().to_string(),
+        // A literal type beyond `Number.MAX_SAFE_INTEGER` would not be the value of the constant
+        (_, ConstValue::Integer(integer)) => match integer.value() {
+            Ok(value) if value.unsigned_abs() <= MAX_SAFE_INTEGER => value.to_string(),
+            _ => "number".to_owned(),
+        },
+        (ConstType::Identifier(identifier), _) => identifier.0.trim_start_matches('_').to_owned(),
+        (_, ConstValue::Float(_)) => "number".to_owned(),
+    }
+}
+
+fn arguments(arguments: &ArgumentList<'_>) -> String {
+    let arguments: Vec<_> = arguments
+        .list
+        .iter()
+        .map(|argument| match argument {
+            Argument::Single(argument) => {
+                let optional = if argument.optional.is_some() { "?" } else { "" };
+                format!(
+                    "{}{optional}: {}",
+                    parameter_name(&argument.identifier.0),
+                    type_(&argument.type_.type_)
+                )
+            }
+            Argument::Variadic(argument) => format!(
+                "...{}: {}",
+                parameter_name(&argument.identifier.0),
+                array(type_(&argument.type_))
+            ),
+        })
+        .collect();
+    arguments.join(", ")
+}
+
+fn return_type(type_: &Type<'_>) -> String {
+    match self::type_(type_).as_str() {
+        "undefined" => "void".to_owned(),
+        type_ => type_.to_owned(),
+    }
+}
+
+fn type_(type_: &Type<'_>) -> String {
+    match type_ {
+        Type::Single(SingleType::Any(_)) => "any".to_owned(),
+        Type::Single(SingleType::Promise(promise)) => {
+            format!("Promise<{}>", return_type(&promise.generics.body))
+        }
+        Type::Single(SingleType::Distinguishable(type_)) => distinguishable(type_),
+        Type::Union(union) => {
+            let mut members = vec![];
+            union_members(&union.type_, &mut members);
+            if union.q_mark.is_some() {
+                members.push("null".to_owned());
+            }
+            // Distinct WebIDL types may map to the same TypeScript type
+            let mut seen = HashSet::new();
+            members.retain(|member| seen.insert(member.clone()));
+            members.join(" | ")
+        }
+    }
+}
+
+fn union_members(union: &UnionType<'_>, members: &mut Vec<String>) {
+    for member in &union.body.list {
+        match member {
+            UnionMemberType::Single(member) => members.push(distinguishable(&member.type_)),
+            UnionMemberType::Union(member) => {
+                union_members(&member.type_, members);
+                if member.q_mark.is_some() {
+                    members.push("null".to_owned());
+                }
+            }
+        }
+    }
+}
+
+fn distinguishable(type_: &DistinguishableType<'_>) -> String {
+    macro_rules! nullable {
+        ($type_:expr, $ts:expr) => {
+            match $type_.q_mark {
+                Some(_) => format!("{} | null", $ts),
+                None => $ts.to_string(),
+            }
+        };
+    }
+    let inner = |type_: &AttributedType<'_>| self::type_(&type_.type_);
+    match type_ {
+        DistinguishableType::Integer(t) => nullable!(t, "number"),
+        DistinguishableType::FloatingPoint(t) => nullable!(t, "number"),
+        DistinguishableType::Boolean(t) => nullable!(t, "boolean"),
+        DistinguishableType::Byte(t) => nullable!(t, "number"),
+        DistinguishableType::Octet(t) => nullable!(t, "number"),
+        DistinguishableType::Bigint(t) => nullable!(t, "bigint"),
+        DistinguishableType::ByteString(t) => nullable!(t, "string"),
+        DistinguishableType::DOMString(t) => nullable!(t, "string"),
+        DistinguishableType::USVString(t) => nullable!(t, "string"),
+        DistinguishableType::Object(t) => nullable!(t, "object"),
+        DistinguishableType::Symbol(t) => nullable!(t, "symbol"),
+        DistinguishableType::ArrayBuffer(t) => nullable!(t, "ArrayBuffer"),
+        DistinguishableType::DataView(t) => nullable!(t, "DataView"),
+        DistinguishableType::Int8Array(t) => nullable!(t, "Int8Array"),
+        DistinguishableType::Int16Array(t) => nullable!(t, "Int16Array"),
+        DistinguishableType::Int32Array(t) => nullable!(t, "Int32Array"),
+        DistinguishableType::Uint8Array(t) => nullable!(t, "Uint8Array"),
+        DistinguishableType::Uint16Array(t) => nullable!(t, "Uint16Array"),
+        DistinguishableType::Uint32Array(t) => nullable!(t, "Uint32Array"),
+        DistinguishableType::Uint8ClampedArray(t) => nullable!(t, "Uint8ClampedArray"),
+        DistinguishableType::BigInt64Array(t) => nullable!(t, "BigInt64Array"),
+        DistinguishableType::BigUint64Array(t) => nullable!(t, "BigUint64Array"),
+        DistinguishableType::Float32Array(t) => nullable!(t, "Float32Array"),
+        DistinguishableType::Float64Array(t) => nullable!(t, "Float64Array"),
+        DistinguishableType::Undefined(t) => nullable!(t, "undefined"),
+        DistinguishableType::Sequence(t) => {
+            nullable!(t, array(inner(&t.type_.generics.body)))
+        }
+        DistinguishableType::FrozenArrayType(t) => {
+            nullable!(
+                t,
+                format!("ReadonlyArray<{}>", inner(&t.type_.generics.body))
+            )
+        }
+        DistinguishableType::ObservableArrayType(t) => {
+            nullable!(t, array(inner(&t.type_.generics.body)))
+        }
+        DistinguishableType::RecordType(t) => {
+            let (_, _, value) = &t.type_.generics.body;
+            nullable!(t, format!("Record<string, {}>", inner(value)))
+        }
+        DistinguishableType::Identifier(t) => nullable!(t, t.type_.0.trim_start_matches('_')),
+    }
+}
+
+/// Returns the array type of the elements, in parentheses when they are a union
+fn array(element: String) -> String {
+    let mut depth = 0;
+    let mut union = false;
+    for c in element.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            '|' if depth == 0 => union = true,
+            _ => {}
+        }
+    }
+    match union {
+        true => format!("({element})[]"),
+        false => format!("{element}[]"),
+    }
+}
+
+fn is_integer(type_: &AttributedType<'_>) -> bool {
+    matches!(
+        type_.type_,
+        Type::Single(SingleType::Distinguishable(DistinguishableType::Integer(_)))
+    )
+}
+
+/// Quotes the member names that are not JavaScript identifiers, e.g. with hyphens
+fn property_name(identifier: &str) -> String {
+    let name = identifier.trim_start_matches('_');
+    match name.contains('-') {
+        true => format!("{name:?}"),
+        false => name.to_owned(),
+    }
+}
+
+/// Renames the parameters named after reserved words, which are not allowed in strict mode
+fn parameter_name(identifier: &str) -> String {
+    const RESERVED: &[&str] = &[
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "function",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "var",
+        "void",
+        "while",
+        "with",
+        "yield",
+    ];
+    let name = identifier.trim_start_matches('_').replace('-', "_");
+    match RESERVED.contains(&name.as_str()) {
+        true => format!("{name}_"),
+        false => name,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn generate_source(source: &str) -> String {
+        let definitions = crate::parse(source).unwrap();
+        let (model, errors) = crate::resolve::resolve(&[definitions]);
+        assert!(errors.is_empty());
+        generate(&model)
+    }
+
+    #[test]
+    fn should_generate_interfaces() {
+        let source = generate_source(
+            "
+            interface Node {
+                const unsigned short ELEMENT_NODE = 1;
+                readonly attribute DOMString nodeName;
+                attribute DOMString? textContent;
+                Node appendChild(Node node);
+            };
+            interface Element : Node {
+                constructor();
+                static Element create(optional DOMString tag);
+                undefined append((Node or DOMString)... nodes);
+                getter Element? item(unsigned long index);
+                iterable<Node>;
+            };
+            interface mixin Slotted { readonly attribute Element? assignedSlot; };
+            Element includes Slotted;
+            [LegacyNoInterfaceObject] interface Storage : Node { stringifier; setlike<long>; };
+            ",
+        );
+
+        assert_eq!(
+            source,
+            "\
+interface Element extends Node {
+    append(...nodes: (Node | string)[]): void;
+    readonly [index: number]: Element | null;
+    item(index: number): Element | null;
+    [Symbol.iterator](): IterableIterator<Node>;
+    entries(): IterableIterator<[number, Node]>;
+    keys(): IterableIterator<number>;
+    values(): IterableIterator<Node>;
+    forEach(callbackfn: (value: Node, key: number, parent: Element) => void, thisArg?: any): void;
+    readonly assignedSlot: Element | null;
+}
+
+declare var Element: {
+    prototype: Element;
+    new(): Element;
+    create(tag?: string): Element;
+};
+
+interface Node {
+    readonly ELEMENT_NODE: 1;
+    readonly nodeName: string;
+    textContent: string | null;
+    appendChild(node: Node): Node;
+}
+
+declare var Node: {
+    prototype: Node;
+    readonly ELEMENT_NODE: 1;
+};
+
+interface Storage extends Node, Set<number> {
+    toString(): string;
+}
+"
+        );
+    }
+
+    #[test]
+    fn should_generate_dictionaries_and_callbacks() {
+        let source = generate_source(
+            "
+            dictionary EventInit { boolean bubbles = false; };
+            dictionary CustomEventInit : EventInit {
+                required any detail;
+                record<DOMString, sequence<long>> counts;
+            };
+            callback Listener = Promise<undefined> (CustomEventInit init, optional long? default);
+            callback interface NodeFilter {
+                const octet FILTER_ACCEPT = 01;
+                const unsigned long long SAFE = 9007199254740991;
+                const unsigned long long BIG = 18446744073709551615;
+                const long long SMALL = -9007199254740992;
+                unsigned short acceptNode(Node node);
+            };
+            namespace console { undefined log(any... data); };
+            typedef (sequence<(long or double)> or FrozenArray<DOMString>)? Numbers;
+            ",
+        );
+
+        assert_eq!(
+            source,
+            "\
+type Numbers = number[] | ReadonlyArray<string> | null;
+
+interface CustomEventInit extends EventInit {
+    detail: any;
+    counts?: Record<string, number[]>;
+}
+
+interface EventInit {
+    bubbles?: boolean;
+}
+
+type Listener = (init: CustomEventInit, default_?: number | null) => Promise<void>;
+
+interface NodeFilter {
+    acceptNode(node: Node): number;
+}
+
+declare var NodeFilter: {
+    readonly FILTER_ACCEPT: 1;
+    readonly SAFE: 9007199254740991;
+    readonly BIG: number;
+    readonly SMALL: number;
+};
+
+declare namespace console {
+    function log(...data: any[]): void;
+}
+"
+        );
+    }
+}
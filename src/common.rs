@@ -1,6 +1,10 @@
+use std::borrow::Cow;
+
 use weedle_derive::Weedle;
 
 use crate::literal::DefaultValue;
+use crate::lossless::Terminals;
+use crate::span::{Shift, Span, Spanned};
 use crate::tokens::{contextful_cut, Tokens};
 use crate::{term, Parse, VerboseResult};
 
@@ -8,13 +12,6 @@ pub(crate) fn is_alphanum_underscore_dash(token: char) -> bool {
     nom::AsChar::is_alphanum(token) || matches!(token, '_' | '-')
 }
 
-fn marker<'slice, 'a, S>(i: Tokens<'slice, 'a>) -> VerboseResult<Tokens<'slice, 'a>, S>
-where
-    S: ::std::default::Default,
-{
-    Ok((i, S::default()))
-}
-
 impl<'a, T: Parse<'a>> Parse<'a> for Option<T> {
     parser!(nom::combinator::opt(weedle!(T)));
 }
@@ -42,6 +39,7 @@ impl<'a, T: Parse<'a>, U: Parse<'a>, V: Parse<'a>> Parse<'a> for (T, U, V) {
 
 /// Parses `( body )`
 #[derive(Weedle, Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(impl_bound = "where T: Parse<'a>")]
 pub struct Parenthesized<T> {
     pub open_paren: term::OpenParen,
@@ -53,6 +51,7 @@ pub struct Parenthesized<T> {
 
 /// Parses `( body )`
 #[derive(Weedle, Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(impl_bound = "where T: Parse<'a>")]
 pub(crate) struct ParenthesizedNonEmpty<T> {
     #[weedle(post_check = "prevent_empty_parentheses")]
@@ -87,6 +86,7 @@ fn prevent_empty_parentheses<'slice, 'a>(
 
 /// Parses `[ body ]`
 #[derive(Weedle, Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(impl_bound = "where T: Parse<'a>")]
 pub struct Bracketed<T> {
     #[weedle(post_check = "prevent_empty_brackets")]
@@ -119,6 +119,7 @@ fn prevent_double_extended_attributes<'slice, 'a>(
 
 /// Parses `{ body }`
 #[derive(Weedle, Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(impl_bound = "where T: Parse<'a>")]
 pub struct Braced<T> {
     #[weedle(cut = "Missing body")]
@@ -131,6 +132,7 @@ pub struct Braced<T> {
 
 /// Parses `< body >`
 #[derive(Weedle, Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(impl_bound = "where T: Parse<'a>")]
 pub struct Generics<T> {
     pub open_angle: term::LessThan,
@@ -141,54 +143,132 @@ pub struct Generics<T> {
 
 /// Parses `(item1, item2, item3,...)?`
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Punctuated<T, S> {
     pub list: Vec<T>,
-    pub separator: S,
+    pub separators: Vec<S>,
 }
 
 impl<'a, T, S> Parse<'a> for Punctuated<T, S>
 where
     T: Parse<'a>,
-    S: Parse<'a> + ::std::default::Default,
+    S: Parse<'a>,
 {
     fn parse_tokens<'slice>(input: Tokens<'slice, 'a>) -> VerboseResult<Tokens<'slice, 'a>, Self> {
-        let (input, (list, separator)) = nom::sequence::tuple((
-            nom::multi::separated_list0(weedle!(S), weedle!(T)),
-            marker,
+        let (input, list) = nom::combinator::opt(nom::sequence::pair(
+            weedle!(T),
+            nom::multi::many0(nom::sequence::pair(weedle!(S), weedle!(T))),
         ))(input)?;
-        Ok((input, Self { list, separator }))
+        let mut result = Self {
+            list: vec![],
+            separators: vec![],
+        };
+        if let Some((first, rest)) = list {
+            result.list.push(first);
+            for (separator, item) in rest {
+                result.separators.push(separator);
+                result.list.push(item);
+            }
+        }
+        Ok((input, result))
+    }
+}
+
+impl<T: Spanned, S: Spanned> Spanned for Punctuated<T, S> {
+    fn span(&self) -> Span {
+        self.list.span().join(self.separators.span())
+    }
+}
+
+impl<T: Shift, S: Shift> Shift for Punctuated<T, S> {
+    fn shift(&mut self, delta: isize) {
+        self.list.shift(delta);
+        self.separators.shift(delta);
+    }
+}
+
+impl<T: Terminals, S: Terminals> Terminals for Punctuated<T, S> {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        for_each_punctuated_terminal(&self.list, &self.separators, f)
     }
 }
 
 /// Parses `item1, item2, item3, ...`
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PunctuatedNonEmpty<T, S> {
     pub list: Vec<T>,
-    pub separator: S,
+    /// Includes the optional trailing separator
+    pub separators: Vec<S>,
 }
 
 impl<'a, T, S> Parse<'a> for PunctuatedNonEmpty<T, S>
 where
     T: Parse<'a>,
-    S: Parse<'a> + ::std::default::Default,
+    S: Parse<'a>,
 {
     fn parse_tokens<'slice>(input: Tokens<'slice, 'a>) -> VerboseResult<Tokens<'slice, 'a>, Self> {
-        let (input, (list, separator)) = nom::sequence::tuple((
-            nom::sequence::terminated(
-                nom::multi::separated_list1(weedle!(S), weedle!(T)),
-                nom::combinator::opt(weedle!(S)),
-            ),
-            marker,
+        let (input, (first, rest, trailing)) = nom::sequence::tuple((
+            weedle!(T),
+            nom::multi::many0(nom::sequence::pair(weedle!(S), weedle!(T))),
+            nom::combinator::opt(weedle!(S)),
         ))(input)?;
-        Ok((input, Self { list, separator }))
+        // An item right after another one is missing its separator
+        if trailing.is_none() && weedle!(T)(input).is_ok() {
+            contextful_cut("Missing separator", weedle!(S))(input)?;
+        }
+        let mut result = Self {
+            list: vec![first],
+            separators: vec![],
+        };
+        for (separator, item) in rest {
+            result.separators.push(separator);
+            result.list.push(item);
+        }
+        result.separators.extend(trailing);
+        Ok((input, result))
+    }
+}
+
+impl<T: Spanned, S: Spanned> Spanned for PunctuatedNonEmpty<T, S> {
+    fn span(&self) -> Span {
+        self.list.span().join(self.separators.span())
+    }
+}
+
+impl<T: Shift, S: Shift> Shift for PunctuatedNonEmpty<T, S> {
+    fn shift(&mut self, delta: isize) {
+        self.list.shift(delta);
+        self.separators.shift(delta);
+    }
+}
+
+impl<T: Terminals, S: Terminals> Terminals for PunctuatedNonEmpty<T, S> {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        for_each_punctuated_terminal(&self.list, &self.separators, f)
+    }
+}
+
+fn for_each_punctuated_terminal<T: Terminals, S: Terminals>(
+    list: &[T],
+    separators: &[S],
+    f: &mut dyn FnMut(&str, Span),
+) {
+    for (i, item) in list.iter().enumerate() {
+        item.for_each_terminal(f);
+        if let Some(separator) = separators.get(i) {
+            separator.for_each_terminal(f);
+        }
     }
 }
 
 /// Represents an identifier
 ///
 /// Follows `/[_-]?[A-Za-z][0-9A-Z_a-z-]*/`
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Identifier<'a>(pub &'a str);
+#[derive(Clone)]
+pub struct Identifier<'a>(pub Cow<'a, str>, pub Span);
+
+text_terminal!(Identifier);
 
 impl<'a> Identifier<'a> {
     lexer!(nom::combinator::map(
@@ -200,7 +280,7 @@ impl<'a> Identifier<'a> {
             nom::bytes::complete::take_while1(nom::AsChar::is_alpha),
             nom::bytes::complete::take_while(is_alphanum_underscore_dash),
         ))),
-        Identifier,
+        Identifier::new,
     ));
 }
 
@@ -208,8 +288,17 @@ impl<'a> Parse<'a> for Identifier<'a> {
     parser!(eat!(Identifier));
 }
 
+/// Represents source skipped after a syntax error by [`parse_recovering`](crate::parse_recovering)
+///
+/// Holds the skipped text, including the comments and whitespace between its tokens.
+#[derive(Clone)]
+pub struct Invalid<'a>(pub Cow<'a, str>, pub Span);
+
+text_terminal!(Invalid);
+
 /// Parses rhs of an assignment expression. Ex: `= 45`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Default<'a> {
     pub assign: term!(=),
     #[weedle(cut = "Unrecognized default value")]
@@ -272,7 +361,7 @@ mod test {
         Generics<(Identifier, term!(,), Identifier)> =>
             Generics {
                 open_angle: term!(<),
-                body: (Identifier("one"), term!(,), Identifier("two")),
+                body: (Identifier::new("one"), term!(,), Identifier::new("two")),
                 close_angle: term!(>),
             }
     });
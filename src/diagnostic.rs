@@ -0,0 +1,297 @@
+//! Renders errors with the source line they point at, in the style of rustc.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::diagnostic::Diagnostic;
+//!
+//! let source = "interface Window {\n  readonly attribute Storage;\n};\n";
+//! let err = weedle::parse(source).unwrap_err();
+//!
+//! assert_eq!(
+//!     Diagnostic::from(&err).render("window.webidl", source),
+//!     "\
+//! error: Missing name
+//!  --> window.webidl:2:29
+//!   |
+//! 2 |   readonly attribute Storage;
+//!   |                             ^ expected identifier
+//!   |
+//!   = note: while parsing AttributeInterfaceMember
+//!   = note: while parsing InterfaceDefinition
+//! "
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::error::ExpectedList;
+use crate::span::{LineColumn, Span};
+use crate::Error;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        }
+    }
+}
+
+/// A message about a span of the source
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    /// Written next to the underline of the span
+    pub label: Option<String>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+            label: None,
+            notes: vec![],
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(message, span)
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Renders as plain text
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        self.render_with(file_name, source, &Style::PLAIN)
+    }
+
+    /// Renders with ANSI escape codes for terminals
+    pub fn render_ansi(&self, file_name: &str, source: &str) -> String {
+        self.render_with(file_name, source, &Style::ansi(self.severity))
+    }
+
+    fn render_with(&self, file_name: &str, source: &str, style: &Style) -> String {
+        let span_start = char_boundary(source, self.span.start);
+        let start = LineColumn::from_offset(source, span_start);
+        let line_start = source[..span_start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[line_start..]
+            .find('\n')
+            .map_or(source.len(), |i| line_start + i);
+        let line = source[line_start..line_end].trim_end_matches('\r');
+
+        // Keeps tabs so that the underline stays aligned with the source line
+        let padding: String = line
+            .chars()
+            .take(start.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        // The span can come from another source, or an edited one
+        let underline_end = char_boundary(source, self.span.end).clamp(span_start, line_end);
+        let underline_len = source[span_start..underline_end].chars().count().max(1);
+
+        let line_number = start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let Style {
+            severity,
+            gutter: gutter_color,
+            bold,
+            reset,
+        } = style;
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{severity}{}{reset}{bold}: {}{reset}",
+            self.severity.name(),
+            self.message
+        );
+        let _ = writeln!(
+            out,
+            "{gutter}{gutter_color}-->{reset} {file_name}:{}:{}",
+            start.line, start.column
+        );
+        let _ = writeln!(out, "{gutter} {gutter_color}|{reset}");
+        let _ = writeln!(out, "{gutter_color}{line_number} |{reset} {line}");
+        let _ = write!(
+            out,
+            "{gutter} {gutter_color}|{reset} {padding}{severity}{}",
+            "^".repeat(underline_len)
+        );
+        if let Some(label) = &self.label {
+            let _ = write!(out, " {label}");
+        }
+        let _ = writeln!(out, "{reset}");
+        if !self.notes.is_empty() {
+            let _ = writeln!(out, "{gutter} {gutter_color}|{reset}");
+            for note in &self.notes {
+                let _ = writeln!(
+                    out,
+                    "{gutter} {gutter_color}={reset} {bold}note{reset}: {note}"
+                );
+            }
+        }
+        out
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(err: &Error) -> Self {
+        let mut diagnostic = Diagnostic::error(err.kind().message(), err.span());
+        if !err.expected().is_empty() {
+            diagnostic.label = Some(ExpectedList(err.expected()).to_string());
+        }
+        for context in err.contexts() {
+            diagnostic.notes.push(format!("while parsing {context}"));
+        }
+        diagnostic
+    }
+}
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+struct Style {
+    severity: &'static str,
+    gutter: &'static str,
+    bold: &'static str,
+    reset: &'static str,
+}
+
+impl Style {
+    const PLAIN: Style = Style {
+        severity: "",
+        gutter: "",
+        bold: "",
+        reset: "",
+    };
+
+    fn ansi(severity: Severity) -> Style {
+        Style {
+            severity: severity.color(),
+            gutter: BLUE,
+            bold: BOLD,
+            reset: RESET,
+        }
+    }
+}
+
+/// Returns the last char boundary of `source` at or before `offset`
+fn char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_underline_token() {
+        let source = "interface A {\n\tattribute long long;\n};";
+        let diagnostic = Diagnostic::error("Some error", Span::new(25, 29)).with_label("here");
+        assert_eq!(
+            diagnostic.render("a.webidl", source),
+            "error: Some error
+ --> a.webidl:2:12
+  |
+2 | \tattribute long long;
+  | \t          ^^^^ here
+"
+        );
+    }
+
+    #[test]
+    fn should_render_end_of_input() {
+        let source = "interface A {";
+        let err = crate::parse(source).unwrap_err();
+        assert_eq!(
+            Diagnostic::from(&err).render("a.webidl", source),
+            "error: Unrecognized member definition
+ --> a.webidl:1:14
+  |
+1 | interface A {
+  |              ^ expected `}`
+  |
+  = note: while parsing InterfaceDefinition
+"
+        );
+    }
+
+    #[test]
+    fn should_clamp_span_to_source() {
+        let diagnostic = Diagnostic::error("Some error", Span::new(50, 60));
+        assert_eq!(
+            diagnostic.render("a.webidl", "short"),
+            "error: Some error
+ --> a.webidl:1:6
+  |
+1 | short
+  |      ^
+"
+        );
+
+        let diagnostic = Diagnostic::error("Some error", Span::new(2, 3));
+        assert_eq!(
+            diagnostic.render("a.webidl", "\"é\""),
+            "error: Some error
+ --> a.webidl:1:2
+  |
+1 | \"é\"
+  |  ^
+"
+        );
+    }
+
+    #[test]
+    fn should_render_warning_with_colors() {
+        let source = "typedef long L;";
+        let diagnostic = Diagnostic::warning("Unused", Span::new(13, 14)).with_note("a note");
+        assert_eq!(
+            diagnostic.render_ansi("a.webidl", source),
+            "\x1b[1;33mwarning\x1b[0m\x1b[1m: Unused\x1b[0m
+ \x1b[1;34m-->\x1b[0m a.webidl:1:14
+  \x1b[1;34m|\x1b[0m
+\x1b[1;34m1 |\x1b[0m typedef long L;
+  \x1b[1;34m|\x1b[0m              \x1b[1;33m^\x1b[0m
+  \x1b[1;34m|\x1b[0m
+  \x1b[1;34m=\x1b[0m \x1b[1mnote\x1b[0m: a note
+"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! The grammar extensions accepted by the parser.
+//!
+//! [`crate::parse`] accepts standard WebIDL, and the string, decimal and integer values of
+//! extended attributes that other parsers accept too. [`crate::parse_with`] takes
+//! [`ParseOptions`] to switch each extension on or off, starting from the set of a [`Dialect`]:
+//!
+//! - string, decimal and integer values of extended attributes, e.g. `[Reflect="for"]`
+//! - legacy keywords removed from WebIDL: `A implements B;` statements, `exception`
+//!   definitions and `legacyiterable<T>;` declarations, see the [`legacy`](crate::legacy)
+//!   nodes
+//! - relaxed identifiers, which may start with an underscore followed by a digit, e.g. `_0`
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::dialect::{Dialect, ParseOptions};
+//!
+//! let source = "[Reflect=\"for\"] interface A {};";
+//! assert!(weedle::parse_with(source, &ParseOptions::new(Dialect::Blink)).is_ok());
+//! assert!(weedle::parse_with(source, &ParseOptions::new(Dialect::WebIdl)).is_err());
+//!
+//! let options = ParseOptions {
+//!     legacy_keywords: true,
+//!     ..ParseOptions::new(Dialect::WebIdl)
+//! };
+//! assert!(weedle::parse_with("Node implements EventTarget;", &options).is_ok());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::tokens::Tokens;
+use crate::VerboseResult;
+
+/// A flavour of WebIDL, with the extensions its sources use
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Dialect {
+    /// Standard WebIDL, without any extension
+    WebIdl,
+    /// The IDL files of Chromium, with values of any kind in extended attributes
+    Blink,
+    /// The IDL files of Firefox, which also keep some legacy keywords
+    Gecko,
+    /// The UDL files of uniffi, which also use relaxed identifiers
+    Uniffi,
+}
+
+impl Dialect {
+    /// The name of the dialect, e.g. on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Dialect::WebIdl => "webidl",
+            Dialect::Blink => "blink",
+            Dialect::Gecko => "gecko",
+            Dialect::Uniffi => "uniffi",
+        }
+    }
+}
+
+impl FromStr for Dialect {
+    type Err = UnknownDialect;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [
+            Dialect::WebIdl,
+            Dialect::Blink,
+            Dialect::Gecko,
+            Dialect::Uniffi,
+        ]
+        .into_iter()
+        .find(|dialect| dialect.name() == name)
+        .ok_or_else(|| UnknownDialect(name.to_owned()))
+    }
+}
+
+/// The error for a name that is not the one of a [`Dialect`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownDialect(pub String);
+
+impl fmt::Display for UnknownDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown dialect `{}`, expected `webidl`, `blink`, `gecko` or `uniffi`",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownDialect {}
+
+/// Configures the grammar accepted by [`crate::parse_with`]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ParseOptions {
+    /// Accepts `[Name="string"]`, `[Name=1.5]` and `[Name=1]`, and their lists
+    pub extended_attribute_values: bool,
+    /// Accepts `implements` statements, `exception` definitions and `legacyiterable`
+    /// declarations
+    pub legacy_keywords: bool,
+    /// Accepts identifiers starting with an underscore followed by a digit
+    pub relaxed_identifiers: bool,
+}
+
+impl ParseOptions {
+    /// Returns the options accepting the extensions of the dialect
+    pub fn new(dialect: Dialect) -> Self {
+        let (extended_attribute_values, legacy_keywords, relaxed_identifiers) = match dialect {
+            Dialect::WebIdl => (false, false, false),
+            Dialect::Blink => (true, false, false),
+            Dialect::Gecko => (true, true, false),
+            Dialect::Uniffi => (true, false, true),
+        };
+        ParseOptions {
+            extended_attribute_values,
+            legacy_keywords,
+            relaxed_identifiers,
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    /// Returns the options of [`crate::parse`], which accepts the values of extended
+    /// attributes only
+    fn default() -> Self {
+        ParseOptions::new(Dialect::Blink)
+    }
+}
+
+impl From<Dialect> for ParseOptions {
+    fn from(dialect: Dialect) -> Self {
+        ParseOptions::new(dialect)
+    }
+}
+
+/// Runs `parser` only when the options enable the extension, failing without expecting
+/// anything otherwise, so that the errors are the ones of the grammar without it
+pub(crate) fn extension<'slice, 'a, O>(
+    enabled: fn(&ParseOptions) -> bool,
+    mut parser: impl nom::Parser<Tokens<'slice, 'a>, O, ParseError<Tokens<'slice, 'a>>>,
+) -> impl FnMut(Tokens<'slice, 'a>) -> VerboseResult<Tokens<'slice, 'a>, O>
+where
+    'a: 'slice,
+{
+    move |input: Tokens<'slice, 'a>| {
+        if !enabled(input.2) {
+            return Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                input,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+        parser.parse(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ErrorKind;
+
+    fn parse(source: &str, dialect: Dialect) -> Result<crate::Definitions<'_>, crate::Error> {
+        crate::parse_with(source, &ParseOptions::new(dialect))
+    }
+
+    #[test]
+    fn should_reject_extensions_in_strict_mode() {
+        let err = parse("[Reflect=\"for\"] interface A {};", Dialect::WebIdl).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnrecognizedExtendedAttribute);
+        assert_eq!(err.offset(), 8);
+
+        let err = parse("[Size=(1, 2)] interface A {};", Dialect::WebIdl).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnrecognizedExtendedAttribute);
+
+        let err = parse("A implements B;", Dialect::WebIdl).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnrecognizedTokens);
+
+        let err = parse("interface A { legacyiterable<long>; };", Dialect::WebIdl).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnrecognizedMemberDefinition);
+
+        let err = parse("interface _0 {};", Dialect::WebIdl).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingName);
+    }
+
+    #[test]
+    fn should_accept_the_extensions_of_dialects() {
+        let values = "[Reflect=\"for\", Size=(1, 2), Ratio=0.5] interface A {};";
+        assert!(parse(values, Dialect::Blink).is_ok());
+        assert!(parse(values, Dialect::Gecko).is_ok());
+        assert!(crate::parse(values).is_ok());
+
+        let legacy = "
+            exception DOMException { const unsigned short INDEX_SIZE_ERR = 1; };
+            interface A { legacyiterable<long>; };
+            A implements B;
+        ";
+        assert_eq!(parse(legacy, Dialect::Gecko).unwrap().len(), 3);
+        assert!(parse(legacy, Dialect::Blink).is_err());
+        assert!(crate::parse(legacy).is_err());
+
+        let relaxed = "dictionary _2D { long _0; };";
+        assert!(parse(relaxed, Dialect::Uniffi).is_ok());
+        assert!(parse(relaxed, Dialect::Gecko).is_err());
+    }
+
+    #[test]
+    fn should_recover_and_print_with_options() {
+        let options = ParseOptions::new(Dialect::Uniffi);
+        let source = "dictionary _2D { long _0; long _1 };\ntypedef long _3;\n";
+
+        let (definitions, errors) = crate::parse_recovering_with(source, &options);
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(errors.len(), 1);
+
+        let (file, errors) = crate::lossless::parse_recovering_with(source, &options);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(file.to_string(), source);
+        assert!(crate::lossless::parse_with(source, &options).is_err());
+        assert!(crate::lossless::parse_with("typedef long _3;", &options).is_ok());
+    }
+
+    #[test]
+    fn should_parse_dialect_names() {
+        assert_eq!("gecko".parse(), Ok(Dialect::Gecko));
+        assert_eq!("webidl".parse(), Ok(Dialect::WebIdl));
+        let err = "chromium".parse::<Dialect>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown dialect `chromium`, expected `webidl`, `blink`, `gecko` or `uniffi`"
+        );
+    }
+
+    #[test]
+    fn should_keep_legacy_keywords_as_identifiers() {
+        let source = "
+            interface A {
+                undefined implements();
+                undefined legacyiterable();
+            };
+            dictionary exception {};
+        ";
+        assert_eq!(
+            parse(source, Dialect::Gecko).unwrap(),
+            parse(source, Dialect::WebIdl).unwrap()
+        );
+    }
+}
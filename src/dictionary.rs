@@ -1,16 +1,29 @@
 use weedle_derive::Weedle;
 
 use crate::attribute::ExtendedAttributeList;
-use crate::common::{Default, Identifier};
+use crate::common::{Default, Identifier, Invalid};
 use crate::types::Type;
 
 /// Parses dictionary members
 pub type DictionaryMembers<'a> = Vec<DictionaryMember<'a>>;
 
+/// Parses one of the dictionary member variants
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(recover = "Member")]
+#[allow(clippy::large_enum_variant)]
+pub enum DictionaryMember<'a> {
+    Field(FieldDictionaryMember<'a>),
+    /// Source skipped by [`parse_recovering`](crate::parse_recovering)
+    #[weedle(skip)]
+    Invalid(Invalid<'a>),
+}
+
 /// Parses dictionary member `[attributes]? required? type identifier ( = default )?;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
-pub struct DictionaryMember<'a> {
+pub struct FieldDictionaryMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub required: Option<term!(required)>,
     pub type_: Type<'a>,
@@ -29,7 +42,7 @@ mod test {
 
     test!(should_parse_dictionary_member { "required long num;" =>
         "";
-        DictionaryMember;
+        FieldDictionaryMember;
         attributes.is_none();
         required.is_some();
         identifier.0 == "num";
@@ -38,7 +51,7 @@ mod test {
 
     test!(should_parse_required_dictionary_member { "long num = 5;" =>
         "";
-        DictionaryMember;
+        FieldDictionaryMember;
         attributes.is_none();
         required.is_none();
         identifier.0 == "num";
@@ -0,0 +1,406 @@
+use std::fmt;
+
+use nom::{InputLength, Offset};
+
+use crate::span::{LineColumn, Span};
+use crate::tokens::Tokens;
+
+macro_rules! generate_error_kinds {
+    ($( $(#[$attr:meta])* $variant:ident => $label:expr, )*) => {
+        /// The reason of a parse error
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        #[non_exhaustive]
+        pub enum ErrorKind {
+            $( $(#[$attr])* $variant, )*
+        }
+
+        impl ErrorKind {
+            /// Returns the message of the error, e.g. "Missing semicolon"
+            pub fn message(&self) -> &'static str {
+                match self {
+                    $( ErrorKind::$variant => $label, )*
+                }
+            }
+
+            fn from_label(label: &str) -> Option<Self> {
+                match label {
+                    $( $label => Some(ErrorKind::$variant), )*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+generate_error_kinds! {
+    MissingArgumentList => "Missing argument list",
+    MissingBody => "Missing body",
+    MissingEqualSign => "Missing equal sign",
+    MissingInheritanceName => "Missing name for inheritance",
+    MissingName => "Missing name",
+    MissingSemicolon => "Missing semicolon",
+    MissingSeparator => "Missing separator",
+    MissingUnionMemberType => "Expected a union member type",
+    InvalidUnionMemberType => "Expected a union member type that is not an `any` or `Promise`",
+    TooFewUnionMemberTypes => "A union type needs at least two member types",
+    NonEmptyDefaultDictionary => "Default dictionary value must be empty",
+    NonEmptyDefaultSequence => "Default sequence value must be empty",
+    InvalidRecordKey => "Record key must be one of: ByteString, DOMString, USVString with no extended attributes",
+    NullableAnyOrPromise => "`any` and Promise cannot be nullable",
+    EmptyParentheses => "Unexpected empty parentheses",
+    EmptyBrackets => "Unexpected empty brackets",
+    DoubleExtendedAttributeLists => "Illegal double extended attribute lists, consider merging them",
+    ReadOnlyInheritedAttribute => "Inherited attributes cannot be read-only, as this form is only used to override the setter of the ancestor's attribute",
+    WritableNamespaceAttribute => "Non-readonly attributes are not allowed in namespaces",
+    UnrecognizedArgument => "Unrecognized argument",
+    UnrecognizedConstType => "Unrecognized const type",
+    UnrecognizedConstValue => "Unrecognized const value",
+    UnrecognizedDefaultValue => "Unrecognized default value",
+    UnrecognizedExtendedAttribute => "Unrecognized extended attribute",
+    UnrecognizedMemberDefinition => "Unrecognized member definition",
+    UnrecognizedReturnType => "Unrecognized return type",
+    UnrecognizedType => "Unrecognized type",
+    UnrecognizedTypeParameter => "Unrecognized type parameter",
+    /// The input has tokens after the last definition that cannot start a definition
+    UnrecognizedTokens => "Unrecognized tokens",
+    /// The input has a character that cannot start any token
+    UnrecognizedCharacter => "Unrecognized character",
+    /// A node parsed by [`Parse::parse`](crate::Parse::parse) does not match the input
+    UnexpectedToken => "Unexpected token",
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// A token that would have been accepted at the position of an error
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Expected {
+    /// A keyword or a punctuation, e.g. `interface` or `;`
+    Keyword(&'static str),
+    Identifier,
+    Integer,
+    Decimal,
+    String,
+    Eof,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Keyword(keyword) => write!(f, "`{keyword}`"),
+            Expected::Identifier => f.write_str("identifier"),
+            Expected::Integer => f.write_str("integer"),
+            Expected::Decimal => f.write_str("decimal"),
+            Expected::String => f.write_str("string"),
+            Expected::Eof => f.write_str("end of input"),
+        }
+    }
+}
+
+/// The error returned when the input is not valid WebIDL
+///
+/// ### Example
+///
+/// ```
+/// use weedle::{ErrorKind, Expected};
+///
+/// let err = weedle::parse("interface Window {}").unwrap_err();
+///
+/// assert_eq!(err.kind(), ErrorKind::MissingSemicolon);
+/// assert_eq!(err.offset(), 19);
+/// assert_eq!((err.location().line, err.location().column), (1, 20));
+/// assert_eq!(err.expected(), &[Expected::Keyword(";")]);
+/// assert_eq!(err.to_string(), "Missing semicolon at line 1, column 20, expected `;`");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    span: Span,
+    location: LineColumn,
+    expected: Vec<Expected>,
+    contexts: Vec<&'static str>,
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte offset of the error in the input
+    pub fn offset(&self) -> usize {
+        self.span.start
+    }
+
+    /// Returns the span of the token where the error happened, which is empty at the end of
+    /// the input
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn location(&self) -> LineColumn {
+        self.location
+    }
+
+    /// Returns the tokens that would have been accepted at the error position, if known
+    pub fn expected(&self) -> &[Expected] {
+        &self.expected
+    }
+
+    /// Returns the names of the nodes being parsed when the error happened, innermost first,
+    /// e.g. `["AttributeInterfaceMember", "InterfaceDefinition"]`
+    pub fn contexts(&self) -> &[&'static str] {
+        &self.contexts
+    }
+}
+
+impl Error {
+    pub(crate) fn with_location(mut self, source: &str) -> Self {
+        self.location = self.span.start_location(source);
+        self
+    }
+
+    /// Moves the error by `delta` bytes after an edit of `source` before it
+    pub(crate) fn shifted(mut self, delta: isize, source: &str) -> Self {
+        self.span = self.span.shifted(delta);
+        self.with_location(source)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.location.line, self.location.column
+        )?;
+        if !self.expected.is_empty() {
+            write!(f, ", {}", ExpectedList(&self.expected))?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes e.g. "expected `,`, `;` or identifier"
+pub(crate) struct ExpectedList<'a>(pub(crate) &'a [Expected]);
+
+impl fmt::Display for ExpectedList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("expected ")?;
+        for (i, expected) in self.0.iter().enumerate() {
+            match i {
+                0 => {}
+                _ if i + 1 == self.0.len() => f.write_str(" or ")?,
+                _ => f.write_str(", ")?,
+            }
+            write!(f, "{expected}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The error type of the internal parsers.
+///
+/// Unlike `nom::error::VerboseError`, it merges the expected tokens of alternatives failing
+/// at the same position.
+#[derive(Debug)]
+pub struct ParseError<I> {
+    input: I,
+    expected: Vec<Expected>,
+    /// Innermost first
+    contexts: Vec<(I, &'static str)>,
+}
+
+impl<I> ParseError<I> {
+    pub(crate) fn expected(input: I, expected: Expected) -> Self {
+        ParseError {
+            input,
+            expected: vec![expected],
+            contexts: vec![],
+        }
+    }
+}
+
+impl<I: Copy + InputLength> ParseError<I> {
+    /// Replaces the error by the context of a cut, keeping the expected tokens if the inner
+    /// parser failed at the start
+    pub(crate) fn cut(input: I, context: &'static str, inner: Self) -> Self {
+        let expected = if inner.input.input_len() == input.input_len() {
+            inner.expected
+        } else {
+            vec![]
+        };
+        ParseError {
+            contexts: vec![(input, context)],
+            input,
+            expected,
+        }
+    }
+}
+
+impl<I: InputLength> nom::error::ParseError<I> for ParseError<I> {
+    fn from_error_kind(input: I, _kind: nom::error::ErrorKind) -> Self {
+        ParseError {
+            input,
+            expected: vec![],
+            contexts: vec![],
+        }
+    }
+
+    fn append(_input: I, _kind: nom::error::ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn or(self, mut other: Self) -> Self {
+        // Prefers the error that got further, with less input left
+        match self.input.input_len().cmp(&other.input.input_len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => {
+                let mut expected = self.expected;
+                for item in other.expected {
+                    if !expected.contains(&item) {
+                        expected.push(item);
+                    }
+                }
+                other.expected = expected;
+                other
+            }
+        }
+    }
+}
+
+impl<I> nom::error::ContextError<I> for ParseError<I> {
+    fn add_context(input: I, context: &'static str, mut other: Self) -> Self {
+        other.contexts.push((input, context));
+        other
+    }
+}
+
+/// The input types of the parsers, which can locate their next token in the source
+pub trait Position {
+    fn span_in(&self, source: &str) -> Span;
+}
+
+impl Position for &str {
+    fn span_in(&self, source: &str) -> Span {
+        let start = source.offset(self);
+        let len = self.chars().next().map_or(0, char::len_utf8);
+        Span::new(start, start + len)
+    }
+}
+
+impl Position for Tokens<'_, '_> {
+    fn span_in(&self, source: &str) -> Span {
+        self.0
+            .first()
+            .map_or(Span::new(source.len(), source.len()), |token| token.span)
+    }
+}
+
+impl<I: Position> ParseError<I> {
+    fn into_error(self, source: &str) -> Error {
+        let mut kind = None;
+        let mut contexts = vec![];
+        for (input, context) in &self.contexts {
+            match ErrorKind::from_label(context) {
+                Some(found) if kind.is_none() => kind = Some((found, input.span_in(source))),
+                _ => contexts.push(*context),
+            }
+        }
+        let (kind, span) =
+            kind.unwrap_or_else(|| (ErrorKind::UnexpectedToken, self.input.span_in(source)));
+
+        Error {
+            kind,
+            span,
+            location: span.start_location(source),
+            expected: self.expected,
+            contexts,
+        }
+    }
+}
+
+impl ParseError<Tokens<'_, '_>> {
+    /// Converts into the public error type, with a location to be set by
+    /// [`Error::with_location`]
+    pub(crate) fn into_unlocated_error(self) -> Error {
+        // The span of tokens does not depend on the source
+        self.into_error("")
+    }
+}
+
+/// Converts an error of the internal parsers on `source` into the public error type
+pub(crate) fn convert_error<I: Position>(source: &str, err: nom::Err<ParseError<I>>) -> Error {
+    match err {
+        nom::Err::Error(e) | nom::Err::Failure(e) => e.into_error(source),
+        nom::Err::Incomplete(_) => unreachable!("The parsers only run on complete input"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Parse;
+
+    #[test]
+    fn should_report_cut_context() {
+        let err = crate::parse("interface A {\n  attribute long ;\n};").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingName);
+        assert_eq!(err.offset(), 31);
+        assert_eq!(
+            err.location(),
+            LineColumn {
+                line: 2,
+                column: 18
+            }
+        );
+        assert_eq!(
+            err.contexts(),
+            &["AttributeInterfaceMember", "InterfaceDefinition"]
+        );
+    }
+
+    #[test]
+    fn should_merge_expected_tokens() {
+        let err = crate::parse("interface A { attribute long a = 1; };").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingSemicolon);
+        assert_eq!(
+            err.to_string(),
+            "Missing semicolon at line 1, column 32, expected `;`"
+        );
+
+        let err = crate::parse("callback C = undefined (long a b);").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnrecognizedArgument);
+        assert_eq!(err.expected(), &[Expected::Keyword(")")]);
+
+        let err = crate::parse("typedef () T;").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidUnionMemberType);
+        assert!(err.expected().contains(&Expected::Keyword("(")));
+        assert!(err.expected().contains(&Expected::Identifier));
+        assert!(err.expected().contains(&Expected::Keyword("sequence")));
+    }
+
+    #[test]
+    fn should_report_unrecognized_character() {
+        let err = crate::parse("interface é {};").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnrecognizedCharacter);
+        assert_eq!(err.offset(), 10);
+    }
+
+    #[test]
+    fn should_report_unexpected_token() {
+        let err = crate::types::Type::parse("  sequence<>").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnrecognizedTypeParameter);
+        assert_eq!(err.offset(), 11);
+
+        let err = crate::common::Identifier::parse(" ;").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedToken);
+        assert_eq!(err.offset(), 1);
+        assert!(err.expected().contains(&Expected::Identifier));
+    }
+}
@@ -0,0 +1,547 @@
+//! Prints parsed nodes back as canonical WebIDL.
+//!
+//! Any node can be formatted, from [`Definitions`](crate::Definitions) down to a single
+//! [`Type`](crate::types::Type) or [`DefaultValue`](crate::literal::DefaultValue).
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::fmt::{format, FormatOptions};
+//!
+//! let parsed = weedle::parse("[Exposed=Window]interface Window{readonly attribute Storage sessionStorage;};").unwrap();
+//!
+//! assert_eq!(
+//!     format(&parsed, &FormatOptions::default()),
+//!     "[Exposed=Window]\ninterface Window {\n  readonly attribute Storage sessionStorage;\n};\n"
+//! );
+//! ```
+
+use crate::lossless::{split_trivia, SourceFile, Terminals};
+use crate::span::Span;
+use crate::term::Keyword;
+
+/// When to put each extended attribute of a list on its own line
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum AttributeWrap {
+    Never,
+    /// Only when the definition or member would exceed the line width otherwise
+    Overflow,
+    Always,
+}
+
+/// Configures the output of the formatter
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FormatOptions {
+    /// The string for one level of indentation
+    pub indent: String,
+    /// The maximum line width before wrapping extended attributes
+    pub line_width: usize,
+    pub attribute_wrap: AttributeWrap,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: "  ".to_owned(),
+            line_width: 80,
+            attribute_wrap: AttributeWrap::Overflow,
+        }
+    }
+}
+
+/// Formats a node, ending with a newline if the node ends with a semicolon.
+///
+/// ### Example
+///
+/// ```
+/// use weedle::fmt::{format, FormatOptions};
+/// use weedle::types::Type;
+/// use weedle::Parse;
+///
+/// let (_, type_) = Type::parse("record < DOMString,sequence<long> >").unwrap();
+///
+/// assert_eq!(format(&type_, &FormatOptions::default()), "record<DOMString, sequence<long>>");
+/// ```
+pub fn format<T: Terminals + ?Sized>(node: &T, options: &FormatOptions) -> String {
+    let mut tokens = vec![];
+    node.for_each_terminal(&mut |text, _| {
+        tokens.push(FormatToken {
+            text: text.to_owned(),
+            trivia: "",
+        })
+    });
+    let mut out = Printer::new(options).print(&tokens, "");
+    if out.ends_with(';') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Formats a lossless source file, keeping the comments and single blank lines between members
+///
+/// ### Example
+///
+/// ```
+/// use weedle::fmt::{format_source_file, FormatOptions};
+///
+/// let file = weedle::lossless::parse("
+///     // The dictionary
+///     dictionary A {   long b; // The member
+///     };
+/// ").unwrap();
+///
+/// assert_eq!(
+///     format_source_file(&file, &FormatOptions::default()),
+///     "// The dictionary\ndictionary A {\n  long b; // The member\n};\n"
+/// );
+/// ```
+pub fn format_source_file(file: &SourceFile<'_>, options: &FormatOptions) -> String {
+    let mut tokens = vec![];
+    file.definitions.for_each_terminal(&mut |text, span: Span| {
+        tokens.push(FormatToken {
+            text: text.to_owned(),
+            trivia: file.leading_trivia(span).unwrap_or(""),
+        })
+    });
+    let mut out = Printer::new(options).print(&tokens, file.trailing_trivia());
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+struct FormatToken<'a> {
+    text: String,
+    trivia: &'a str,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Brace {
+    Body,
+    Literal,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Bracket {
+    ExtendedAttributes {
+        wrap: bool,
+        break_after: bool,
+        paren_depth: usize,
+    },
+    Literal,
+}
+
+struct Printer<'o> {
+    options: &'o FormatOptions,
+    out: String,
+    pending_newlines: usize,
+    indent: usize,
+    braces: Vec<Brace>,
+    brackets: Vec<Bracket>,
+    paren_depth: usize,
+    generic_depth: usize,
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_alphabetic() || c == '_') && Keyword::match_word(text).is_none()
+}
+
+impl<'o> Printer<'o> {
+    fn new(options: &'o FormatOptions) -> Self {
+        Self {
+            options,
+            out: String::new(),
+            pending_newlines: 0,
+            indent: 0,
+            braces: vec![],
+            brackets: vec![],
+            paren_depth: 0,
+            generic_depth: 0,
+        }
+    }
+
+    fn newline(&mut self, count: usize) {
+        if !self.out.is_empty() {
+            self.pending_newlines = self.pending_newlines.max(count);
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.pending_newlines > 0
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.pending_newlines > 0 {
+            for _ in 0..self.pending_newlines {
+                self.out.push('\n');
+            }
+            for _ in 0..self.indent {
+                self.out.push_str(&self.options.indent);
+            }
+            self.pending_newlines = 0;
+        } else if space && !self.out.is_empty() {
+            self.out.push(' ');
+        }
+        self.out.push_str(text);
+    }
+
+    fn in_body(&self) -> bool {
+        self.braces.last() == Some(&Brace::Body)
+            && self.brackets.is_empty()
+            && self.paren_depth == 0
+            && self.generic_depth == 0
+    }
+
+    /// Whether `=` belongs to an extended attribute, e.g. `Exposed=Window`
+    fn in_attribute_assignment(&self) -> bool {
+        matches!(
+            self.brackets.last(),
+            Some(Bracket::ExtendedAttributes { paren_depth, .. }) if *paren_depth == self.paren_depth
+        )
+    }
+
+    fn write_comments(&mut self, trivia: &str) {
+        let (comments, newlines_after) = split_trivia(trivia);
+        for comment in &comments {
+            if comment.newlines_before == 0 && !self.out.is_empty() {
+                // A comment following a token on the same line
+                let pending = std::mem::take(&mut self.pending_newlines);
+                self.out.push(' ');
+                self.out.push_str(comment.text);
+                self.pending_newlines = pending;
+            } else {
+                self.newline(comment.newlines_before.min(2));
+                self.write(comment.text, false);
+            }
+            if comment.text.starts_with("//") {
+                self.newline(1);
+            }
+        }
+        if !comments.is_empty() && newlines_after > 0 {
+            self.newline(newlines_after.min(2));
+        } else if self.pending_newlines > 0 && newlines_after > 1 {
+            // Keeps a single blank line between members
+            self.newline(2);
+        }
+    }
+
+    /// Estimates the width of the definition or member starting with the extended attributes
+    /// at `start` when printed on the current line, or only of the extended attributes
+    fn flat_width(&self, tokens: &[FormatToken], start: usize, attributes_only: bool) -> usize {
+        let mut width = self.indent * self.options.indent.len();
+        let mut depth = 0usize;
+        for token in &tokens[start..] {
+            width += token.text.len() + 1;
+            match token.text.as_str() {
+                "(" | "[" | "<" => depth += 1,
+                ")" | "]" | ">" => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 && attributes_only {
+                        break;
+                    }
+                }
+                ";" | "{" if depth == 0 => break,
+                _ => {}
+            }
+        }
+        width
+    }
+
+    fn print(mut self, tokens: &[FormatToken], trailing_trivia: &str) -> String {
+        for (i, token) in tokens.iter().enumerate() {
+            self.write_comments(token.trivia);
+
+            let text = token.text.as_str();
+            let previous = i.checked_sub(1).map(|i| tokens[i].text.as_str());
+            let next = tokens.get(i + 1).map(|token| token.text.as_str());
+            let space_after_previous = !matches!(previous, Some("(" | "[" | "<") | None);
+
+            match text {
+                "{" => {
+                    if previous == Some("=") {
+                        self.write(text, true);
+                        self.braces.push(Brace::Literal);
+                    } else {
+                        self.write(text, true);
+                        self.braces.push(Brace::Body);
+                        if next != Some("}") {
+                            self.indent += 1;
+                            self.newline(1);
+                        }
+                    }
+                }
+                "}" => {
+                    if self.braces.pop() == Some(Brace::Body) && previous != Some("{") {
+                        self.indent -= 1;
+                        self.newline(1);
+                    }
+                    self.write(text, false);
+                }
+                ";" => {
+                    self.write(text, false);
+                    if self.paren_depth == 0 && self.brackets.is_empty() {
+                        self.newline(if self.braces.is_empty() { 2 } else { 1 });
+                    }
+                }
+                "[" if previous == Some("=") => {
+                    self.write(text, true);
+                    self.brackets.push(Bracket::Literal);
+                }
+                "[" => {
+                    let leading = self.at_line_start();
+                    // Definitions always start on the line after their extended attributes,
+                    // members only when they would not fit otherwise.
+                    let (wrap, break_after) = match self.options.attribute_wrap {
+                        _ if !leading => (false, false),
+                        AttributeWrap::Never => (false, self.braces.is_empty()),
+                        AttributeWrap::Overflow => {
+                            let width = self.options.line_width;
+                            if self.flat_width(tokens, i, false) <= width {
+                                (false, self.braces.is_empty())
+                            } else {
+                                (self.flat_width(tokens, i, true) > width, true)
+                            }
+                        }
+                        AttributeWrap::Always => (true, true),
+                    };
+                    self.write(text, space_after_previous);
+                    self.brackets.push(Bracket::ExtendedAttributes {
+                        wrap,
+                        break_after,
+                        paren_depth: self.paren_depth,
+                    });
+                    if wrap {
+                        self.indent += 1;
+                        self.newline(1);
+                    }
+                }
+                "]" => match self.brackets.pop() {
+                    Some(Bracket::ExtendedAttributes {
+                        wrap, break_after, ..
+                    }) => {
+                        if wrap {
+                            self.indent -= 1;
+                            self.newline(1);
+                        }
+                        self.write(text, false);
+                        if break_after {
+                            self.newline(1);
+                        }
+                    }
+                    _ => self.write(text, false),
+                },
+                "," => {
+                    self.write(text, false);
+                    let wrapped_attributes = matches!(
+                        self.brackets.last(),
+                        Some(Bracket::ExtendedAttributes { wrap: true, paren_depth, .. })
+                            if *paren_depth == self.paren_depth
+                    );
+                    if wrapped_attributes || self.in_body() {
+                        self.newline(1);
+                    }
+                }
+                "(" => {
+                    let attached = match previous {
+                        Some(previous) => {
+                            is_identifier(previous)
+                                || matches!(previous, "constructor" | "includes" | ">" | "=")
+                                    && (previous != "=" || self.in_attribute_assignment())
+                        }
+                        None => false,
+                    };
+                    self.write(text, !attached && space_after_previous);
+                    self.paren_depth += 1;
+                }
+                ")" => {
+                    self.write(text, false);
+                    self.paren_depth -= 1;
+                }
+                "<" => {
+                    self.write(text, false);
+                    self.generic_depth += 1;
+                }
+                ">" => {
+                    self.write(text, false);
+                    self.generic_depth -= 1;
+                }
+                "?" | "..." => self.write(text, false),
+                "=" if self.in_attribute_assignment() => self.write(text, false),
+                _ => {
+                    let attached = previous == Some("=") && self.in_attribute_assignment()
+                        || previous == Some("{") && self.braces.last() == Some(&Brace::Literal)
+                        || previous == Some("[") && self.brackets.last() == Some(&Bracket::Literal);
+                    self.write(text, !attached && space_after_previous);
+                }
+            }
+        }
+
+        self.write_comments(trailing_trivia);
+        let mut out = self.out;
+        out.truncate(out.trim_end().len());
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Parse;
+
+    fn format_default<T: Terminals>(node: &T) -> String {
+        format(node, &FormatOptions::default())
+    }
+
+    #[test]
+    fn should_format_interface() {
+        let parsed = crate::parse(
+            "interface  A:B{const long C=0x1;constructor(optional long x=3,long...rest);getter DOMString?(unsigned long index);attribute(long or DOMString)?u;async iterable<long>(long a);};",
+        )
+        .unwrap();
+        assert_eq!(
+            format_default(&parsed),
+            "interface A : B {
+  const long C = 0x1;
+  constructor(optional long x = 3, long... rest);
+  getter DOMString? (unsigned long index);
+  attribute (long or DOMString)? u;
+  async iterable<long>(long a);
+};
+"
+        );
+    }
+
+    #[test]
+    fn should_format_definitions() {
+        let parsed = crate::parse(
+            "enum E{\"a\",\"b\"};dictionary D{required sequence<long>s;record<DOMString,any>r={};long l=-Infinity;sequence<long>e=[];};A includes B;typedef([Clamp]long or float)T;callback C=undefined(DOMString s);interface Empty{};",
+        )
+        .unwrap();
+        assert_eq!(
+            format_default(&parsed),
+            r#"enum E {
+  "a",
+  "b"
+};
+
+dictionary D {
+  required sequence<long> s;
+  record<DOMString, any> r = {};
+  long l = -Infinity;
+  sequence<long> e = [];
+};
+
+A includes B;
+
+typedef ([Clamp] long or float) T;
+
+callback C = undefined (DOMString s);
+
+interface Empty {};
+"#
+        );
+    }
+
+    #[test]
+    fn should_format_extended_attributes() {
+        let parsed = crate::parse(
+            "[Exposed=(Window,Worker),LegacyFactoryFunction=Image(optional long x=1)]interface A{[SameObject,PutForwards=b]readonly attribute B c;undefined f([Clamp]long x);};",
+        )
+        .unwrap();
+        assert_eq!(
+            format_default(&parsed),
+            "[
+  Exposed=(Window, Worker),
+  LegacyFactoryFunction=Image(optional long x = 1)
+]
+interface A {
+  [SameObject, PutForwards=b] readonly attribute B c;
+  undefined f([Clamp] long x);
+};
+"
+        );
+    }
+
+    #[test]
+    fn should_format_with_options() {
+        let parsed =
+            crate::parse("[Exposed=Window] interface A { [SameObject] readonly attribute B c; };")
+                .unwrap();
+        let options = FormatOptions {
+            indent: "\t".to_owned(),
+            line_width: 80,
+            attribute_wrap: AttributeWrap::Always,
+        };
+        assert_eq!(
+            format(&parsed, &options),
+            "[\n\tExposed=Window\n]\ninterface A {\n\t[\n\t\tSameObject\n\t]\n\treadonly attribute B c;\n};\n"
+        );
+
+        let options = FormatOptions {
+            line_width: 30,
+            ..Default::default()
+        };
+        assert_eq!(
+            format(&parsed, &options),
+            "[Exposed=Window]\ninterface A {\n  [SameObject]\n  readonly attribute B c;\n};\n"
+        );
+
+        let options = FormatOptions {
+            line_width: 10,
+            attribute_wrap: AttributeWrap::Never,
+            ..Default::default()
+        };
+        assert_eq!(
+            format(&parsed, &options),
+            "[Exposed=Window]\ninterface A {\n  [SameObject] readonly attribute B c;\n};\n"
+        );
+    }
+
+    #[test]
+    fn should_format_nodes() {
+        let (_, parsed) =
+            crate::attribute::ExtendedAttributeList::parse("[ Exposed = * , Throws ]").unwrap();
+        assert_eq!(format_default(&parsed), "[Exposed=*, Throws]");
+
+        let (_, parsed) = crate::literal::DefaultValue::parse("\"value\"").unwrap();
+        assert_eq!(format_default(&parsed), "\"value\"");
+    }
+
+    #[test]
+    fn should_keep_comments() {
+        let file = crate::lossless::parse(
+            "/**
+ * Doc
+ */
+interface A {
+  // First
+
+  attribute long a; /* trailing */
+  attribute long b;
+
+
+  attribute long c;
+}; // end
+// last
+",
+        )
+        .unwrap();
+        assert_eq!(
+            format_source_file(&file, &FormatOptions::default()),
+            "/**
+ * Doc
+ */
+interface A {
+  // First
+
+  attribute long a; /* trailing */
+  attribute long b;
+
+  attribute long c;
+}; // end
+
+// last
+"
+        );
+    }
+}
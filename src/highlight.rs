@@ -0,0 +1,303 @@
+//! Syntax highlighting of WebIDL source text.
+//!
+//! [`highlight`] classifies the tokens and comments of a source, which [`to_html`] renders with
+//! CSS classes and [`to_ansi`] with terminal colours. Sources with syntax errors are highlighted
+//! too, as only the lexer is used.
+//!
+//! ### Example
+//!
+//! ```
+//! let html = weedle::highlight::to_html("typedef long Count; // <= 10\n");
+//!
+//! assert_eq!(
+//!     html,
+//!     "<span class=\"webidl-keyword\">typedef</span> \
+//!      <span class=\"webidl-type\">long</span> \
+//!      <span class=\"webidl-identifier\">Count</span>\
+//!      <span class=\"webidl-punctuation\">;</span> \
+//!      <span class=\"webidl-comment\">// &lt;= 10</span>\n"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::lexer::{Lexer, Terminal};
+use crate::span::Span;
+use crate::term::Keyword;
+
+/// The class of a piece of source text
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Class {
+    /// A keyword, e.g. `interface` or `readonly`
+    Keyword,
+    /// A keyword naming a type, e.g. `DOMString` or `unsigned`
+    TypeKeyword,
+    Identifier,
+    String,
+    /// An integer or decimal literal, including `Infinity`, `-Infinity` and `NaN`
+    Number,
+    Comment,
+    Punctuation,
+}
+
+impl Class {
+    /// The CSS class of the spans written by [`to_html`]
+    pub fn css_class(self) -> &'static str {
+        match self {
+            Class::Keyword => "webidl-keyword",
+            Class::TypeKeyword => "webidl-type",
+            Class::Identifier => "webidl-identifier",
+            Class::String => "webidl-string",
+            Class::Number => "webidl-number",
+            Class::Comment => "webidl-comment",
+            Class::Punctuation => "webidl-punctuation",
+        }
+    }
+
+    /// The escape sequence setting the colour written by [`to_ansi`], if any
+    fn ansi(self) -> Option<&'static str> {
+        match self {
+            Class::Keyword => Some(MAGENTA),
+            Class::TypeKeyword => Some(CYAN),
+            Class::Identifier => Some(BLUE),
+            Class::String => Some(GREEN),
+            Class::Number => Some(YELLOW),
+            Class::Comment => Some(GRAY),
+            Class::Punctuation => None,
+        }
+    }
+}
+
+const MAGENTA: &str = "\x1b[1;35m";
+const CYAN: &str = "\x1b[36m";
+const BLUE: &str = "\x1b[34m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const GRAY: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// A piece of source text, with its class if it has one
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Highlight {
+    pub class: Option<Class>,
+    pub span: Span,
+}
+
+/// Splits the source into pieces covering it in order, merging the adjacent pieces of the same
+/// class
+///
+/// Whitespace and characters that are not WebIDL tokens have no class. After a character the
+/// lexer does not recognize, the rest of the source is a single piece without a class.
+pub fn highlight(source: &str) -> Vec<Highlight> {
+    let mut highlights = vec![];
+    let mut end = 0;
+    for token in Lexer::new(source) {
+        let Ok(token) = token else {
+            break;
+        };
+        highlight_trivia(token.trivia, end, &mut highlights);
+        push(&mut highlights, classify(&token.value), token.span);
+        end = token.span.end;
+    }
+    push(&mut highlights, None, Span::new(end, source.len()));
+    highlights
+}
+
+/// Renders the source as HTML, wrapping the classified pieces in `<span>` elements with the
+/// [`Class::css_class`] of their class
+///
+/// The text is escaped, and is meant to be placed in a `<pre>` element.
+pub fn to_html(source: &str) -> String {
+    let mut html = String::with_capacity(source.len() * 2);
+    for piece in highlight(source) {
+        let text = escape_html(&source[piece.span.range()]);
+        match piece.class {
+            Some(class) => {
+                let _ = write!(html, "<span class=\"{}\">{text}</span>", class.css_class());
+            }
+            None => html.push_str(&text),
+        }
+    }
+    html
+}
+
+/// Renders the source with ANSI colour escape sequences, for terminals
+pub fn to_ansi(source: &str) -> String {
+    let mut ansi = String::with_capacity(source.len() * 2);
+    for piece in highlight(source) {
+        let text = &source[piece.span.range()];
+        match piece.class.and_then(Class::ansi) {
+            Some(colour) => {
+                let _ = write!(ansi, "{colour}{text}{RESET}");
+            }
+            None => ansi.push_str(text),
+        }
+    }
+    ansi
+}
+
+fn classify(terminal: &Terminal<'_>) -> Option<Class> {
+    match terminal {
+        Terminal::Keyword(keyword) if keyword.is_punctuation() => Some(Class::Punctuation),
+        Terminal::Keyword(Keyword::Infinity(_) | Keyword::NegInfinity(_) | Keyword::NaN(_)) => {
+            Some(Class::Number)
+        }
+        Terminal::Keyword(keyword) if is_type_keyword(keyword) => Some(Class::TypeKeyword),
+        Terminal::Keyword(_) => Some(Class::Keyword),
+        Terminal::Integer(_) | Terminal::Decimal(_) => Some(Class::Number),
+        Terminal::Identifier(_) => Some(Class::Identifier),
+        Terminal::String(_) => Some(Class::String),
+        Terminal::Other(_) | Terminal::Eof(_) => None,
+    }
+}
+
+fn is_type_keyword(keyword: &Keyword) -> bool {
+    matches!(
+        keyword,
+        Keyword::Any(_)
+            | Keyword::Undefined(_)
+            | Keyword::Boolean(_)
+            | Keyword::Byte(_)
+            | Keyword::Octet(_)
+            | Keyword::Short(_)
+            | Keyword::Long(_)
+            | Keyword::Unsigned(_)
+            | Keyword::Float(_)
+            | Keyword::Double(_)
+            | Keyword::Unrestricted(_)
+            | Keyword::Bigint(_)
+            | Keyword::ByteString(_)
+            | Keyword::DOMString(_)
+            | Keyword::USVString(_)
+            | Keyword::Object(_)
+            | Keyword::Symbol(_)
+            | Keyword::Sequence(_)
+            | Keyword::Record(_)
+            | Keyword::Promise(_)
+            | Keyword::FrozenArray(_)
+            | Keyword::ObservableArray(_)
+            | Keyword::ArrayBuffer(_)
+            | Keyword::DataView(_)
+            | Keyword::Int8Array(_)
+            | Keyword::Int16Array(_)
+            | Keyword::Int32Array(_)
+            | Keyword::Uint8Array(_)
+            | Keyword::Uint16Array(_)
+            | Keyword::Uint32Array(_)
+            | Keyword::Uint8ClampedArray(_)
+            | Keyword::BigInt64Array(_)
+            | Keyword::BigUint64Array(_)
+            | Keyword::Float32Array(_)
+            | Keyword::Float64Array(_)
+    )
+}
+
+/// Splits the whitespace and comments before a token, starting at the byte offset `start`
+fn highlight_trivia(trivia: &str, start: usize, highlights: &mut Vec<Highlight>) {
+    let mut offset = start;
+    let mut rest = trivia;
+    while !rest.is_empty() {
+        let (class, len) = if rest.starts_with("//") {
+            (Some(Class::Comment), rest.find('\n').unwrap_or(rest.len()))
+        } else if rest.starts_with("/*") {
+            (
+                Some(Class::Comment),
+                rest.find("*/").map_or(rest.len(), |i| i + 2),
+            )
+        } else {
+            (None, rest.find('/').unwrap_or(rest.len()))
+        };
+        push(highlights, class, Span::new(offset, offset + len));
+        offset += len;
+        rest = &rest[len..];
+    }
+}
+
+fn push(highlights: &mut Vec<Highlight>, class: Option<Class>, span: Span) {
+    if span.is_empty() {
+        return;
+    }
+    match highlights.last_mut() {
+        Some(last) if last.class == class && last.span.end == span.start => {
+            last.span.end = span.end;
+        }
+        _ => highlights.push(Highlight { class, span }),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn classes(source: &str) -> Vec<(Option<Class>, &str)> {
+        highlight(source)
+            .into_iter()
+            .map(|piece| (piece.class, &source[piece.span.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn should_classify_tokens() {
+        let source = "/* A */ readonly attribute unsigned long? a = -Infinity; // b\n";
+
+        assert_eq!(
+            classes(source),
+            [
+                (Some(Class::Comment), "/* A */"),
+                (None, " "),
+                (Some(Class::Keyword), "readonly"),
+                (None, " "),
+                (Some(Class::Keyword), "attribute"),
+                (None, " "),
+                (Some(Class::TypeKeyword), "unsigned"),
+                (None, " "),
+                (Some(Class::TypeKeyword), "long"),
+                (Some(Class::Punctuation), "?"),
+                (None, " "),
+                (Some(Class::Identifier), "a"),
+                (None, " "),
+                (Some(Class::Punctuation), "="),
+                (None, " "),
+                (Some(Class::Number), "-Infinity"),
+                (Some(Class::Punctuation), ";"),
+                (None, " "),
+                (Some(Class::Comment), "// b"),
+                (None, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_cover_sources_with_errors() {
+        let source = "enum E { \"a\", 0x1F }; $ é // c";
+
+        let pieces = classes(source);
+        assert_eq!(
+            pieces.iter().map(|(_, text)| *text).collect::<String>(),
+            source
+        );
+        assert!(pieces.contains(&(Some(Class::String), "\"a\"")));
+        assert!(pieces.contains(&(Some(Class::Number), "0x1F")));
+        assert!(pieces.contains(&(Some(Class::Punctuation), "};")));
+        assert_eq!(pieces.last(), Some(&(None, " $ é // c")));
+    }
+
+    #[test]
+    fn should_render_ansi() {
+        assert_eq!(to_ansi("long x;"), "\x1b[36mlong\x1b[0m \x1b[34mx\x1b[0m;");
+    }
+}
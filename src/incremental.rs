@@ -0,0 +1,545 @@
+//! Incremental reparsing of an edited source.
+//!
+//! [`IncrementalParse`] keeps the source together with its tokens and definitions. After an
+//! edit, the tokens are lexed again from before the edit until they line up with the previous
+//! ones (see [`crate::lexer::relex`]). The top-level definitions are then parsed again from the
+//! first one the edit may change, until the parse reaches the start of a previous definition in
+//! the tokens that were not lexed again: the definitions from there are reused with their spans
+//! moved, as parsing them again would give them back. The result is the one
+//! [`crate::parse_recovering`] gives for the whole source.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::incremental::IncrementalParse;
+//!
+//! let mut parse = IncrementalParse::new("interface A {};\ninterface B {};\ninterface C {};\n");
+//! let reparsed = parse.edit(27..27, " : A");
+//!
+//! assert_eq!(parse.source(), "interface A {};\ninterface B : A {};\ninterface C {};\n");
+//! assert_eq!(reparsed, 1..2);
+//! assert!(parse.errors().is_empty());
+//! ```
+
+use std::ops::Range;
+
+use crate::dialect::ParseOptions;
+use crate::lexer::{lex_with, relex_tokens, LexedToken, Terminal, Token};
+use crate::owned::IntoOwned;
+use crate::recovery::Recovery;
+use crate::span::{Shift, Span};
+use crate::tokens::Tokens;
+use crate::{Definition, Definitions, Error, Parse};
+
+/// A source and its definitions, parsed with recovery, that can be updated after edits
+#[derive(Clone, Debug)]
+pub struct IncrementalParse {
+    source: String,
+    options: ParseOptions,
+    definitions: Definitions<'static>,
+    errors: Vec<Error>,
+    /// The tokens of the source, or none when the next edit must parse the whole source again
+    tokens: Vec<Lexeme>,
+    /// The index of the token after every definition
+    ends: Vec<usize>,
+    /// The index of the token after the last one read to parse every definition, which may
+    /// lie past its end when its parse backtracked
+    reads: Vec<usize>,
+    /// The number of errors up to the end of every definition
+    error_ends: Vec<usize>,
+}
+
+/// A token kept without the source, to lex the source again after an edit
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Lexeme {
+    value: Terminal<'static>,
+    trivia_start: usize,
+    span: Span,
+}
+
+impl Lexeme {
+    fn new(token: &Token<'_>) -> Self {
+        Lexeme {
+            value: token.value.kind(),
+            trivia_start: token.trivia_start(),
+            span: token.span,
+        }
+    }
+}
+
+impl LexedToken for Lexeme {
+    fn value(&self) -> &Terminal<'_> {
+        &self.value
+    }
+
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn trivia_start(&self) -> usize {
+        self.trivia_start
+    }
+}
+
+/// The definitions parsed from a token, by [`parse_from`]
+struct Parsed<'a> {
+    definitions: Definitions<'a>,
+    ends: Vec<usize>,
+    reads: Vec<usize>,
+    errors: Vec<Error>,
+    /// The number of errors up to the end of every definition
+    error_ends: Vec<usize>,
+    /// The previous definition the parse reached, or `None` at the end of the source
+    reached: Option<usize>,
+}
+
+impl IncrementalParse {
+    pub fn new(source: impl Into<String>) -> Self {
+        IncrementalParse::with_options(source, ParseOptions::default())
+    }
+
+    /// Parses the source like [`crate::parse_recovering_with`], and the edits with the same
+    /// options
+    pub fn with_options(source: impl Into<String>, options: ParseOptions) -> Self {
+        let mut parse = IncrementalParse {
+            source: source.into(),
+            options,
+            definitions: vec![],
+            errors: vec![],
+            tokens: vec![],
+            ends: vec![],
+            reads: vec![],
+            error_ends: vec![],
+        };
+        parse.parse_all();
+        parse
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn definitions(&self) -> &[Definition<'static>] {
+        &self.definitions
+    }
+
+    /// Returns the syntax errors, as [`crate::parse_recovering`] would
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    /// Replaces the byte `range` of the source with `text`, and parses the definitions that
+    /// the edit may have changed again
+    ///
+    /// Returns the indices of the definitions that were parsed again; the other ones were kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or does not lie on character boundaries, like
+    /// [`String::replace_range`].
+    pub fn edit(&mut self, range: Range<usize>, text: &str) -> Range<usize> {
+        self.source.replace_range(range.clone(), text);
+        if self.tokens.is_empty() {
+            self.parse_all();
+            return 0..self.definitions.len();
+        }
+        let delta = text.len() as isize - range.len() as isize;
+
+        let relexed =
+            match relex_tokens(&self.source, &self.tokens, range, text.len(), &self.options) {
+                Ok(relexed) => relexed,
+                Err(err) => {
+                    self.fail(err);
+                    return 0..0;
+                }
+            };
+        let tokens = relexed.tokens;
+        // The definitions from the first one whose parse read a token lexed again are parsed
+        // again
+        let first = self
+            .reads
+            .iter()
+            .position(|&read| read > relexed.lexed.start)
+            .unwrap_or(self.definitions.len());
+        let start = match first {
+            0 => 0,
+            _ => self.ends[first - 1],
+        };
+        // The tokens from `lexed.end` are the previous ones from `reused`
+        let shift = relexed.lexed.end as isize - relexed.reused as isize;
+        let ends = &self.ends;
+        let previous_start = |index: usize| {
+            if index < relexed.lexed.end {
+                return None;
+            }
+            let index = index.wrapping_add_signed(-shift);
+            let definition = match index {
+                0 => 0,
+                _ => ends.binary_search(&index).ok()? + 1,
+            };
+            (definition >= first).then_some(definition)
+        };
+        let parsed = match parse_from(&self.source, &tokens, start, &self.options, previous_start) {
+            Some(parsed) => parsed,
+            None => {
+                self.parse_all();
+                return 0..self.definitions.len();
+            }
+        };
+
+        // The errors of the definitions that were parsed again, or of all the following ones
+        // at the end of the source
+        let error_start = match first {
+            0 => 0,
+            _ => self.error_ends[first - 1],
+        };
+        let (replaced, error_end) = match parsed.reached {
+            Some(reached) => (
+                first..reached,
+                match reached {
+                    0 => 0,
+                    _ => self.error_ends[reached - 1],
+                },
+            ),
+            None => (first..self.definitions.len(), self.errors.len()),
+        };
+        let added = parsed.errors.len() as isize - (error_end - error_start) as isize;
+
+        for definition in &mut self.definitions[replaced.end..] {
+            definition.shift(delta);
+        }
+        for end in &mut self.ends[replaced.end..] {
+            *end = end.wrapping_add_signed(shift);
+        }
+        for read in &mut self.reads[replaced.end..] {
+            *read = read.wrapping_add_signed(shift);
+        }
+        for end in &mut self.error_ends[replaced.end..] {
+            *end = end.wrapping_add_signed(added);
+        }
+        let source = &self.source;
+        let moved: Vec<_> = self
+            .errors
+            .drain(error_end..)
+            .map(|err| err.shifted(delta, source))
+            .collect();
+        self.errors.truncate(error_start);
+        self.errors.extend(parsed.errors);
+        self.errors.extend(moved);
+
+        let count = parsed.definitions.len();
+        self.definitions
+            .splice(replaced.clone(), parsed.definitions.into_owned());
+        self.ends.splice(replaced.clone(), parsed.ends);
+        self.reads.splice(replaced.clone(), parsed.reads);
+        self.error_ends.splice(
+            replaced,
+            parsed.error_ends.into_iter().map(|end| error_start + end),
+        );
+
+        let lexemes: Vec<_> = tokens[relexed.lexed.clone()]
+            .iter()
+            .map(Lexeme::new)
+            .collect();
+        self.tokens
+            .splice(relexed.lexed.start..relexed.reused, lexemes);
+        for lexeme in &mut self.tokens[relexed.lexed.end..] {
+            lexeme.trivia_start = lexeme.trivia_start.wrapping_add_signed(delta);
+            lexeme.span = lexeme.span.shifted(delta);
+        }
+
+        first..first + count
+    }
+
+    /// Lexes and parses the whole source
+    fn parse_all(&mut self) {
+        let source = std::mem::take(&mut self.source);
+        match lex_with(&source, &self.options) {
+            Ok(tokens) => self.parse_tokens(&source, &tokens),
+            Err(err) => self.fail(crate::error::convert_error(&source, err)),
+        }
+        self.source = source;
+    }
+
+    /// Parses all the tokens of the source
+    fn parse_tokens<'a>(&mut self, source: &'a str, tokens: &[Token<'a>]) {
+        match parse_from(source, tokens, 0, &self.options, |_| None) {
+            Some(parsed) => {
+                self.definitions = parsed.definitions.into_owned();
+                self.errors = parsed.errors;
+                self.ends = parsed.ends;
+                self.reads = parsed.reads;
+                self.error_ends = parsed.error_ends;
+                self.tokens = tokens.iter().map(Lexeme::new).collect();
+            }
+            None => {
+                // Like `parse_recovering`, a parse that cannot skip some tokens leaves no
+                // definition, and the next edit parses the whole source again
+                let (definitions, errors) =
+                    crate::parse_tokens_recovering(source, tokens, &self.options);
+                self.definitions = definitions.into_owned();
+                self.errors = errors;
+                self.ends.clear();
+                self.reads.clear();
+                self.error_ends.clear();
+                self.tokens.clear();
+            }
+        }
+    }
+
+    /// Records a lexing error, which leaves no definition like in `parse_recovering`
+    fn fail(&mut self, err: Error) {
+        self.definitions.clear();
+        self.errors = vec![err];
+        self.ends.clear();
+        self.reads.clear();
+        self.error_ends.clear();
+        self.tokens.clear();
+    }
+}
+
+/// Parses the definitions from the token `start`, until the end of the source or until a token
+/// where `previous_start` returns the index of the previous definition starting there
+///
+/// Returns `None` when some tokens cannot be parsed, where `parse_recovering` would give no
+/// definition.
+fn parse_from<'a>(
+    source: &'a str,
+    tokens: &[Token<'a>],
+    start: usize,
+    options: &ParseOptions,
+    previous_start: impl Fn(usize) -> Option<usize>,
+) -> Option<Parsed<'a>> {
+    let mut parsed = Parsed {
+        definitions: vec![],
+        ends: vec![],
+        reads: vec![],
+        errors: vec![],
+        error_ends: vec![],
+        reached: None,
+    };
+    let mut index = start;
+    loop {
+        if let Some(reached) = previous_start(index) {
+            parsed.reached = Some(reached);
+            return Some(parsed);
+        }
+        if matches!(tokens[index].value, Terminal::Eof(_)) {
+            return Some(parsed);
+        }
+        let recovery = Recovery::default();
+        let (rest, definition) =
+            Definition::parse_tokens(Tokens(&tokens[index..], Some(&recovery), options)).ok()?;
+        index = tokens.len() - rest.0.len();
+        parsed.definitions.push(definition);
+        parsed.ends.push(index);
+        parsed
+            .reads
+            .push(tokens.len() - recovery.unread().unwrap_or(tokens.len() - index));
+        parsed.errors.extend(recovery.into_errors(source));
+        parsed.error_ends.push(parsed.errors.len());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::lossless::Terminals;
+    use crate::span::Spanned;
+
+    /// Checks that the incremental parse gives the definitions, the spans and the errors of
+    /// a parse of the whole source
+    fn assert_same_as_full_parse(parse: &IncrementalParse) {
+        let (definitions, errors) = crate::parse_recovering_with(parse.source(), &parse.options);
+        assert_eq!(parse.definitions(), definitions.as_slice());
+
+        let terminals = |definitions: &[Definition<'_>]| {
+            let mut terminals = vec![];
+            for definition in definitions {
+                definition
+                    .for_each_terminal(&mut |text, span| terminals.push((text.to_owned(), span)));
+            }
+            terminals
+        };
+        assert_eq!(terminals(parse.definitions()), terminals(&definitions));
+        assert_eq!(parse.errors(), errors.as_slice());
+
+        // The tokens kept for the next edit are the ones of the source
+        if !parse.tokens.is_empty() {
+            let tokens = lex_with(parse.source(), &parse.options).unwrap();
+            let lexemes: Vec<_> = tokens.iter().map(Lexeme::new).collect();
+            assert_eq!(parse.tokens, lexemes);
+        }
+        for (definition, &end) in parse.definitions().iter().zip(&parse.ends) {
+            assert_eq!(definition.span().end, parse.tokens[end - 1].span.end);
+        }
+        if !parse.tokens.is_empty() {
+            assert_eq!(parse.error_ends.last().copied().unwrap_or(0), errors.len());
+        }
+    }
+
+    const SOURCE: &str = "\
+interface A {
+  attribute long a;
+};
+
+dictionary B {
+  long b;
+};
+
+// A comment
+enum C { \"c\" };
+";
+
+    #[test]
+    fn should_reparse_the_edited_definition() {
+        let mut parse = IncrementalParse::new(SOURCE);
+
+        let offset = SOURCE.find("long b").unwrap();
+        let reparsed = parse.edit(offset..offset + 4, "unsigned long long");
+        assert_eq!(reparsed, 1..2);
+        assert_same_as_full_parse(&parse);
+
+        let offset = parse.source().find("attribute").unwrap();
+        let reparsed = parse.edit(offset..offset, "readonly ");
+        assert_eq!(reparsed, 0..1);
+        assert_same_as_full_parse(&parse);
+    }
+
+    #[test]
+    fn should_reparse_with_the_options() {
+        let options = ParseOptions::new(Dialect::Gecko);
+        let mut parse =
+            IncrementalParse::with_options("interface A {};\nA implements B;\n", options);
+        assert!(parse.errors().is_empty());
+
+        let offset = parse.source().find("B;").unwrap();
+        let reparsed = parse.edit(offset..offset + 1, "C");
+        assert_eq!(reparsed, 1..2);
+        assert!(parse.errors().is_empty());
+        assert!(matches!(parse.definitions()[1], Definition::Implements(_)));
+        assert_same_as_full_parse(&parse);
+    }
+
+    #[test]
+    fn should_reparse_definitions_joined_by_an_edit() {
+        let mut parse = IncrementalParse::new(SOURCE);
+
+        // Removes the semicolon ending `A`, which then runs into `B`
+        let offset = SOURCE.find("};").unwrap();
+        let reparsed = parse.edit(offset + 1..offset + 2, "");
+        assert_eq!(reparsed.start, 0);
+        assert_eq!(parse.errors().len(), 1);
+        assert_same_as_full_parse(&parse);
+
+        let reparsed = parse.edit(offset + 1..offset + 1, ";");
+        assert_eq!(reparsed.start, 0);
+        assert!(parse.errors().is_empty());
+        assert_same_as_full_parse(&parse);
+    }
+
+    #[test]
+    fn should_reparse_text_typed_between_definitions() {
+        let mut parse = IncrementalParse::new(SOURCE);
+
+        let offset = SOURCE.find("// A comment").unwrap();
+        for (i, c) in "typedef long D;\n".char_indices() {
+            parse.edit(offset + i..offset + i, &c.to_string());
+            assert_same_as_full_parse(&parse);
+        }
+        assert_eq!(parse.definitions().len(), 4);
+        assert!(parse.errors().is_empty());
+    }
+
+    #[test]
+    fn should_reparse_comments_spanning_definitions() {
+        let mut parse = IncrementalParse::new(SOURCE);
+
+        parse.edit(0..0, "/*");
+        assert_same_as_full_parse(&parse);
+        let offset = parse.source().find("// A comment").unwrap();
+        parse.edit(offset..offset, "*/");
+        assert_same_as_full_parse(&parse);
+        assert_eq!(parse.definitions().len(), 1);
+
+        parse.edit(0..2, "");
+        assert_same_as_full_parse(&parse);
+        assert_eq!(parse.definitions().len(), 3);
+    }
+
+    #[test]
+    fn should_reparse_edits_anywhere_in_a_file() {
+        let source = std::fs::read_to_string("tests/defs/streams.webidl").unwrap();
+        let mut parse = IncrementalParse::new(source.as_str());
+
+        // Types and deletes back text that joins, splits or comments out definitions
+        for offset in (0..source.len()).step_by(97) {
+            for text in ["", "};", "(", "/*", "*/", "\""] {
+                let deleted = source[offset..offset + 1].to_owned();
+                parse.edit(offset..offset + 1, text);
+                assert_same_as_full_parse(&parse);
+                parse.edit(offset..offset + text.len(), &deleted);
+                assert_same_as_full_parse(&parse);
+            }
+        }
+        assert_eq!(parse.source(), source);
+    }
+
+    #[test]
+    fn should_reparse_until_the_tokens_line_up() {
+        let source = "interface A { attribute long a; };\ninterface B { long f(); };\n\
+                      dictionary C { long c; };\ntypedef long D;\nenum E { \"e\" };\n";
+        let mut parse = IncrementalParse::new(source);
+
+        // Closes the parenthesis of `f` too early, which changes where the errors end
+        parse.edit(85..88, ")");
+        assert_same_as_full_parse(&parse);
+        assert_eq!(parse.errors().last().unwrap().location().line, 5);
+    }
+
+    #[test]
+    fn should_reparse_random_edits_like_a_full_parse() {
+        let source = std::fs::read_to_string("tests/defs/dom.webidl").unwrap();
+        let mut parse = IncrementalParse::new(source.as_str());
+
+        let texts = [
+            "",
+            "};",
+            "(",
+            ")",
+            "/*",
+            "*/",
+            "\"",
+            "{",
+            ";",
+            "[",
+            "]",
+            "interface X {",
+            "long",
+            "\n",
+        ];
+        // A xorshift generator, so that failures can be reproduced
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize % bound
+        };
+        for _ in 0..300 {
+            let source = parse.source();
+            let mut start = random(source.len() + 1);
+            while !source.is_char_boundary(start) {
+                start -= 1;
+            }
+            let mut end = (start + random(9)).min(source.len());
+            while !source.is_char_boundary(end) {
+                end += 1;
+            }
+            let text = texts[random(texts.len())];
+            parse.edit(start..end, text);
+            assert_same_as_full_parse(&parse);
+        }
+    }
+}
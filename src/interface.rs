@@ -2,7 +2,8 @@ use weedle_derive::Weedle;
 
 use crate::argument::ArgumentList;
 use crate::attribute::ExtendedAttributeList;
-use crate::common::{Generics, Identifier, Parenthesized};
+use crate::common::{Generics, Identifier, Invalid, Parenthesized};
+use crate::legacy::LegacyIterableInterfaceMember;
 use crate::members::{
     AttributeInterfaceMember, ConstMember, OperationInterfaceMember, RegularOperationMember,
 };
@@ -13,7 +14,8 @@ pub type InterfaceMembers<'a> = Vec<InterfaceMember<'a>>;
 pub type CallbackInterfaceMembers<'a> = Vec<CallbackInterfaceMember<'a>>;
 
 /// Parses inheritance clause `: identifier`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Inheritance<'a> {
     pub colon: term!(:),
     #[weedle(cut = "Missing name for inheritance")]
@@ -24,6 +26,7 @@ pub struct Inheritance<'a> {
 ///
 /// (( )) means ( ) chars
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct ConstructorInterfaceMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -35,6 +38,7 @@ pub struct ConstructorInterfaceMember<'a> {
 
 /// Parses an iterable declaration `[attributes]? iterable<attributedtype>;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SingleTypedIterable<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub iterable: term!(iterable),
@@ -45,6 +49,7 @@ pub struct SingleTypedIterable<'a> {
 
 /// Parses an iterable declaration `[attributes]? iterable<attributedtype, attributedtype>;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DoubleTypedIterable<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub iterable: term!(iterable),
@@ -55,7 +60,9 @@ pub struct DoubleTypedIterable<'a> {
 
 /// Parses an iterable declaration `[attributes]? (iterable<attributedtype> | iterable<attributedtype, attributedtype>) ;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
+#[allow(clippy::large_enum_variant)]
 pub enum IterableInterfaceMember<'a> {
     Single(SingleTypedIterable<'a>),
     Double(DoubleTypedIterable<'a>),
@@ -63,6 +70,7 @@ pub enum IterableInterfaceMember<'a> {
 
 /// Parses an async iterable declaration `[attributes]? async iterable<attributedtype> (( args ))? ;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SingleTypedAsyncIterable<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub async_iterable: (term!(async), term!(iterable)),
@@ -74,6 +82,7 @@ pub struct SingleTypedAsyncIterable<'a> {
 
 /// Parses an async iterable declaration `[attributes]? async iterable<attributedtype, attributedtype> (( args ))? ;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DoubleTypedAsyncIterable<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub async_iterable: (term!(async), term!(iterable)),
@@ -85,7 +94,9 @@ pub struct DoubleTypedAsyncIterable<'a> {
 
 /// Parses an async iterable declaration `[attributes]? async (iterable<attributedtype> | iterable<attributedtype, attributedtype>) (( args ))? ;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
+#[allow(clippy::large_enum_variant)]
 pub enum AsyncIterableInterfaceMember<'a> {
     Single(SingleTypedAsyncIterable<'a>),
     Double(DoubleTypedAsyncIterable<'a>),
@@ -93,6 +104,7 @@ pub enum AsyncIterableInterfaceMember<'a> {
 
 /// Parses an maplike declaration `[attributes]? readonly? maplike<attributedtype, attributedtype>;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct MaplikeInterfaceMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -104,6 +116,7 @@ pub struct MaplikeInterfaceMember<'a> {
 }
 
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct SetlikeInterfaceMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -116,6 +129,7 @@ pub struct SetlikeInterfaceMember<'a> {
 
 /// Parses `stringifier;`
 #[derive(Weedle, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct StringifierMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -126,6 +140,8 @@ pub struct StringifierMember<'a> {
 
 /// Parses one of the interface member variants
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(recover = "Member")]
 pub enum InterfaceMember<'a> {
     Const(ConstMember<'a>),
     Attribute(AttributeInterfaceMember<'a>),
@@ -136,13 +152,23 @@ pub enum InterfaceMember<'a> {
     Maplike(MaplikeInterfaceMember<'a>),
     Setlike(SetlikeInterfaceMember<'a>),
     Stringifier(StringifierMember<'a>),
+    #[weedle(extension = "legacy_keywords")]
+    LegacyIterable(LegacyIterableInterfaceMember<'a>),
+    /// Source skipped by [`parse_recovering`](crate::parse_recovering)
+    #[weedle(skip)]
+    Invalid(Invalid<'a>),
 }
 
 /// Parses one of the interface member variants
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(recover = "Member")]
 pub enum CallbackInterfaceMember<'a> {
     Const(ConstMember<'a>),
     Operation(RegularOperationMember<'a>),
+    /// Source skipped by [`parse_recovering`](crate::parse_recovering)
+    #[weedle(skip)]
+    Invalid(Invalid<'a>),
 }
 
 #[cfg(test)]
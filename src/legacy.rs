@@ -0,0 +1,172 @@
+//! Syntax removed from WebIDL, parsed when [`ParseOptions::legacy_keywords`] is set.
+//!
+//! The legacy keywords are not keywords of WebIDL any more, so they are identifiers to the
+//! lexer and only have a meaning where these nodes start. Elsewhere, e.g. as operation names,
+//! they stay identifiers.
+//!
+//! [`ParseOptions::legacy_keywords`]: crate::dialect::ParseOptions::legacy_keywords
+
+use weedle_derive::Weedle;
+
+use crate::attribute::ExtendedAttributeList;
+use crate::common::{Braced, Generics, Identifier};
+use crate::error::ParseError;
+use crate::interface::Inheritance;
+use crate::members::ConstMember;
+use crate::tokens::Tokens;
+use crate::types::{AttributedType, Type};
+use crate::{Expected, VerboseResult};
+
+/// Parses exception members
+pub type ExceptionMembers<'a> = Vec<ExceptionMember<'a>>;
+
+// Parses an identifier spelling a legacy keyword, keeping it as the identifier
+macro_rules! legacy_keyword {
+    ($typ:ident => $keyword:expr) => {
+        #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        struct $typ<'a>(Identifier<'a>);
+
+        impl<'a> crate::Parse<'a> for $typ<'a> {
+            fn parse_tokens<'slice>(
+                input: Tokens<'slice, 'a>,
+            ) -> VerboseResult<Tokens<'slice, 'a>, Self> {
+                match eat!(Identifier)(input) {
+                    Ok((tokens, identifier)) if identifier.0 == $keyword => {
+                        Ok((tokens, $typ(identifier)))
+                    }
+                    _ => Err(nom::Err::Error(ParseError::expected(
+                        input,
+                        Expected::Keyword($keyword),
+                    ))),
+                }
+            }
+        }
+
+        impl<'a> From<$typ<'a>> for Identifier<'a> {
+            fn from(value: $typ<'a>) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+legacy_keyword!(Implements => "implements");
+legacy_keyword!(Exception => "exception");
+legacy_keyword!(LegacyIterable => "legacyiterable");
+
+/// Parses `[attributes]? identifier implements identifier;`, which was replaced by `includes`
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(context)]
+pub struct ImplementsStatementDefinition<'a> {
+    pub attributes: Option<ExtendedAttributeList<'a>>,
+    pub lhs_identifier: Identifier<'a>,
+    #[weedle(from = "Implements")]
+    pub implements: Identifier<'a>,
+    #[weedle(cut = "Missing name")]
+    pub rhs_identifier: Identifier<'a>,
+    #[weedle(cut = "Missing semicolon")]
+    pub semi_colon: term!(;),
+}
+
+/// Parses `[attributes]? exception identifier ( : inheritance )? { members };`
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(context)]
+pub struct ExceptionDefinition<'a> {
+    pub attributes: Option<ExtendedAttributeList<'a>>,
+    #[weedle(from = "Exception")]
+    pub exception: Identifier<'a>,
+    pub identifier: Identifier<'a>,
+    pub inheritance: Option<Inheritance<'a>>,
+    pub members: Braced<ExceptionMembers<'a>>,
+    #[weedle(cut = "Missing semicolon")]
+    pub semi_colon: term!(;),
+}
+
+/// Parses an exception field `[attributes]? type identifier;`
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(context)]
+pub struct ExceptionFieldMember<'a> {
+    pub attributes: Option<ExtendedAttributeList<'a>>,
+    pub type_: Type<'a>,
+    #[weedle(cut = "Missing name")]
+    pub identifier: Identifier<'a>,
+    #[weedle(cut = "Missing semicolon")]
+    pub semi_colon: term!(;),
+}
+
+/// Parses one of the exception member variants
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExceptionMember<'a> {
+    Const(ConstMember<'a>),
+    Field(ExceptionFieldMember<'a>),
+}
+
+/// Parses `[attributes]? legacyiterable<attributedtype>;`, which was replaced by `iterable`
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegacyIterableInterfaceMember<'a> {
+    pub attributes: Option<ExtendedAttributeList<'a>>,
+    #[weedle(from = "LegacyIterable")]
+    pub legacyiterable: Identifier<'a>,
+    pub generics: Generics<AttributedType<'a>>,
+    #[weedle(cut = "Missing semicolon")]
+    pub semi_colon: term!(;),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dialect::{Dialect, ParseOptions};
+    use crate::Definition;
+
+    fn parse(source: &str) -> Definition<'_> {
+        let mut definitions =
+            crate::parse_with(source, &ParseOptions::new(Dialect::Gecko)).unwrap();
+        assert_eq!(definitions.len(), 1);
+        definitions.remove(0)
+    }
+
+    #[test]
+    fn should_parse_implements_statement() {
+        let Definition::Implements(def) = parse("Node implements EventTarget;") else {
+            unreachable!()
+        };
+        assert_eq!(def.lhs_identifier.0, "Node");
+        assert_eq!(def.implements.0, "implements");
+        assert_eq!(def.rhs_identifier.0, "EventTarget");
+    }
+
+    #[test]
+    fn should_parse_exception() {
+        let Definition::Exception(def) = parse(
+            "exception FileException : Error {
+                const unsigned short NOT_FOUND_ERR = 1;
+                unsigned short code;
+            };",
+        ) else {
+            unreachable!()
+        };
+        assert_eq!(def.identifier.0, "FileException");
+        assert_eq!(def.inheritance.unwrap().identifier.0, "Error");
+        assert!(matches!(def.members.body[0], ExceptionMember::Const(_)));
+        match &def.members.body[1] {
+            ExceptionMember::Field(field) => assert_eq!(field.identifier.0, "code"),
+            member => panic!("unexpected member {member:?}"),
+        }
+    }
+
+    #[test]
+    fn should_parse_legacy_iterable() {
+        let Definition::Interface(def) = parse("interface A { legacyiterable<long>; };") else {
+            unreachable!()
+        };
+        assert!(matches!(
+            def.members.body[0],
+            crate::interface::InterfaceMember::LegacyIterable(_)
+        ));
+    }
+}
@@ -1,49 +1,207 @@
-use nom::{multi::many0, sequence::tuple, Parser};
+//! The tokens of WebIDL source text
+//!
+//! [`crate::lex`] returns all the tokens of a source, and [`Lexer`] produces them one at a time.
+//! Either way, the last token is an [`Terminal::Eof`] token holding the trivia at the end of the
+//! source, so that the trivia and the text of the tokens add up to the whole source.
+//!
+//! ### Stability
+//!
+//! [`Token`], [`Terminal`] and [`Keyword`] follow semantic versioning like the syntax tree.
+//! `Terminal` and `Keyword` are non-exhaustive, as new versions of WebIDL may add kinds of
+//! tokens and keywords in minor releases.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::lexer::{Lexer, Terminal};
+//!
+//! let kinds: Vec<_> = Lexer::new("typedef long /* comment */ Foo;")
+//!     .map(|token| {
+//!         let token = token.unwrap();
+//!         (token.trivia, token.text, matches!(token.value, Terminal::Identifier(_)))
+//!     })
+//!     .collect();
+//!
+//! assert_eq!(
+//!     kinds,
+//!     [
+//!         ("", "typedef", false),
+//!         (" ", "long", false),
+//!         (" /* comment */ ", "Foo", true),
+//!         ("", ";", false),
+//!         ("", "", false),
+//!     ]
+//! );
+//! ```
 
-use crate::common::Identifier;
-use crate::literal::{FloatValueLit, IntegerLit, StringLit};
+use std::ops::Range;
+
+use nom::{combinator::consumed, sequence::tuple, Offset, Parser};
+
+use crate::common::{is_alphanum_underscore_dash, Identifier};
+use crate::dialect::ParseOptions;
+use crate::error::{convert_error, ParseError};
+use crate::literal::{DecLit, FloatValueLit, HexLit, IntegerLit, OctLit, StringLit};
+use crate::span::Span;
 use crate::term::Keyword;
+use crate::tokens::contextful_cut;
 use crate::whitespace::sp;
+use crate::Error;
 
-pub type NomResult<'a, O> = crate::VerboseResult<&'a str, O>;
+pub(crate) type NomResult<'a, O> = crate::VerboseResult<&'a str, O>;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// The kind of a token, with its value
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
 pub enum Terminal<'a> {
+    /// A keyword or a punctuation mark
     Keyword(Keyword),
     Integer(IntegerLit<'a>),
     Decimal(FloatValueLit<'a>),
     Identifier(Identifier<'a>),
     String(StringLit<'a>),
+    /// A character that no other terminal starts with, which is a syntax error for the parser
     Other(char),
+    /// The end of the source
     Eof(()),
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+impl<'a> Terminal<'a> {
+    fn with_span(self, span: Span) -> Self {
+        match self {
+            Terminal::Keyword(keyword) => Terminal::Keyword(keyword.with_span(span)),
+            Terminal::Integer(IntegerLit::Dec(lit)) => {
+                Terminal::Integer(IntegerLit::Dec(DecLit(lit.0, span)))
+            }
+            Terminal::Integer(IntegerLit::Hex(lit)) => {
+                Terminal::Integer(IntegerLit::Hex(HexLit(lit.0, span)))
+            }
+            Terminal::Integer(IntegerLit::Oct(lit)) => {
+                Terminal::Integer(IntegerLit::Oct(OctLit(lit.0, span)))
+            }
+            Terminal::Decimal(lit) => Terminal::Decimal(FloatValueLit(lit.0, span)),
+            Terminal::Identifier(id) => Terminal::Identifier(Identifier(id.0, span)),
+            Terminal::String(lit) => Terminal::String(StringLit(lit.0, span)),
+            Terminal::Other(_) | Terminal::Eof(_) => self,
+        }
+    }
+
+    /// Returns the terminal of the same kind for the token `text`
+    fn with_text<'b>(&self, text: &'b str) -> Terminal<'b> {
+        match self {
+            Terminal::Keyword(keyword) => Terminal::Keyword(*keyword),
+            Terminal::Integer(IntegerLit::Dec(_)) => {
+                Terminal::Integer(IntegerLit::Dec(DecLit::new(text)))
+            }
+            Terminal::Integer(IntegerLit::Hex(_)) => {
+                Terminal::Integer(IntegerLit::Hex(HexLit::new(text)))
+            }
+            Terminal::Integer(IntegerLit::Oct(_)) => {
+                Terminal::Integer(IntegerLit::Oct(OctLit::new(text)))
+            }
+            Terminal::Decimal(_) => Terminal::Decimal(FloatValueLit::new(text)),
+            Terminal::Identifier(_) => Terminal::Identifier(Identifier::new(text)),
+            Terminal::String(_) => Terminal::String(StringLit::new(&text[1..text.len() - 1])),
+            Terminal::Other(c) => Terminal::Other(*c),
+            Terminal::Eof(()) => Terminal::Eof(()),
+        }
+    }
+
+    /// Returns the kind of the terminal, without its text
+    pub(crate) fn kind(&self) -> Terminal<'static> {
+        match self {
+            Terminal::Keyword(keyword) => Terminal::Keyword(*keyword),
+            Terminal::Integer(IntegerLit::Dec(_)) => {
+                Terminal::Integer(IntegerLit::Dec(DecLit::new("")))
+            }
+            Terminal::Integer(IntegerLit::Hex(_)) => {
+                Terminal::Integer(IntegerLit::Hex(HexLit::new("")))
+            }
+            Terminal::Integer(IntegerLit::Oct(_)) => {
+                Terminal::Integer(IntegerLit::Oct(OctLit::new("")))
+            }
+            Terminal::Decimal(_) => Terminal::Decimal(FloatValueLit::new("")),
+            Terminal::Identifier(_) => Terminal::Identifier(Identifier::new("")),
+            Terminal::String(_) => Terminal::String(StringLit::new("")),
+            Terminal::Other(c) => Terminal::Other(*c),
+            Terminal::Eof(()) => Terminal::Eof(()),
+        }
+    }
+}
+
+/// A token of the source, with the trivia before it
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Token<'a> {
     pub value: Terminal<'a>,
+    /// The whitespace and comments before the token
     pub trivia: &'a str,
-    // TODO: Use https://github.com/fflorent/nom_locate/ ?
+    /// The source text of the token, which is empty for the `Eof` token
+    pub text: &'a str,
+    /// The byte range of the token itself, excluding the trivia
+    pub span: Span,
 }
 
 impl Token<'_> {
-    pub fn new<'a>((trivia, tag): (&'a str, Terminal<'a>)) -> Token<'a> {
-        Token { value: tag, trivia }
+    /// Creates a token from its trivia and its text, both of which must be slices of `input`
+    fn new<'a>(input: &'a str, trivia: &'a str, text: &'a str, value: Terminal<'a>) -> Token<'a> {
+        let start = input.offset(text);
+        let span = Span::new(start, start + text.len());
+        Token {
+            value: value.with_span(span),
+            trivia,
+            text,
+            span,
+        }
+    }
+
+    /// Returns the token with the kind of `kind` at `span` of `input`, after the trivia
+    /// starting at `trivia_start`
+    pub(crate) fn of_kind<'a>(
+        kind: &Terminal<'_>,
+        input: &'a str,
+        trivia_start: usize,
+        span: Span,
+    ) -> Token<'a> {
+        let text = &input[span.range()];
+        Token {
+            value: kind.with_text(text).with_span(span),
+            trivia: &input[trivia_start..span.start],
+            text,
+            span,
+        }
     }
 }
 
-fn other(input: &str) -> NomResult<char> {
+fn other(input: &str) -> NomResult<'_, char> {
     nom::character::complete::satisfy(|c| !"\t\n\r ".contains(c) && !c.is_alphanumeric())(input)
 }
 
-fn id_or_keyword(input: &str) -> NomResult<Terminal> {
+fn id_or_keyword(input: &str) -> NomResult<'_, Terminal<'_>> {
     let (input, id) = Identifier::lex(input)?;
-    match Keyword::match_word(id.0) {
+    match Keyword::match_word(&id.0) {
         Some(keyword) => Ok((input, Terminal::Keyword(keyword))),
         _ => Ok((input, Terminal::Identifier(id))),
     }
 }
 
-fn tag(input: &str) -> NomResult<Terminal> {
+/// Lexes `_` followed by a digit, e.g. `_0`, which only the relaxed identifiers allow
+fn relaxed_identifier(input: &str) -> NomResult<'_, Identifier<'_>> {
+    nom::combinator::map(
+        nom::combinator::recognize(tuple((
+            nom::character::complete::char('_'),
+            nom::character::complete::satisfy(|c| c.is_ascii_digit()),
+            nom::bytes::complete::take_while(is_alphanum_underscore_dash),
+        ))),
+        Identifier::new,
+    )(input)
+}
+
+fn tag(input: &str, relaxed_identifiers: bool) -> NomResult<'_, Terminal<'_>> {
+    if relaxed_identifiers {
+        if let Ok((input, id)) = relaxed_identifier(input) {
+            return Ok((input, Terminal::Identifier(id)));
+        }
+    }
     nom::branch::alt((
         FloatValueLit::lex.map(Terminal::Decimal),
         IntegerLit::lex.map(Terminal::Integer),
@@ -54,20 +212,258 @@ fn tag(input: &str) -> NomResult<Terminal> {
     ))(input)
 }
 
-pub fn lex(input: &str) -> Result<Vec<Token>, nom::Err<nom::error::VerboseError<&str>>> {
-    let (unread, (mut tokens, eof)) = tuple((
-        many0(tuple((sp, tag)).map(Token::new)),
-        tuple((sp, nom::combinator::eof)).map(|(trivia, _)| Token {
-            value: Terminal::Eof(()),
-            trivia,
-        }),
-    ))(input)?;
+/// An iterator over the tokens of a source, which lexes them as they are requested
+///
+/// The iterator ends after the `Eof` token, or after the first error.
+#[derive(Clone, Debug)]
+pub struct Lexer<'a> {
+    input: &'a str,
+    rest: &'a str,
+    done: bool,
+    relaxed_identifiers: bool,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Lexer::starting_at(input, 0)
+    }
+
+    /// Returns a lexer that also accepts relaxed identifiers when the options do, see
+    /// [`ParseOptions::relaxed_identifiers`]
+    pub fn with_options(input: &'a str, options: &ParseOptions) -> Self {
+        Lexer {
+            relaxed_identifiers: options.relaxed_identifiers,
+            ..Lexer::new(input)
+        }
+    }
+
+    /// Lexes `input` from the byte offset `start`, which must be where the trivia before a
+    /// token starts
+    fn starting_at(input: &'a str, start: usize) -> Self {
+        Lexer {
+            input,
+            rest: &input[start..],
+            done: false,
+            relaxed_identifiers: false,
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Result<Token<'a>, nom::Err<ParseError<&'a str>>>> {
+        if self.done {
+            return None;
+        }
+        let relaxed_identifiers = self.relaxed_identifiers;
+        let tag = |input| tag(input, relaxed_identifiers);
+        match tuple((sp, consumed(tag)))(self.rest) {
+            Ok((unread, (trivia, (text, value)))) => {
+                self.rest = unread;
+                return Some(Ok(Token::new(self.input, trivia, text, value)));
+            }
+            Err(nom::Err::Error(_)) => {}
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        }
+
+        self.done = true;
+        let input = self.input;
+        let eof = tuple((
+            sp,
+            contextful_cut("Unrecognized character", consumed(nom::combinator::eof)),
+        ))
+        .map(|(trivia, (text, _))| Token::new(input, trivia, text, Terminal::Eof(())))
+        .parse(self.rest);
+        Some(eof.map(|(unread, eof)| {
+            // Cannot be empty here since eof would fail then
+            assert!(unread.is_empty());
+            eof
+        }))
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let input = self.input;
+        let token = self.next_token()?;
+        Some(token.map_err(|err| convert_error(input, err)))
+    }
+}
+
+impl std::iter::FusedIterator for Lexer<'_> {}
+
+pub(crate) fn lex(input: &str) -> Result<Vec<Token<'_>>, nom::Err<ParseError<&str>>> {
+    lex_with(input, &ParseOptions::default())
+}
+
+pub(crate) fn lex_with<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> Result<Vec<Token<'a>>, nom::Err<ParseError<&'a str>>> {
+    collect(Lexer::with_options(input, options))
+}
+
+/// Lexes `input`, the source of the `previous` tokens once the byte range `edited` of it was
+/// replaced by `inserted` bytes of text
+///
+/// Only the tokens around the edit are lexed again, until the lexer reaches the end of a
+/// previous token after the edit. The tokens from there on are the previous ones, moved by the
+/// edit. The tokens are the ones [`Lexer::with_options`] would give for `input`.
+///
+/// ### Example
+///
+/// ```
+/// use weedle::dialect::ParseOptions;
+/// use weedle::lexer::relex;
+///
+/// let previous = weedle::lex("interface A { attribute long a; };").unwrap();
+/// let source = "interface A { attribute long long a; };";
+/// let tokens = relex(source, &previous, 28..28, 5, &ParseOptions::default()).unwrap();
+///
+/// assert_eq!(tokens, weedle::lex(source).unwrap());
+/// ```
+pub fn relex<'a>(
+    input: &'a str,
+    previous: &[Token<'_>],
+    edited: Range<usize>,
+    inserted: usize,
+    options: &ParseOptions,
+) -> Result<Vec<Token<'a>>, Error> {
+    relex_tokens(input, previous, edited, inserted, options).map(|relexed| relexed.tokens)
+}
+
+/// A token of the source before an edit, see [`relex_tokens`]
+pub(crate) trait LexedToken {
+    /// The terminal, of which only the kind matters
+    fn value(&self) -> &Terminal<'_>;
+    fn span(&self) -> Span;
+    /// Where the trivia before the token starts
+    fn trivia_start(&self) -> usize;
+
+    /// Returns the token moved by `delta` bytes in `input`, where it and its trivia have the
+    /// same text
+    fn moved<'a>(&self, input: &'a str, delta: isize) -> Token<'a> {
+        Token::of_kind(
+            self.value(),
+            input,
+            self.trivia_start().wrapping_add_signed(delta),
+            self.span().shifted(delta),
+        )
+    }
+}
+
+impl LexedToken for Token<'_> {
+    fn value(&self) -> &Terminal<'_> {
+        &self.value
+    }
 
-    // Cannot be empty here since eof would fail then
-    assert!(unread.is_empty());
+    fn span(&self) -> Span {
+        self.span
+    }
 
-    tokens.push(eof);
+    fn trivia_start(&self) -> usize {
+        self.span.start - self.trivia.len()
+    }
+}
+
+/// The tokens of an edited source, from [`relex_tokens`]
+pub(crate) struct Relexed<'a> {
+    pub(crate) tokens: Vec<Token<'a>>,
+    /// The tokens that were lexed again, before which the tokens are the previous ones
+    pub(crate) lexed: Range<usize>,
+    /// The index of the previous token moved to `lexed.end`
+    pub(crate) reused: usize,
+}
 
+/// Lexes again like [`relex`], telling which tokens were lexed again
+pub(crate) fn relex_tokens<'a>(
+    input: &'a str,
+    previous: &[impl LexedToken],
+    edited: Range<usize>,
+    inserted: usize,
+    options: &ParseOptions,
+) -> Result<Relexed<'a>, Error> {
+    let delta = inserted as isize - edited.len() as isize;
+    let first = first_changed(previous, edited.start);
+    let start = match first {
+        0 => 0,
+        _ => previous[first - 1].span().end,
+    };
+
+    let lexer = Lexer {
+        relaxed_identifiers: options.relaxed_identifiers,
+        ..Lexer::starting_at(input, start)
+    };
+    let mut tokens: Vec<_> = previous[..first]
+        .iter()
+        .map(|token| token.moved(input, 0))
+        .collect();
+    let mut reused = previous.len();
+    let mut next = first;
+    for token in lexer {
+        let token = token?;
+        // Where the token ends in the previous source
+        let end = token.span.end as isize - delta;
+        let at_eof = matches!(token.value, Terminal::Eof(_));
+        tokens.push(token);
+        if at_eof || end < edited.end as isize {
+            continue;
+        }
+        let end = end as usize;
+        // Past the edit, the lexer continues like it did from the end of a previous token
+        while next < previous.len() && previous[next].span().end < end {
+            next += 1;
+        }
+        if next < previous.len() && previous[next].span().end == end {
+            reused = next + 1;
+            break;
+        }
+    }
+
+    // The tokens lexed again before the edit are usually the previous ones
+    let mut changed = first;
+    while changed < tokens.len().min(reused) {
+        let (token, previous) = (&tokens[changed], &previous[changed]);
+        let same = token.span == previous.span()
+            && token.span.end <= edited.start
+            && token.trivia.len() == token.span.start - previous.trivia_start()
+            && token.value.kind() == previous.value().kind();
+        if !same {
+            break;
+        }
+        changed += 1;
+    }
+    let lexed = changed..tokens.len();
+    tokens.extend(
+        previous[reused..]
+            .iter()
+            .map(|token| token.moved(input, delta)),
+    );
+    Ok(Relexed {
+        tokens,
+        lexed,
+        reused,
+    })
+}
+
+/// Returns the index of the first token that an edit from `start` may change. Lexing a token
+/// looks at up to three tokens after it, e.g. `1e+` before a digit, and at the rest of the
+/// source for an unterminated comment or string.
+fn first_changed(tokens: &[impl LexedToken], start: usize) -> usize {
+    let touched = tokens.partition_point(|token| token.span().end < start);
+    let unterminated = tokens[..touched]
+        .iter()
+        .position(|token| matches!(token.value(), Terminal::Other('/' | '"')));
+    unterminated.unwrap_or(touched.saturating_sub(3))
+}
+
+fn collect(mut lexer: Lexer<'_>) -> Result<Vec<Token<'_>>, nom::Err<ParseError<&str>>> {
+    let mut tokens = vec![];
+    while let Some(token) = lexer.next_token() {
+        tokens.push(token?);
+    }
     Ok(tokens)
 }
 
@@ -126,6 +522,53 @@ mod tests {
             "Last token should be EOF"
         );
 
-        // TODO: source file reconstruction test
+        let reconstructed: String = tokens
+            .iter()
+            .flat_map(|token| [token.trivia, &content[token.span.range()]])
+            .collect();
+        assert_eq!(reconstructed, content);
+        assert!(tokens
+            .iter()
+            .all(|token| token.text == &content[token.span.range()]));
+
+        let streamed: Vec<_> = Lexer::new(&content).map(Result::unwrap).collect();
+        assert_eq!(streamed, tokens);
+    }
+
+    #[test]
+    fn should_lex_spans() {
+        let tokens = lex("  interface /* mixin */ Foo \"bar\" ;").unwrap();
+        let spans: Vec<_> = tokens.iter().map(|token| token.span.range()).collect();
+        assert_eq!(spans, [2..11, 24..27, 28..33, 34..35, 35..35]);
+
+        match &tokens[1].value {
+            Terminal::Identifier(id) => assert_eq!(id.1, Span::new(24, 27)),
+            _ => unreachable!(),
+        }
+        match &tokens[2].value {
+            Terminal::String(lit) => assert_eq!(lit.1, Span::new(28, 33)),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_stop_streaming_after_an_error() {
+        let mut lexer = Lexer::new("interface /* unterminated");
+
+        assert_eq!(lexer.next().unwrap().unwrap().text, "interface");
+        assert_eq!(lexer.next().unwrap().unwrap().value, Terminal::Other('/'));
+        assert_eq!(lexer.next().unwrap().unwrap().text, "*");
+        assert_eq!(lexer.next().unwrap().unwrap().text, "unterminated");
+        assert!(matches!(
+            lexer.next().unwrap().unwrap().value,
+            Terminal::Eof(_)
+        ));
+        assert!(lexer.next().is_none());
+
+        let mut lexer = Lexer::new("interface é {};");
+        assert!(lexer.next().unwrap().is_ok());
+        let err = lexer.next().unwrap().unwrap_err();
+        assert_eq!(err.offset(), 10);
+        assert!(lexer.next().is_none());
     }
 }
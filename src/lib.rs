@@ -20,14 +20,22 @@
 //! This parser follows the grammar given at [WebIDL](https://heycam.github.io/webidl).
 //!
 //! If any flaws found when parsing string with a valid grammar, create an issue.
+//!
+//! With the `serde` feature, the syntax tree implements `Serialize` and `Deserialize`.
+//! Terminals are written as their source text; their spans are not serialized.
 
+use crate::dialect::ParseOptions;
+use crate::error::convert_error;
 use crate::tokens::contextful_cut;
 
+pub use error::{Error, ErrorKind, Expected};
+
 use self::argument::ArgumentList;
 use self::attribute::ExtendedAttributeList;
-use self::common::{Braced, Identifier, Parenthesized, PunctuatedNonEmpty};
+use self::common::{Braced, Identifier, Invalid, Parenthesized, PunctuatedNonEmpty};
 use self::dictionary::DictionaryMembers;
 use self::interface::{CallbackInterfaceMembers, Inheritance, InterfaceMembers};
+use self::legacy::{ExceptionDefinition, ImplementsStatementDefinition};
 use self::literal::StringLit;
 use self::mixin::MixinMembers;
 use self::namespace::NamespaceMembers;
@@ -41,22 +49,66 @@ mod whitespace;
 pub mod term;
 pub mod argument;
 pub mod attribute;
+#[cfg(feature = "codegen-rust")]
+pub mod codegen_rust;
+#[cfg(feature = "codegen-ts")]
+pub mod codegen_ts;
 pub mod common;
+pub mod diagnostic;
+pub mod dialect;
 pub mod dictionary;
+pub mod fmt;
+pub mod highlight;
+pub mod incremental;
 pub mod interface;
+pub mod legacy;
+pub mod lexer;
 pub mod literal;
+pub mod lossless;
 pub mod members;
 pub mod mixin;
 pub mod namespace;
+pub mod owned;
+pub mod registry;
+pub mod resolve;
+pub mod span;
+pub mod symbols;
 pub mod types;
+pub mod validate;
+pub mod visit;
+#[cfg(feature = "webidl2")]
+pub mod webidl2;
 
-mod lexer;
+mod error;
+mod recovery;
 mod tokens;
 
-use lexer::lex;
+use lexer::Token;
 use tokens::Tokens;
 
-type VerboseResult<I, O> = nom::IResult<I, O, nom::error::VerboseError<I>>;
+type VerboseResult<I, O> = nom::IResult<I, O, error::ParseError<I>>;
+
+/// Splits the source into tokens, ending with an `Eof` token
+///
+/// Each token holds its kind and value, its text, the trivia (whitespace and comments) before
+/// it and its byte range. Use [`lexer::Lexer`] to get the tokens one at a time.
+///
+/// ### Example
+///
+/// ```
+/// use weedle::lexer::Terminal;
+/// use weedle::term::Keyword;
+///
+/// let tokens = weedle::lex("enum Mode { \"open\" }; // trailing\n").unwrap();
+///
+/// assert!(matches!(tokens[0].value, Terminal::Keyword(Keyword::Enum(_))));
+/// assert_eq!(tokens[3].text, "\"open\"");
+/// assert_eq!(tokens[3].span.range(), 12..18);
+/// assert_eq!(tokens.last().unwrap().trivia, " // trailing\n");
+/// ```
+pub fn lex(input: &'_ str) -> Result<Vec<Token<'_>>, Error> {
+    lexer::lex(input).map_err(|err| convert_error(input, err))
+}
 
 /// A convenient parse function
 ///
@@ -73,15 +125,40 @@ type VerboseResult<I, O> = nom::IResult<I, O, nom::error::VerboseError<I>>;
 ///
 /// println!("{:?}", parsed);
 /// ```
-pub fn parse(
-    input: &'_ str,
-) -> Result<Definitions<'_>, nom::Err<nom::error::VerboseError<&'_ str>>> {
-    let tokens = lex(input)?;
+pub fn parse(input: &'_ str) -> Result<Definitions<'_>, Error> {
+    parse_with(input, &ParseOptions::default())
+}
+
+/// Parses the definitions, accepting the grammar extensions enabled by the options
+///
+/// ### Example
+///
+/// ```
+/// use weedle::dialect::{Dialect, ParseOptions};
+///
+/// let parsed = weedle::parse_with("
+///     interface LegacyIterable {
+///         legacyiterable<long>;
+///     };
+/// ", &ParseOptions::new(Dialect::Gecko)).unwrap();
+///
+/// assert_eq!(parsed.len(), 1);
+/// ```
+pub fn parse_with<'a>(input: &'a str, options: &ParseOptions) -> Result<Definitions<'a>, Error> {
+    let tokens = lexer::lex_with(input, options).map_err(|err| convert_error(input, err))?;
+    parse_tokens(input, &tokens, options)
+}
+
+fn parse_tokens<'a>(
+    input: &'a str,
+    tokens: &[Token<'a>],
+    options: &ParseOptions,
+) -> Result<Definitions<'a>, Error> {
     let (unread, (defs, _eof)) = nom::sequence::tuple((
         Definitions::parse_tokens,
         contextful_cut("Unrecognized tokens", eat!(Eof)),
-    ))(Tokens(&tokens[..]))
-    .map_err(tokens::nom_error_into)?;
+    ))(Tokens(tokens, None, options))
+    .map_err(|err| convert_error(input, err))?;
 
     // Cannot be empty here since eof would fail then
     assert!(unread.0.is_empty());
@@ -89,17 +166,103 @@ pub fn parse(
     Ok(defs)
 }
 
+/// Parses the definitions that are valid, skipping the ones with syntax errors
+///
+/// After an error, parsing resumes at the end of the definition or member, that is after the
+/// next `;` or the `}` closing a body. The skipped source is kept as an `Invalid` definition or
+/// member, and the errors are returned in source order.
+///
+/// ### Example
+///
+/// ```
+/// let (definitions, errors) = weedle::parse_recovering("
+///     interface Window {
+///         readonly attribute Storage;
+///         attribute DOMString name;
+///     };
+///     typedef long
+/// ");
+///
+/// assert_eq!(definitions.len(), 2);
+/// assert!(matches!(definitions[1], weedle::Definition::Invalid(_)));
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].to_string(), "Missing name at line 3, column 35, expected identifier");
+/// ```
+pub fn parse_recovering(input: &'_ str) -> (Definitions<'_>, Vec<Error>) {
+    parse_recovering_with(input, &ParseOptions::default())
+}
+
+/// Parses the definitions like [`parse_recovering`], accepting the grammar extensions enabled
+/// by the options
+///
+/// ### Example
+///
+/// ```
+/// use weedle::dialect::{Dialect, ParseOptions};
+///
+/// let (definitions, errors) = weedle::parse_recovering_with("
+///     Node implements EventTarget;
+///     exception DOMException { unsigned short code };
+/// ", &ParseOptions::new(Dialect::Gecko));
+///
+/// assert!(matches!(definitions[0], weedle::Definition::Implements(_)));
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn parse_recovering_with<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> (Definitions<'a>, Vec<Error>) {
+    let tokens = match lexer::lex_with(input, options) {
+        Ok(tokens) => tokens,
+        Err(err) => return (vec![], vec![convert_error(input, err)]),
+    };
+    parse_tokens_recovering(input, &tokens, options)
+}
+
+fn parse_tokens_recovering<'a>(
+    input: &'a str,
+    tokens: &[Token<'a>],
+    options: &ParseOptions,
+) -> (Definitions<'a>, Vec<Error>) {
+    let recovery = recovery::Recovery::default();
+    let result = nom::sequence::tuple((
+        Definitions::parse_tokens,
+        contextful_cut("Unrecognized tokens", eat!(Eof)),
+    ))(Tokens(tokens, Some(&recovery), options));
+
+    let (defs, unexpected) = match result {
+        Ok((_, (defs, _eof))) => (defs, None),
+        // Only happens when recovery cannot skip anything, e.g. at the end of the input
+        Err(err) => (vec![], Some(convert_error(input, err))),
+    };
+    let mut errors = recovery.into_errors(input);
+    errors.extend(unexpected);
+    (defs, errors)
+}
+
+/// A node parsed from WebIDL source. The trait is implemented by the nodes of this crate only.
 pub trait Parse<'token>: Sized {
+    /// Parses from the lexed tokens, the building block of the parsers of this crate. It is not
+    /// part of the public interface, use [`Parse::parse`] or [`Parse::parse_with`] instead.
+    #[doc(hidden)]
     fn parse_tokens<'slice>(
         input: Tokens<'slice, 'token>,
     ) -> VerboseResult<Tokens<'slice, 'token>, Self>;
 
-    fn parse(input: &'token str) -> VerboseResult<&'token str, Self> {
-        let (input, _) = whitespace::sp(input)?;
-        let tokens = lex(input)?;
-        let (unread, def) =
-            Self::parse_tokens(Tokens(&tokens[..])).map_err(tokens::nom_error_into)?;
-        let (unread, _) = whitespace::sp(unread.into())?;
+    /// Parses the node at the start of the input, returning it with the unread rest
+    fn parse(input: &'token str) -> Result<(&'token str, Self), Error> {
+        Self::parse_with(input, &ParseOptions::default())
+    }
+
+    /// Parses like [`Parse::parse`], accepting the grammar extensions enabled by the options
+    fn parse_with(
+        input: &'token str,
+        options: &ParseOptions,
+    ) -> Result<(&'token str, Self), Error> {
+        let tokens = lexer::lex_with(input, options).map_err(|err| convert_error(input, err))?;
+        let (unread, def) = Self::parse_tokens(Tokens(&tokens[..], None, options))
+            .map_err(|err| convert_error(input, err))?;
+        let (unread, _) = whitespace::sp(unread.into()).map_err(|err| convert_error(input, err))?;
         Ok((unread, def))
     }
 }
@@ -124,6 +287,7 @@ pub type Definitions<'a> = Vec<Definition<'a>>;
 
 /// Parses `[attributes]? callback identifier = type ( (arg1, arg2, ..., argN)? );`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct CallbackDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -142,6 +306,7 @@ pub struct CallbackDefinition<'a> {
 
 /// Parses `[attributes]? callback interface identifier ( : inheritance )? { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct CallbackInterfaceDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -156,6 +321,7 @@ pub struct CallbackInterfaceDefinition<'a> {
 
 /// Parses `[attributes]? interface identifier ( : inheritance )? { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct InterfaceDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -170,6 +336,7 @@ pub struct InterfaceDefinition<'a> {
 
 /// Parses `[attributes]? interface mixin identifier { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct InterfaceMixinDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -184,6 +351,7 @@ pub struct InterfaceMixinDefinition<'a> {
 
 /// Parses `[attributes]? namespace identifier { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct NamespaceDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -197,6 +365,7 @@ pub struct NamespaceDefinition<'a> {
 
 /// Parses `[attributes]? dictionary identifier ( : inheritance )? { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct DictionaryDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -211,6 +380,7 @@ pub struct DictionaryDefinition<'a> {
 
 /// Parses `[attributes]? partial interface identifier { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct PartialInterfaceDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -225,6 +395,7 @@ pub struct PartialInterfaceDefinition<'a> {
 
 /// Parses `[attributes]? partial interface mixin identifier { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct PartialInterfaceMixinDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -240,6 +411,7 @@ pub struct PartialInterfaceMixinDefinition<'a> {
 
 /// Parses `[attributes]? partial dictionary identifier { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct PartialDictionaryDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -254,6 +426,7 @@ pub struct PartialDictionaryDefinition<'a> {
 
 /// Parses `[attributes]? partial namespace identifier { members };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct PartialNamespaceDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -268,6 +441,7 @@ pub struct PartialNamespaceDefinition<'a> {
 
 /// Parses `[attributes]? enum identifier { values };`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct EnumDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -281,6 +455,7 @@ pub struct EnumDefinition<'a> {
 
 /// Parses `[attributes]? typedef attributedtype identifier;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct TypedefDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -295,6 +470,7 @@ pub struct TypedefDefinition<'a> {
 
 /// Parses `[attributes]? identifier includes identifier;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct IncludesStatementDefinition<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -307,6 +483,8 @@ pub struct IncludesStatementDefinition<'a> {
 
 /// Parses a definition
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(recover = "Definition")]
 pub enum Definition<'a> {
     CallbackInterface(CallbackInterfaceDefinition<'a>),
     Callback(CallbackDefinition<'a>),
@@ -321,6 +499,13 @@ pub enum Definition<'a> {
     Enum(EnumDefinition<'a>),
     Typedef(TypedefDefinition<'a>),
     IncludesStatement(IncludesStatementDefinition<'a>),
+    #[weedle(extension = "legacy_keywords")]
+    Implements(ImplementsStatementDefinition<'a>),
+    #[weedle(extension = "legacy_keywords")]
+    Exception(ExceptionDefinition<'a>),
+    /// Source skipped by [`parse_recovering`]
+    #[weedle(skip)]
+    Invalid(Invalid<'a>),
 }
 
 /// Parses a non-empty enum value list
@@ -1,11 +1,18 @@
+use std::borrow::Cow;
+use std::num::ParseIntError;
+
 use nom::Parser;
 use weedle_derive::Weedle;
 
+use crate::lossless::Terminals;
+use crate::span::{Shift, Span, Spanned};
 use crate::Parse;
 
 /// Parses `-?[1-9][0-9]*`
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct DecLit<'a>(pub &'a str);
+#[derive(Clone)]
+pub struct DecLit<'a>(pub Cow<'a, str>, pub Span);
+
+text_terminal!(DecLit);
 
 impl<'a> DecLit<'a> {
     lexer!(nom::combinator::map(
@@ -14,13 +21,22 @@ impl<'a> DecLit<'a> {
             nom::character::complete::one_of("123456789"),
             nom::bytes::complete::take_while(nom::AsChar::is_dec_digit),
         ))),
-        DecLit,
+        DecLit::new,
     ));
 }
 
+impl DecLit<'_> {
+    /// Returns the value, or an error if it does not fit in an `i128`
+    pub fn value(&self) -> Result<i128, ParseIntError> {
+        parse_integer(&self.0, 0, 10)
+    }
+}
+
 /// Parses `-?0[Xx][0-9A-Fa-f]+)`
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct HexLit<'a>(pub &'a str);
+#[derive(Clone)]
+pub struct HexLit<'a>(pub Cow<'a, str>, pub Span);
+
+text_terminal!(HexLit);
 
 impl<'a> HexLit<'a> {
     lexer!(nom::combinator::map(
@@ -30,13 +46,22 @@ impl<'a> HexLit<'a> {
             nom::character::complete::one_of("xX"),
             nom::bytes::complete::take_while(nom::AsChar::is_hex_digit),
         ))),
-        HexLit,
+        HexLit::new,
     ));
 }
 
+impl HexLit<'_> {
+    /// Returns the value, or an error if it does not fit in an `i128` or has no digits
+    pub fn value(&self) -> Result<i128, ParseIntError> {
+        parse_integer(&self.0, 2, 16)
+    }
+}
+
 /// Parses `-?0[0-7]*`
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct OctLit<'a>(pub &'a str);
+#[derive(Clone)]
+pub struct OctLit<'a>(pub Cow<'a, str>, pub Span);
+
+text_terminal!(OctLit);
 
 impl<'a> OctLit<'a> {
     lexer!(nom::combinator::map(
@@ -45,12 +70,34 @@ impl<'a> OctLit<'a> {
             nom::character::complete::char('0'),
             nom::bytes::complete::take_while(nom::AsChar::is_oct_digit),
         ))),
-        OctLit,
+        OctLit::new,
     ));
 }
 
+impl OctLit<'_> {
+    /// Returns the value, or an error if it does not fit in an `i128`
+    pub fn value(&self) -> Result<i128, ParseIntError> {
+        // The leading zero is a valid octal digit
+        parse_integer(&self.0, 0, 8)
+    }
+}
+
+/// Parses an integer with an optional `-` sign, followed by a prefix of `prefix_len` bytes
+fn parse_integer(text: &str, prefix_len: usize, radix: u32) -> Result<i128, ParseIntError> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", text),
+    };
+    // A literal built by hand can be shorter than its prefix, and then has no digits, which
+    // `from_str_radix` reports as an error
+    let digits = unsigned.get(prefix_len..).unwrap_or_default();
+    // Keeps the sign, so that the smallest value does not overflow
+    i128::from_str_radix(&format!("{sign}{digits}"), radix)
+}
+
 /// Represents an integer value
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntegerLit<'a> {
     Dec(DecLit<'a>),
     Hex(HexLit<'a>),
@@ -65,15 +112,67 @@ impl<'a> IntegerLit<'a> {
     )));
 }
 
+impl IntegerLit<'_> {
+    /// Returns the value, or an error if it does not fit in an `i128`
+    ///
+    /// The values of all the WebIDL integer types fit, including `unsigned long long`, which
+    /// can then be converted with `u64::try_from`.
+    pub fn value(&self) -> Result<i128, ParseIntError> {
+        match self {
+            IntegerLit::Dec(lit) => lit.value(),
+            IntegerLit::Hex(lit) => lit.value(),
+            IntegerLit::Oct(lit) => lit.value(),
+        }
+    }
+}
+
 impl<'a> Parse<'a> for IntegerLit<'a> {
     parser!(eat!(Integer));
 }
 
+impl Spanned for IntegerLit<'_> {
+    fn span(&self) -> Span {
+        match self {
+            IntegerLit::Dec(lit) => lit.span(),
+            IntegerLit::Hex(lit) => lit.span(),
+            IntegerLit::Oct(lit) => lit.span(),
+        }
+    }
+}
+
+impl Shift for IntegerLit<'_> {
+    fn shift(&mut self, delta: isize) {
+        match self {
+            IntegerLit::Dec(lit) => lit.shift(delta),
+            IntegerLit::Hex(lit) => lit.shift(delta),
+            IntegerLit::Oct(lit) => lit.shift(delta),
+        }
+    }
+}
+
+impl Terminals for IntegerLit<'_> {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        match self {
+            IntegerLit::Dec(lit) => lit.for_each_terminal(f),
+            IntegerLit::Hex(lit) => lit.for_each_terminal(f),
+            IntegerLit::Oct(lit) => lit.for_each_terminal(f),
+        }
+    }
+}
+
 /// Represents a string value
 ///
 /// Follow `/"[^"]*"/`
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct StringLit<'a>(pub &'a str);
+#[derive(Clone)]
+pub struct StringLit<'a>(pub Cow<'a, str>, pub Span);
+
+text_terminal!(@common StringLit);
+
+impl Terminals for StringLit<'_> {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        f(&format!("\"{}\"", self.0), self.1)
+    }
+}
 
 impl<'a> StringLit<'a> {
     lexer!(nom::combinator::map(
@@ -82,7 +181,7 @@ impl<'a> StringLit<'a> {
             nom::bytes::complete::take_while(|c| c != '\"'),
             nom::character::complete::char('\"'),
         ),
-        StringLit,
+        StringLit::new,
     ));
 }
 
@@ -92,6 +191,7 @@ impl<'a> Parse<'a> for StringLit<'a> {
 
 /// Represents `[ ]`
 #[derive(Weedle, Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EmptyArrayLit {
     pub open_bracket: term!(OpenBracket),
     #[weedle(cut = "Default sequence value must be empty")]
@@ -100,6 +200,7 @@ pub struct EmptyArrayLit {
 
 /// Represents `{ }`
 #[derive(Weedle, Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EmptyDictionaryLit {
     pub open_brace: term!(OpenBrace),
     #[weedle(cut = "Default dictionary value must be empty")]
@@ -107,7 +208,8 @@ pub struct EmptyDictionaryLit {
 }
 
 /// Represents a default literal value. Ex: `34|34.23|"value"|[ ]|true|false|null`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DefaultValue<'a> {
     Boolean(BooleanLit),
     EmptyArray(EmptyArrayLit),
@@ -118,31 +220,128 @@ pub enum DefaultValue<'a> {
     String(StringLit<'a>),
 }
 
+impl DefaultValue<'_> {
+    /// Returns the value, or an error if it is an integer that does not fit in an `i128`
+    pub fn value(&self) -> Result<IdlValue<'_>, ParseIntError> {
+        Ok(match self {
+            DefaultValue::Boolean(lit) => IdlValue::Boolean(lit.value()),
+            DefaultValue::EmptyArray(_) => IdlValue::EmptySequence,
+            DefaultValue::EmptyDictionary(_) => IdlValue::EmptyDictionary,
+            DefaultValue::Float(lit) => IdlValue::Float(lit.value()),
+            DefaultValue::Integer(lit) => IdlValue::Integer(lit.value()?),
+            DefaultValue::Null(_) => IdlValue::Null,
+            DefaultValue::String(lit) => IdlValue::String(lit.as_str()),
+        })
+    }
+}
+
 /// Represents `true`, `false`, `34.23`, `null`, `56`, ...
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConstValue<'a> {
     Boolean(BooleanLit),
     Float(FloatLit<'a>),
     Integer(IntegerLit<'a>),
 }
 
+impl ConstValue<'_> {
+    /// Returns the value, or an error if it is an integer that does not fit in an `i128`
+    pub fn value(&self) -> Result<IdlValue<'_>, ParseIntError> {
+        Ok(match self {
+            ConstValue::Boolean(lit) => IdlValue::Boolean(lit.value()),
+            ConstValue::Float(lit) => IdlValue::Float(lit.value()),
+            ConstValue::Integer(lit) => IdlValue::Integer(lit.value()?),
+        })
+    }
+}
+
 /// Represents either `true` or `false`
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct BooleanLit(bool);
+#[derive(Copy, Clone)]
+pub struct BooleanLit(bool, Span);
+
+impl BooleanLit {
+    /// Returns the value
+    pub fn value(&self) -> bool {
+        self.0
+    }
+}
 
 impl<'a> Parse<'a> for BooleanLit {
-    parser!(nom::combinator::map(
-        nom::branch::alt((
-            nom::combinator::value(true, weedle!(term!(true))),
-            nom::combinator::value(false, weedle!(term!(false))),
-        )),
-        BooleanLit
-    ));
+    parser!(nom::branch::alt((
+        nom::combinator::map(weedle!(term!(true)), |t| BooleanLit(true, t.span)),
+        nom::combinator::map(weedle!(term!(false)), |t| BooleanLit(false, t.span)),
+    )));
+}
+
+impl ::std::fmt::Debug for BooleanLit {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.debug_tuple("BooleanLit").field(&self.0).finish()
+    }
+}
+
+impl PartialEq for BooleanLit {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for BooleanLit {}
+
+impl PartialOrd for BooleanLit {
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BooleanLit {
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl ::std::hash::Hash for BooleanLit {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl Spanned for BooleanLit {
+    fn span(&self) -> Span {
+        self.1
+    }
+}
+
+impl Shift for BooleanLit {
+    fn shift(&mut self, delta: isize) {
+        self.1.shift(delta)
+    }
+}
+
+impl Terminals for BooleanLit {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        f(if self.0 { "true" } else { "false" }, self.1)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BooleanLit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bool(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BooleanLit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        bool::deserialize(deserializer).map(|value| BooleanLit(value, Span::default()))
+    }
 }
 
 /// Parses `/-?(([0-9]+\.[0-9]*|[0-9]*\.[0-9]+)([Ee][+-]?[0-9]+)?|[0-9]+[Ee][+-]?[0-9]+)/`
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct FloatValueLit<'a>(pub &'a str);
+#[derive(Clone)]
+pub struct FloatValueLit<'a>(pub Cow<'a, str>, pub Span);
+
+text_terminal!(FloatValueLit);
 
 impl<'a> FloatValueLit<'a> {
     lexer!(nom::combinator::map(
@@ -185,7 +384,7 @@ impl<'a> FloatValueLit<'a> {
                 ),
             )),
         ))),
-        FloatValueLit,
+        FloatValueLit::new,
     ));
 }
 
@@ -193,8 +392,17 @@ impl<'a> Parse<'a> for FloatValueLit<'a> {
     parser!(eat!(Decimal));
 }
 
+impl FloatValueLit<'_> {
+    /// Returns the value, rounded to the nearest `f64`, or `NaN` if the text is not a decimal
+    /// literal
+    pub fn value(&self) -> f64 {
+        self.0.parse().unwrap_or(f64::NAN)
+    }
+}
+
 /// Represents a floating point value, `NaN`, `Infinity`, '+Infinity`
-#[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FloatLit<'a> {
     Value(FloatValueLit<'a>),
     NegInfinity(term!(-Infinity)),
@@ -202,6 +410,31 @@ pub enum FloatLit<'a> {
     NaN(term!(NaN)),
 }
 
+impl FloatLit<'_> {
+    pub fn value(&self) -> f64 {
+        match self {
+            FloatLit::Value(lit) => lit.value(),
+            FloatLit::NegInfinity(_) => f64::NEG_INFINITY,
+            FloatLit::Infinity(_) => f64::INFINITY,
+            FloatLit::NaN(_) => f64::NAN,
+        }
+    }
+}
+
+/// The value of a constant or of a default value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IdlValue<'a> {
+    Boolean(bool),
+    Integer(i128),
+    Float(f64),
+    String(&'a str),
+    Null,
+    /// `[]`
+    EmptySequence,
+    /// `{}`
+    EmptyDictionary,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -210,72 +443,72 @@ mod test {
 
     test!(should_parse_integer { "45" =>
         "";
-        IntegerLit => IntegerLit::Dec(DecLit("45"))
+        IntegerLit => IntegerLit::Dec(DecLit::new("45"))
     });
 
     test!(should_parse_integer_surrounding_with_spaces { "  123123  " =>
         "";
-        IntegerLit => IntegerLit::Dec(DecLit("123123"))
+        IntegerLit => IntegerLit::Dec(DecLit::new("123123"))
     });
 
     test!(should_parse_integer_preceding_others { "3453 string" =>
         "string";
-        IntegerLit => IntegerLit::Dec(DecLit("3453"))
+        IntegerLit => IntegerLit::Dec(DecLit::new("3453"))
     });
 
     test!(should_parse_neg_integer { "-435" =>
         "";
-        IntegerLit => IntegerLit::Dec(DecLit("-435"))
+        IntegerLit => IntegerLit::Dec(DecLit::new("-435"))
     });
 
     test!(should_parse_hex_number { "0X08" =>
         "";
-        IntegerLit => IntegerLit::Hex(HexLit("0X08"))
+        IntegerLit => IntegerLit::Hex(HexLit::new("0X08"))
     });
 
     test!(should_parse_hex_large_number { "0xA" =>
         "";
-        IntegerLit => IntegerLit::Hex(HexLit("0xA"))
+        IntegerLit => IntegerLit::Hex(HexLit::new("0xA"))
     });
 
     test!(should_parse_zero { "0" =>
         "";
-        IntegerLit => IntegerLit::Oct(OctLit("0"))
+        IntegerLit => IntegerLit::Oct(OctLit::new("0"))
     });
 
     test!(should_parse_oct_number { "-07561" =>
         "";
-        IntegerLit => IntegerLit::Oct(OctLit("-07561"))
+        IntegerLit => IntegerLit::Oct(OctLit::new("-07561"))
     });
 
     test!(should_parse_float { "45.434" =>
         "";
-        FloatLit => FloatLit::Value(FloatValueLit("45.434"))
+        FloatLit => FloatLit::Value(FloatValueLit::new("45.434"))
     });
 
     test!(should_parse_float_surrounding_with_spaces { "  2345.2345  " =>
         "";
-        FloatLit => FloatLit::Value(FloatValueLit("2345.2345"))
+        FloatLit => FloatLit::Value(FloatValueLit::new("2345.2345"))
     });
 
     test!(should_parse_float_preceding_others { "3453.32334 string" =>
         "string";
-        FloatLit => FloatLit::Value(FloatValueLit("3453.32334"))
+        FloatLit => FloatLit::Value(FloatValueLit::new("3453.32334"))
     });
 
     test!(should_parse_neg_float { "-435.3435" =>
         "";
-        FloatLit => FloatLit::Value(FloatValueLit("-435.3435"))
+        FloatLit => FloatLit::Value(FloatValueLit::new("-435.3435"))
     });
 
     test!(should_parse_float_exp { "3e23" =>
         "";
-        FloatLit => FloatLit::Value(FloatValueLit("3e23"))
+        FloatLit => FloatLit::Value(FloatValueLit::new("3e23"))
     });
 
     test!(should_parse_float_exp_with_decimal { "5.3434e23" =>
         "";
-        FloatLit => FloatLit::Value(FloatValueLit("5.3434e23"))
+        FloatLit => FloatLit::Value(FloatValueLit::new("5.3434e23"))
     });
 
     test!(should_parse_neg_infinity { "-Infinity" =>
@@ -290,33 +523,33 @@ mod test {
 
     test!(should_parse_string { r#""this is a string""# =>
         "";
-        StringLit => StringLit("this is a string")
+        StringLit => StringLit::new("this is a string")
     });
 
     test!(should_parse_string_surround_with_spaces { r#"  "this is a string"  "# =>
         "";
-        StringLit => StringLit("this is a string")
+        StringLit => StringLit::new("this is a string")
     });
 
     test!(should_parse_string_followed_by_string { r#" "this is first"  "this is second" "# =>
         r#""this is second" "#;
-        StringLit => StringLit("this is first")
+        StringLit => StringLit::new("this is first")
     });
 
     test!(should_parse_string_with_spaces { r#"  "  this is a string  "  "# =>
         "";
-        StringLit => StringLit("  this is a string  ")
+        StringLit => StringLit::new("  this is a string  ")
     });
 
     test!(should_parse_string_with_comment { r#"  "// this is still a string"
      "# =>
         "";
-        StringLit => StringLit("// this is still a string")
+        StringLit => StringLit::new("// this is still a string")
     });
 
     test!(should_parse_string_with_multiline_comment { r#"  "/*"  "*/"  "# =>
         r#""*/"  "#;
-        StringLit => StringLit("/*")
+        StringLit => StringLit::new("/*")
     });
 
     test!(should_parse_null { "null" =>
@@ -331,11 +564,91 @@ mod test {
 
     test!(should_parse_bool_true { "true" =>
         "";
-        BooleanLit => BooleanLit(true)
+        BooleanLit => BooleanLit(true, Default::default())
     });
 
     test!(should_parse_bool_false { "false" =>
         "";
-        BooleanLit => BooleanLit(false)
+        BooleanLit => BooleanLit(false, Default::default())
+    });
+
+    test!(should_evaluate_hex_integer { "-0XfF" =>
+        "";
+        IntegerLit;
+        value() == Ok(-255);
+    });
+
+    test!(should_evaluate_oct_integer { "-07561" =>
+        "";
+        IntegerLit;
+        value() == Ok(-0o7561);
+    });
+
+    test!(should_evaluate_negative_zero { "-0" =>
+        "";
+        IntegerLit;
+        value() == Ok(0);
+    });
+
+    test!(should_evaluate_unsigned_long_long_max { "0xFFFFFFFFFFFFFFFF" =>
+        "";
+        IntegerLit;
+        value() == Ok(u64::MAX.into());
+    });
+
+    test!(should_evaluate_i128_min { "-0x80000000000000000000000000000000" =>
+        "";
+        IntegerLit;
+        value() == Ok(i128::MIN);
+    });
+
+    test!(should_evaluate_float_exp { "1e-3" =>
+        "";
+        FloatLit;
+        value() == 0.001;
+    });
+
+    test!(should_evaluate_float_without_integer_part { "-.5" =>
+        "";
+        FloatLit;
+        value() == -0.5;
+    });
+
+    test!(should_evaluate_neg_infinity { "-Infinity" =>
+        "";
+        FloatLit;
+        value() == f64::NEG_INFINITY;
+    });
+
+    #[test]
+    fn should_fail_to_evaluate_overflowing_integer() {
+        let lit = DecLit::new("170141183460469231731687303715884105728");
+        assert!(lit.value().is_err());
+        assert!(HexLit::new("0x").value().is_err());
+        assert!(HexLit::new("0").value().is_err());
+        assert!(HexLit::new("-").value().is_err());
+    }
+
+    #[test]
+    fn should_evaluate_nan() {
+        assert!(FloatLit::NaN(term!(NaN)).value().is_nan());
+    }
+
+    test!(should_evaluate_default_string { r#""a b""# =>
+        "";
+        DefaultValue;
+        value() == Ok(IdlValue::String("a b"));
+    });
+
+    test!(should_evaluate_default_empty_dictionary { "{}" =>
+        "";
+        DefaultValue;
+        value() == Ok(IdlValue::EmptyDictionary);
+    });
+
+    test!(should_evaluate_const_integer { "0x10" =>
+        "";
+        ConstValue;
+        value() == Ok(IdlValue::Integer(16));
     });
 }
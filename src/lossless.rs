@@ -0,0 +1,493 @@
+//! Lossless representation of a WebIDL source.
+//!
+//! [`parse`] keeps the trivia (whitespace and comments) before every token, so that the
+//! definitions can be printed back byte for byte, even after they have been modified.
+//!
+//! ### Example
+//!
+//! ```
+//! let source = "
+//!     // The window
+//!     interface Window {
+//!         readonly attribute Storage sessionStorage; // in storage.webidl
+//!     };
+//! ";
+//! let mut file = weedle::lossless::parse(source).unwrap();
+//! assert_eq!(file.to_string(), source);
+//!
+//! if let weedle::Definition::Interface(interface) = &mut file.definitions[0] {
+//!     interface.identifier.0 = "Frame".into();
+//! }
+//! assert_eq!(file.to_string(), source.replace("Window", "Frame"));
+//! ```
+
+use std::fmt;
+
+use crate::dialect::ParseOptions;
+use crate::error::convert_error;
+use crate::lexer::{lex_with, Token};
+use crate::span::Span;
+use crate::{Definitions, Error};
+
+/// Provides the terminal symbols of a node in source order
+pub trait Terminals {
+    /// Calls `f` with the text and the span of each terminal symbol.
+    ///
+    /// The span is empty for terminal symbols that were not produced by the parser.
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span));
+}
+
+impl<T: Terminals> Terminals for Option<T> {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        if let Some(inner) = self {
+            inner.for_each_terminal(f);
+        }
+    }
+}
+
+impl<T: Terminals> Terminals for Box<T> {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        (**self).for_each_terminal(f)
+    }
+}
+
+impl<T: Terminals> Terminals for Vec<T> {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        for item in self {
+            item.for_each_terminal(f);
+        }
+    }
+}
+
+impl<T: Terminals, U: Terminals> Terminals for (T, U) {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        self.0.for_each_terminal(f);
+        self.1.for_each_terminal(f);
+    }
+}
+
+impl<T: Terminals, U: Terminals, V: Terminals> Terminals for (T, U, V) {
+    fn for_each_terminal(&self, f: &mut dyn FnMut(&str, Span)) {
+        self.0.for_each_terminal(f);
+        self.1.for_each_terminal(f);
+        self.2.for_each_terminal(f);
+    }
+}
+
+/// Parsed definitions together with the trivia of every token
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceFile<'a> {
+    pub definitions: Definitions<'a>,
+    /// Sorted by the start offset of the token following the trivia
+    trivia: Vec<(usize, &'a str)>,
+    trailing_trivia: &'a str,
+}
+
+/// Parses WebIDL definitions, keeping the trivia of every token
+///
+/// ### Example
+///
+/// ```
+/// let source = "/* A comment */ interface Window {};\n";
+/// let file = weedle::lossless::parse(source).unwrap();
+///
+/// assert_eq!(file.definitions.len(), 1);
+/// assert_eq!(file.to_string(), source);
+/// ```
+pub fn parse(input: &str) -> Result<SourceFile<'_>, Error> {
+    parse_with(input, &ParseOptions::default())
+}
+
+/// Parses WebIDL definitions like [`crate::parse_with`], keeping the trivia of every token
+pub fn parse_with<'a>(input: &'a str, options: &ParseOptions) -> Result<SourceFile<'a>, Error> {
+    let tokens = lex_with(input, options).map_err(|err| convert_error(input, err))?;
+    let definitions = crate::parse_tokens(input, &tokens, options)?;
+
+    Ok(SourceFile::new(definitions, &tokens))
+}
+
+/// Parses WebIDL definitions like [`crate::parse_recovering`], keeping the trivia of every
+/// token
+///
+/// The skipped source is kept by the `Invalid` nodes, so the file still prints back to its
+/// source.
+///
+/// ### Example
+///
+/// ```
+/// let source = "interface Window { attribute long; };\n";
+/// let (file, errors) = weedle::lossless::parse_recovering(source);
+///
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(file.to_string(), source);
+/// ```
+pub fn parse_recovering(input: &str) -> (SourceFile<'_>, Vec<Error>) {
+    parse_recovering_with(input, &ParseOptions::default())
+}
+
+/// Parses WebIDL definitions like [`crate::parse_recovering_with`], keeping the trivia of
+/// every token
+pub fn parse_recovering_with<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> (SourceFile<'a>, Vec<Error>) {
+    let tokens = match lex_with(input, options) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            let file = SourceFile {
+                definitions: vec![],
+                trivia: vec![],
+                trailing_trivia: input,
+            };
+            return (file, vec![convert_error(input, err)]);
+        }
+    };
+    let (definitions, errors) = crate::parse_tokens_recovering(input, &tokens, options);
+
+    (SourceFile::new(definitions, &tokens), errors)
+}
+
+impl<'a> SourceFile<'a> {
+    fn new(definitions: Definitions<'a>, tokens: &[Token<'a>]) -> Self {
+        let (eof, tokens) = tokens.split_last().expect("The lexer always emits Eof");
+        let trivia = tokens
+            .iter()
+            .map(|token| (token.span.start, token.trivia))
+            .collect();
+
+        SourceFile {
+            definitions,
+            trivia,
+            trailing_trivia: eof.trivia,
+        }
+    }
+}
+
+impl<'a> SourceFile<'a> {
+    /// Returns the trivia before the token at the given span, if the token came from the source.
+    pub fn leading_trivia(&self, span: Span) -> Option<&'a str> {
+        if span.is_empty() {
+            return None;
+        }
+        self.trivia
+            .binary_search_by_key(&span.start, |(start, _)| *start)
+            .ok()
+            .map(|i| self.trivia[i].1)
+    }
+
+    /// Returns the trivia after the last token
+    pub fn trailing_trivia(&self) -> &'a str {
+        self.trailing_trivia
+    }
+
+    /// Returns the documentation comment directly before a node, e.g. a [`Definition`],
+    /// an [`InterfaceMember`], a [`DictionaryMember`], an enum value or an [`Argument`].
+    ///
+    /// Documentation comments are consecutive `/** ... */` or `///` comments with no blank
+    /// line in between or before the node. The comment markers and the leading `*` of each
+    /// line are removed.
+    ///
+    /// [`Definition`]: crate::Definition
+    /// [`InterfaceMember`]: crate::interface::InterfaceMember
+    /// [`DictionaryMember`]: crate::dictionary::DictionaryMember
+    /// [`Argument`]: crate::argument::Argument
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// let file = weedle::lossless::parse("
+    ///     /// A key-value store
+    ///     interface Storage {
+    ///         /**
+    ///          * The number of items
+    ///          */
+    ///         readonly attribute unsigned long length;
+    ///     };
+    /// ").unwrap();
+    ///
+    /// let definition = &file.definitions[0];
+    /// assert_eq!(file.doc_comment(definition).as_deref(), Some("A key-value store"));
+    ///
+    /// if let weedle::Definition::Interface(interface) = definition {
+    ///     let member = &interface.members.body[0];
+    ///     assert_eq!(file.doc_comment(member).as_deref(), Some("The number of items"));
+    /// }
+    /// ```
+    pub fn doc_comment<T: Terminals + ?Sized>(&self, node: &T) -> Option<String> {
+        let mut first = None;
+        node.for_each_terminal(&mut |_, span| {
+            if first.is_none() && !span.is_empty() {
+                first = Some(span);
+            }
+        });
+        let first = first?;
+        let trivia = self.leading_trivia(first)?;
+        let at_file_start = self.trivia.first().map(|(start, _)| *start) == Some(first.start);
+
+        let (comments, newlines_after) = split_trivia(trivia);
+        if newlines_after > 1 {
+            return None;
+        }
+        let mut block_start = comments.len();
+        for (i, comment) in comments.iter().enumerate().rev() {
+            // A comment ending the line of the previous token belongs to that token
+            let ends_line = comments
+                .get(i + 1)
+                .map_or(newlines_after, |next| next.newlines_before)
+                > 0;
+            let trailing = i == 0 && comment.newlines_before == 0 && !at_file_start && ends_line;
+            if !is_doc_comment(comment.text) || trailing {
+                break;
+            }
+            block_start = i;
+            if comment.newlines_before > 1 {
+                break;
+            }
+        }
+        let block = &comments[block_start..];
+        if block.is_empty() {
+            return None;
+        }
+
+        let lines: Vec<_> = block
+            .iter()
+            .flat_map(|comment| doc_comment_lines(comment.text))
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+/// A comment within trivia with the number of line breaks before it
+pub(crate) struct Comment<'a> {
+    pub(crate) text: &'a str,
+    pub(crate) newlines_before: usize,
+}
+
+/// Splits trivia into comments, also returning the line breaks after the last comment
+pub(crate) fn split_trivia(trivia: &str) -> (Vec<Comment<'_>>, usize) {
+    let mut comments = vec![];
+    let mut newlines = 0;
+    let mut rest = trivia;
+    while let Some(c) = rest.chars().next() {
+        let end = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            rest.find("*/").map_or(rest.len(), |i| i + 2)
+        } else {
+            if c == '\n' {
+                newlines += 1;
+            }
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        comments.push(Comment {
+            text: rest[..end].trim_end(),
+            newlines_before: newlines,
+        });
+        newlines = 0;
+        rest = &rest[end..];
+    }
+    (comments, newlines)
+}
+
+fn is_doc_comment(comment: &str) -> bool {
+    comment.starts_with("///") || comment.starts_with("/**") && comment != "/**/"
+}
+
+fn doc_comment_lines(comment: &str) -> Vec<&str> {
+    if let Some(line) = comment.strip_prefix("///") {
+        return vec![line.strip_prefix(' ').unwrap_or(line).trim_end()];
+    }
+    let inner = comment.trim_start_matches("/**").trim_end_matches("*/");
+    let mut lines: Vec<_> = inner
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+    while lines.first() == Some(&"") {
+        lines.remove(0);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines
+}
+
+// Terminal symbols without trivia, i.e. inserted ones, are separated by a space when needed.
+fn push_inserted(out: &mut String, text: &str) {
+    let space = match (out.chars().last(), text.chars().next()) {
+        (None, _) | (_, None) => false,
+        (Some(previous), _) if "([<".contains(previous) || previous.is_whitespace() => false,
+        (_, Some(next)) => !",;)]>?".contains(next),
+    };
+    if space {
+        out.push(' ');
+    }
+    out.push_str(text);
+}
+
+impl fmt::Display for SourceFile<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        // Inserted terminal symbols are written after the trivia of the next parsed one, so
+        // that e.g. an inserted extended attribute list comes after the comments.
+        let mut inserted: Vec<String> = vec![];
+        self.definitions
+            .for_each_terminal(&mut |text, span| match self.leading_trivia(span) {
+                Some(trivia) => {
+                    out.push_str(trivia);
+                    if inserted.is_empty() {
+                        out.push_str(text);
+                        return;
+                    }
+                    for text in inserted.drain(..) {
+                        push_inserted(&mut out, &text);
+                    }
+                    push_inserted(&mut out, text);
+                }
+                None => inserted.push(text.to_owned()),
+            });
+        for text in inserted {
+            push_inserted(&mut out, &text);
+        }
+        f.write_str(&out)?;
+        f.write_str(self.trailing_trivia)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::attribute::{ExtendedAttribute, ExtendedAttributeNoArgs};
+    use crate::common::{Bracketed, Identifier, Punctuated};
+    use crate::Definition;
+
+    #[test]
+    fn should_keep_trivia() {
+        let source = "// leading\ninterface A { /* inside */ };\n\n// trailing\n";
+        let file = parse(source).unwrap();
+        assert_eq!(file.to_string(), source);
+        assert_eq!(file.trailing_trivia(), "\n\n// trailing\n");
+
+        let span = match &file.definitions[0] {
+            Definition::Interface(interface) => interface.members.close_brace.span,
+            _ => unreachable!(),
+        };
+        assert_eq!(file.leading_trivia(span), Some(" /* inside */ "));
+    }
+
+    #[test]
+    fn should_keep_separators() {
+        let source = "enum E { \"a\" , \"b\", };\ncallback C = undefined (long a,long b);";
+        let file = parse(source).unwrap();
+        assert_eq!(file.to_string(), source);
+    }
+
+    #[test]
+    fn should_print_removed_argument() {
+        let source = "interface A {\n  undefined f(long a, /* b */ long b, long c);\n};\n";
+        let mut file = parse(source).unwrap();
+        match &mut file.definitions[0] {
+            Definition::Interface(interface) => match &mut interface.members.body[0] {
+                crate::interface::InterfaceMember::Operation(op) => {
+                    op.args.body.list.remove(1);
+                    op.args.body.separators.remove(1);
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            file.to_string(),
+            "interface A {\n  undefined f(long a, long c);\n};\n"
+        );
+    }
+
+    #[test]
+    fn should_print_inserted_extended_attribute() {
+        let source = "// Comment\ninterface A {};\n";
+        let mut file = parse(source).unwrap();
+        match &mut file.definitions[0] {
+            Definition::Interface(interface) => {
+                interface.attributes = Some(Bracketed {
+                    open_bracket: term!(OpenBracket),
+                    body: Punctuated {
+                        list: vec![ExtendedAttribute::NoArgs(ExtendedAttributeNoArgs(
+                            Identifier::new("Exposed"),
+                        ))],
+                        separators: vec![],
+                    },
+                    close_bracket: term!(CloseBracket),
+                });
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(file.to_string(), "// Comment\n[Exposed] interface A {};\n");
+    }
+
+    #[test]
+    fn should_extract_doc_comments() {
+        let source = "/// First
+/// definition
+dictionary A {
+  /** The member */
+  long b; /// Not for c
+
+  /**
+   * Multiple
+   *
+   * lines
+   */
+  long c;
+  // Not a doc comment
+  long d;
+  /// Separated
+
+  long e;
+};
+enum E {
+  /// The value
+  \"a\"
+};
+callback C = undefined (/** The argument */ long x);
+";
+        let file = parse(source).unwrap();
+        let docs = |node: &dyn Terminals| file.doc_comment(node);
+
+        assert_eq!(
+            docs(&file.definitions[0]).as_deref(),
+            Some("First\ndefinition")
+        );
+        match &file.definitions[0] {
+            Definition::Dictionary(dictionary) => {
+                let members = &dictionary.members.body;
+                assert_eq!(docs(&members[0]).as_deref(), Some("The member"));
+                assert_eq!(docs(&members[1]).as_deref(), Some("Multiple\n\nlines"));
+                assert_eq!(docs(&members[2]), None);
+                assert_eq!(docs(&members[3]), None);
+            }
+            _ => unreachable!(),
+        }
+        match &file.definitions[1] {
+            Definition::Enum(enum_) => {
+                assert_eq!(
+                    docs(&enum_.values.body.list[0]).as_deref(),
+                    Some("The value")
+                );
+            }
+            _ => unreachable!(),
+        }
+        match &file.definitions[2] {
+            Definition::Callback(callback) => {
+                assert_eq!(
+                    docs(&callback.arguments.body.list[0]).as_deref(),
+                    Some("The argument")
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -16,6 +16,90 @@ macro_rules! lexer {
     };
 }
 
+// Implements the traits of a terminal symbol holding its source text, ignoring the span
+// so that nodes compare by their content. Serialization likewise writes the text alone.
+macro_rules! text_terminal {
+    ($typ:ident) => {
+        text_terminal!(@common $typ);
+
+        impl $crate::lossless::Terminals for $typ<'_> {
+            fn for_each_terminal(&self, f: &mut dyn FnMut(&str, $crate::span::Span)) {
+                f(&self.0, self.1)
+            }
+        }
+    };
+    (@common $typ:ident) => {
+        impl<'a> $typ<'a> {
+            pub fn new(value: impl Into<::std::borrow::Cow<'a, str>>) -> Self {
+                Self(value.into(), ::std::default::Default::default())
+            }
+
+            /// Returns the source text
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl ::std::fmt::Debug for $typ<'_> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_tuple(stringify!($typ)).field(&self.0).finish()
+            }
+        }
+
+        impl PartialEq for $typ<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl Eq for $typ<'_> {}
+
+        impl PartialOrd for $typ<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $typ<'_> {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl ::std::hash::Hash for $typ<'_> {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+
+        impl $crate::span::Spanned for $typ<'_> {
+            fn span(&self) -> $crate::span::Span {
+                self.1
+            }
+        }
+
+        impl $crate::span::Shift for $typ<'_> {
+            fn shift(&mut self, delta: isize) {
+                $crate::span::Shift::shift(&mut self.1, delta)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $typ<'_> {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $typ<'_> {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <String as ::serde::Deserialize>::deserialize(deserializer).map(Self::new)
+            }
+        }
+    };
+}
+
 macro_rules! weedle {
     ($t:ty) => {
         <$t as $crate::Parse<'a>>::parse_tokens
@@ -55,9 +139,12 @@ macro_rules! eat {
                 match input.iter_elements().next() {
                     Some($crate::lexer::Token {
                         value: $crate::lexer::Terminal::$variant(variant),
-                        trivia: _,
+                        ..
                     }) => Ok((input.slice(1..), variant)),
-                    _ => nom::combinator::fail(input),
+                    _ => Err(nom::Err::Error($crate::error::ParseError::expected(
+                        input,
+                        $crate::Expected::$variant,
+                    ))),
                 }
             },
         )
@@ -74,9 +161,12 @@ macro_rules! eat_key {
                 match input.iter_elements().next() {
                     Some($crate::lexer::Token {
                         value: Terminal::Keyword(Keyword::$variant(variant)),
-                        trivia: _,
+                        ..
                     }) => Ok((input.slice(1..), variant)),
-                    _ => nom::combinator::fail(input),
+                    _ => Err(nom::Err::Error($crate::error::ParseError::expected(
+                        input,
+                        $crate::Expected::Keyword($crate::term::$variant.value()),
+                    ))),
                 }
             },
         )
@@ -87,7 +177,7 @@ macro_rules! try_eat_keys {
     ($typ:ident, $input:ident, $($variant:ident),+) => {
         $(
             if let Ok((tokens, result)) = eat_key!($variant)($input) {
-                return Ok((tokens, $typ(result.value())));
+                return Ok((tokens, $typ($crate::common::Identifier(result.value().into(), result.span))));
             }
         )+
     };
@@ -4,14 +4,16 @@ use crate::{
     argument::ArgumentList,
     attribute::ExtendedAttributeList,
     common::{Identifier, Parenthesized},
+    error::ParseError,
     literal::ConstValue,
     tokens::{contextful_cut, Tokens},
     types::{AttributedType, ConstType, Type},
-    VerboseResult,
+    Expected, VerboseResult,
 };
 
 /// Parses a const interface member `[attributes]? const type identifier = value;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct ConstMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -30,6 +32,7 @@ pub struct ConstMember<'a> {
 
 /// Parses `stringifier|inherit|static`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StringifierOrInheritOrStatic {
     Stringifier(term!(stringifier)),
     #[weedle(post_check = "prevent_inherit_readonly")]
@@ -47,28 +50,32 @@ fn prevent_inherit_readonly<'slice, 'a>(
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-struct AttributeName<'a>(&'a str);
+struct AttributeName<'a>(Identifier<'a>);
 
 impl<'a> crate::Parse<'a> for AttributeName<'a> {
     fn parse_tokens<'slice>(
         input: crate::tokens::Tokens<'slice, 'a>,
     ) -> VerboseResult<crate::tokens::Tokens<'slice, 'a>, Self> {
         if let Ok((tokens, result)) = eat!(Identifier)(input) {
-            return Ok((tokens, AttributeName(result.0)));
+            return Ok((tokens, AttributeName(result)));
         }
         try_eat_keys!(AttributeName, input, Async, Required);
-        nom::combinator::fail(input)
+        Err(nom::Err::Error(ParseError::expected(
+            input,
+            Expected::Identifier,
+        )))
     }
 }
 
 impl<'a> From<AttributeName<'a>> for Identifier<'a> {
     fn from(value: AttributeName<'a>) -> Self {
-        Self(value.0)
+        value.0
     }
 }
 
 /// Parses `[attributes]? (stringifier|inherit|static)? readonly? attribute attributedtype identifier;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct AttributeInterfaceMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -84,6 +91,7 @@ pub struct AttributeInterfaceMember<'a> {
 
 /// Parses `[attributes]? stringifier? readonly? attribute attributedtype identifier;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct AttributeMixinMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -99,6 +107,7 @@ pub struct AttributeMixinMember<'a> {
 
 /// Parses `[attribute]? readonly attributetype type identifier;`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct AttributeNamespaceMember<'a> {
     #[weedle(post_check = "prevent_writable_attribute")]
@@ -123,6 +132,7 @@ fn prevent_writable_attribute<'slice, 'a>(
 
 /// Parses one of the special keyword `getter|setter|deleter` or `static`.
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Modifier {
     Getter(term!(getter)),
     Setter(term!(setter)),
@@ -131,23 +141,26 @@ pub enum Modifier {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-struct OperationName<'a>(&'a str);
+struct OperationName<'a>(Identifier<'a>);
 
 impl<'a> crate::Parse<'a> for OperationName<'a> {
     fn parse_tokens<'slice>(
         input: crate::tokens::Tokens<'slice, 'a>,
     ) -> VerboseResult<crate::tokens::Tokens<'slice, 'a>, Self> {
         if let Ok((tokens, result)) = eat!(Identifier)(input) {
-            return Ok((tokens, OperationName(result.0)));
+            return Ok((tokens, OperationName(result)));
         }
         try_eat_keys!(OperationName, input, Includes);
-        nom::combinator::fail(input)
+        Err(nom::Err::Error(ParseError::expected(
+            input,
+            Expected::Identifier,
+        )))
     }
 }
 
 impl<'a> From<OperationName<'a>> for Identifier<'a> {
     fn from(value: OperationName<'a>) -> Self {
-        Self(value.0)
+        value.0
     }
 }
 
@@ -155,6 +168,7 @@ impl<'a> From<OperationName<'a>> for Identifier<'a> {
 ///
 /// (( )) means ( ) chars
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct OperationInterfaceMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
@@ -171,6 +185,7 @@ pub struct OperationInterfaceMember<'a> {
 ///
 /// (( )) means ( ) chars
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(context)]
 pub struct RegularOperationMember<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
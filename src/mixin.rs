@@ -1,16 +1,59 @@
 use weedle_derive::Weedle;
 
-use crate::interface::StringifierMember;
-use crate::members::{AttributeMixinMember, ConstMember, RegularOperationMember};
+use crate::common::Invalid;
+use crate::interface::{InterfaceMember, StringifierMember};
+use crate::members::{
+    AttributeInterfaceMember, AttributeMixinMember, ConstMember, OperationInterfaceMember,
+    RegularOperationMember, StringifierOrInheritOrStatic,
+};
 
 /// Parses the members declarations of a mixin
 pub type MixinMembers<'a> = Vec<MixinMember<'a>>;
 
 /// Parses one of the variants of a mixin member
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(recover = "Member")]
 pub enum MixinMember<'a> {
     Const(ConstMember<'a>),
     Operation(RegularOperationMember<'a>),
     Attribute(AttributeMixinMember<'a>),
     Stringifier(StringifierMember<'a>),
+    /// Source skipped by [`parse_recovering`](crate::parse_recovering)
+    #[weedle(skip)]
+    Invalid(Invalid<'a>),
+}
+
+/// Converts the member of a mixin to the member of the interface including it
+impl<'a> From<MixinMember<'a>> for InterfaceMember<'a> {
+    fn from(value: MixinMember<'a>) -> Self {
+        match value {
+            MixinMember::Const(member) => InterfaceMember::Const(member),
+            MixinMember::Operation(member) => {
+                InterfaceMember::Operation(OperationInterfaceMember {
+                    attributes: member.attributes,
+                    modifier: None,
+                    return_type: member.return_type,
+                    identifier: member.identifier,
+                    args: member.args,
+                    semi_colon: member.semi_colon,
+                })
+            }
+            MixinMember::Attribute(member) => {
+                InterfaceMember::Attribute(AttributeInterfaceMember {
+                    attributes: member.attributes,
+                    modifier: member
+                        .stringifier
+                        .map(StringifierOrInheritOrStatic::Stringifier),
+                    readonly: member.readonly,
+                    attribute: member.attribute,
+                    type_: member.type_,
+                    identifier: member.identifier,
+                    semi_colon: member.semi_colon,
+                })
+            }
+            MixinMember::Stringifier(member) => InterfaceMember::Stringifier(member),
+            MixinMember::Invalid(invalid) => InterfaceMember::Invalid(invalid),
+        }
+    }
 }
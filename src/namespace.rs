@@ -1,5 +1,6 @@
 use weedle_derive::Weedle;
 
+use crate::common::Invalid;
 use crate::members::{AttributeNamespaceMember, ConstMember, RegularOperationMember};
 
 /// Parses namespace members declaration
@@ -7,8 +8,13 @@ pub type NamespaceMembers<'a> = Vec<NamespaceMember<'a>>;
 
 /// Parses namespace member declaration
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[weedle(recover = "Member")]
 pub enum NamespaceMember<'a> {
     Operation(RegularOperationMember<'a>),
     Attribute(AttributeNamespaceMember<'a>),
     Const(ConstMember<'a>),
+    /// Source skipped by [`parse_recovering`](crate::parse_recovering)
+    #[weedle(skip)]
+    Invalid(Invalid<'a>),
 }
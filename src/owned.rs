@@ -0,0 +1,200 @@
+//! Conversion of the syntax tree to a tree that owns its text
+//!
+//! The nodes borrow their text from the parsed source, so they cannot outlive it.
+//! [`IntoOwned::into_owned`] copies the borrowed text of every node, turning for example a
+//! `Definition<'a>` into a `Definition<'static>` that can be stored or sent to another thread
+//! after the source is dropped.
+//!
+//! The conversion is derived along with the parser, so every node deriving `Weedle` supports
+//! it.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::owned::IntoOwned;
+//! use weedle::Definitions;
+//!
+//! fn load() -> Definitions<'static> {
+//!     let source = String::from("interface Window { attribute DOMString name; };");
+//!     weedle::parse(&source).unwrap().into_owned()
+//! }
+//!
+//! let definitions = load();
+//! std::thread::spawn(move || assert_eq!(definitions.len(), 1))
+//!     .join()
+//!     .unwrap();
+//! ```
+
+use std::borrow::Cow;
+
+use crate::common::{Identifier, Invalid, Punctuated, PunctuatedNonEmpty};
+use crate::literal::{BooleanLit, DecLit, FloatValueLit, HexLit, IntegerLit, OctLit, StringLit};
+
+/// A node that can be converted to a node owning its text
+pub trait IntoOwned {
+    /// The same node, with a `'static` lifetime
+    type Owned: 'static;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl<T: IntoOwned> IntoOwned for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(IntoOwned::into_owned)
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter().map(IntoOwned::into_owned).collect()
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Box<T> {
+    type Owned = Box<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        Box::new((*self).into_owned())
+    }
+}
+
+impl<T: IntoOwned, U: IntoOwned> IntoOwned for (T, U) {
+    type Owned = (T::Owned, U::Owned);
+
+    fn into_owned(self) -> Self::Owned {
+        (self.0.into_owned(), self.1.into_owned())
+    }
+}
+
+impl<T: IntoOwned, U: IntoOwned, W: IntoOwned> IntoOwned for (T, U, W) {
+    type Owned = (T::Owned, U::Owned, W::Owned);
+
+    fn into_owned(self) -> Self::Owned {
+        (
+            self.0.into_owned(),
+            self.1.into_owned(),
+            self.2.into_owned(),
+        )
+    }
+}
+
+impl<T: IntoOwned, S: IntoOwned> IntoOwned for Punctuated<T, S> {
+    type Owned = Punctuated<T::Owned, S::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        Punctuated {
+            list: self.list.into_owned(),
+            separators: self.separators.into_owned(),
+        }
+    }
+}
+
+impl<T: IntoOwned, S: IntoOwned> IntoOwned for PunctuatedNonEmpty<T, S> {
+    type Owned = PunctuatedNonEmpty<T::Owned, S::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        PunctuatedNonEmpty {
+            list: self.list.into_owned(),
+            separators: self.separators.into_owned(),
+        }
+    }
+}
+
+macro_rules! text_into_owned {
+    ($($typ:ident),*) => {
+        $(
+            impl IntoOwned for $typ<'_> {
+                type Owned = $typ<'static>;
+
+                fn into_owned(self) -> Self::Owned {
+                    $typ(Cow::Owned(self.0.into_owned()), self.1)
+                }
+            }
+        )*
+    };
+}
+
+text_into_owned!(
+    Identifier,
+    Invalid,
+    StringLit,
+    DecLit,
+    HexLit,
+    OctLit,
+    FloatValueLit
+);
+
+impl IntoOwned for BooleanLit {
+    type Owned = BooleanLit;
+
+    fn into_owned(self) -> Self::Owned {
+        self
+    }
+}
+
+impl IntoOwned for IntegerLit<'_> {
+    type Owned = IntegerLit<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            IntegerLit::Dec(lit) => IntegerLit::Dec(lit.into_owned()),
+            IntegerLit::Hex(lit) => IntegerLit::Hex(lit.into_owned()),
+            IntegerLit::Oct(lit) => IntegerLit::Oct(lit.into_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Definitions;
+
+    #[test]
+    fn should_outlive_the_source() {
+        let (owned, borrowed): (Definitions<'static>, String) = {
+            let source = String::from(
+                r#"
+                [Exposed=Window]
+                interface Node : EventTarget {
+                    const unsigned short ELEMENT_NODE = 0x1;
+                    attribute DOMString? nodeValue;
+                    Node cloneNode(optional boolean deep = false);
+                };
+                enum Mode { "open", "closed" };
+                "#,
+            );
+            let definitions = crate::parse(&source).unwrap();
+            (
+                definitions.into_owned(),
+                format!("{:?}", crate::parse(&source).unwrap()),
+            )
+        };
+
+        assert_eq!(format!("{owned:?}"), borrowed);
+    }
+
+    #[test]
+    fn should_keep_spans() {
+        let source = "typedef long Size;";
+        let definitions = crate::parse(source).unwrap();
+        let owned = definitions.clone().into_owned();
+
+        assert_eq!(owned, definitions);
+        let fmt = crate::fmt::FormatOptions::default();
+        assert_eq!(
+            crate::fmt::format(&owned, &fmt),
+            crate::fmt::format(&definitions, &fmt)
+        );
+        match (&owned[0], &definitions[0]) {
+            (crate::Definition::Typedef(a), crate::Definition::Typedef(b)) => {
+                assert_eq!(a.identifier.1, b.identifier.1);
+                assert!(matches!(a.identifier.0, Cow::Owned(_)));
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::cell::{Cell, RefCell};
+
+use nom::InputTake;
+
+use crate::common::Invalid;
+use crate::error::ParseError;
+use crate::lexer::{Terminal, Token};
+use crate::term::Keyword;
+use crate::tokens::{source_text, Tokens};
+use crate::{Error, VerboseResult};
+
+/// Collects the errors skipped by [`recover`], and how far the parsers read the tokens
+#[derive(Debug, Default)]
+pub struct Recovery {
+    errors: RefCell<Vec<Error>>,
+    /// The number of tokens after the last one read
+    unread: Cell<Option<usize>>,
+}
+
+impl Recovery {
+    /// Notes that a parser read the token followed by `unread` tokens
+    pub(crate) fn read(&self, unread: usize) {
+        let unread = self.unread.get().map_or(unread, |last| last.min(unread));
+        self.unread.set(Some(unread));
+    }
+
+    /// Returns the number of tokens after the last one read, if any was
+    pub(crate) fn unread(&self) -> Option<usize> {
+        self.unread.get()
+    }
+
+    /// Returns the errors, with their locations computed from `source`
+    pub(crate) fn into_errors(self, source: &str) -> Vec<Error> {
+        self.errors
+            .into_inner()
+            .into_iter()
+            .map(|err| err.with_location(source))
+            .collect()
+    }
+}
+
+/// Where the parser resumes after an error
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Boundary {
+    /// After the next `;` or the `}` closing a body, e.g. `interface A { ... }`
+    Definition,
+    /// After the next `;`, or before the `}` closing the enclosing body
+    Member,
+}
+
+impl Boundary {
+    /// The error when nothing at the position could be parsed
+    fn context(&self) -> &'static str {
+        match self {
+            Boundary::Definition => "Unrecognized tokens",
+            Boundary::Member => "Unrecognized member definition",
+        }
+    }
+
+    /// Whether the list of definitions or members ends at the token
+    fn ends_list(&self, token: &Token) -> bool {
+        match token.value {
+            Terminal::Eof(_) => true,
+            Terminal::Keyword(Keyword::CloseBrace(_)) => *self == Boundary::Member,
+            _ => false,
+        }
+    }
+
+    /// Returns the number of tokens to skip to get to the boundary. Brackets and parentheses
+    /// do not span members, so a member ends at its `;` or before the enclosing `}` even when
+    /// one of them is left open.
+    fn skip_count(&self, tokens: &[Token]) -> usize {
+        let member = *self == Boundary::Member;
+        let (mut depth, mut braces) = (0usize, 0usize);
+        for (i, token) in tokens.iter().enumerate() {
+            match token.value {
+                Terminal::Eof(_) => return i,
+                Terminal::Keyword(Keyword::OpenBrace(_)) => {
+                    depth += 1;
+                    braces += 1;
+                }
+                Terminal::Keyword(Keyword::OpenBracket(_) | Keyword::OpenParen(_)) => depth += 1,
+                Terminal::Keyword(Keyword::CloseBrace(_)) if braces == 0 && member => return i,
+                Terminal::Keyword(Keyword::CloseBrace(_)) if depth > 0 => {
+                    depth -= 1;
+                    braces = braces.saturating_sub(1);
+                    let semi_colon_follows = matches!(
+                        tokens.get(i + 1).map(|token| &token.value),
+                        Some(Terminal::Keyword(Keyword::SemiColon(_)))
+                    );
+                    if depth == 0 && !member && !semi_colon_follows {
+                        return i + 1;
+                    }
+                }
+                Terminal::Keyword(Keyword::CloseBracket(_) | Keyword::CloseParen(_)) => {
+                    depth = depth.saturating_sub(1);
+                }
+                Terminal::Keyword(Keyword::SemiColon(_)) if depth == 0 || braces == 0 && member => {
+                    return i + 1
+                }
+                _ => {}
+            }
+        }
+        tokens.len()
+    }
+}
+
+/// Runs `parser`, and when parsing with recovery, turns its failure into an [`Invalid`]
+/// node covering the tokens up to the boundary.
+pub fn recover<'slice, 'a, O>(
+    boundary: Boundary,
+    input: Tokens<'slice, 'a>,
+    parser: impl FnOnce(Tokens<'slice, 'a>) -> VerboseResult<Tokens<'slice, 'a>, O>,
+    invalid: impl FnOnce(Invalid<'a>) -> O,
+) -> VerboseResult<Tokens<'slice, 'a>, O> {
+    let recovery = match input.1 {
+        Some(recovery) => recovery,
+        None => return parser(input),
+    };
+    let err = match parser(input) {
+        Err(nom::Err::Error(e)) => {
+            input.read(1);
+            if input
+                .0
+                .first()
+                .is_none_or(|token| boundary.ends_list(token))
+            {
+                return Err(nom::Err::Error(e));
+            }
+            ParseError::cut(input, boundary.context(), e)
+        }
+        Err(nom::Err::Failure(e)) => e,
+        result => return result,
+    };
+
+    let count = boundary.skip_count(input.0);
+    // Skipping looks at the token after the skipped ones
+    input.read(count + 1);
+    if count == 0 {
+        return Err(nom::Err::Failure(err));
+    }
+    recovery
+        .errors
+        .borrow_mut()
+        .push(err.into_unlocated_error());
+
+    let (rest, skipped) = input.take_split(count);
+    let span = skipped.0[0].span.join(skipped.0[count - 1].span);
+    Ok((
+        rest,
+        invalid(Invalid(source_text(skipped, rest).into(), span)),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dictionary::DictionaryMember;
+    use crate::interface::InterfaceMember;
+    use crate::{parse_recovering, Definition, ErrorKind};
+
+    #[test]
+    fn should_report_every_missing_semicolon() {
+        let (defs, errors) = parse_recovering(
+            "
+            interface A {
+                attribute long a
+                attribute long b;
+                const long c = 1
+            };
+            typedef long L
+            enum E { \"e\" };
+            ",
+        );
+
+        let kinds: Vec<_> = errors.iter().map(|err| err.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                ErrorKind::MissingSemicolon,
+                ErrorKind::MissingSemicolon,
+                ErrorKind::MissingSemicolon
+            ]
+        );
+        assert_eq!(errors[0].location().line, 4);
+        assert_eq!(errors[1].location().line, 6);
+        assert_eq!(errors[2].location().line, 8);
+
+        assert_eq!(defs.len(), 2);
+        match &defs[0] {
+            Definition::Interface(interface) => {
+                let members = &interface.members.body;
+                assert_eq!(members.len(), 2);
+                assert!(matches!(&members[0], InterfaceMember::Invalid(invalid)
+                    if invalid.0 == "attribute long a\n                attribute long b;"));
+                assert!(matches!(&members[1], InterfaceMember::Invalid(invalid)
+                    if invalid.0 == "const long c = 1"));
+            }
+            _ => panic!("Expected an interface"),
+        }
+        assert!(matches!(&defs[1], Definition::Invalid(invalid)
+            if invalid.0 == "typedef long L\n            enum E { \"e\" };"));
+    }
+
+    #[test]
+    fn should_recover_dictionary_members() {
+        let (defs, errors) = parse_recovering("dictionary D { long a long b; required long c; };");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ErrorKind::MissingSemicolon);
+
+        assert_eq!(defs.len(), 1);
+        match &defs[0] {
+            Definition::Dictionary(dictionary) => {
+                let members = &dictionary.members.body;
+                assert_eq!(members.len(), 2);
+                assert!(matches!(&members[0], DictionaryMember::Invalid(invalid)
+                    if invalid.0 == "long a long b;"));
+                assert!(matches!(&members[1], DictionaryMember::Field(member)
+                    if member.identifier.0 == "c" && member.required.is_some()));
+            }
+            _ => panic!("Expected a dictionary"),
+        }
+    }
+
+    #[test]
+    fn should_skip_to_closing_brace() {
+        let (defs, errors) = parse_recovering(
+            "interface A { attribute long a; } interface B {}; namespace C { 5; };",
+        );
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind(), ErrorKind::MissingSemicolon);
+        assert_eq!(errors[1].kind(), ErrorKind::UnrecognizedMemberDefinition);
+
+        assert_eq!(defs.len(), 3);
+        assert!(matches!(&defs[0], Definition::Invalid(invalid)
+            if invalid.0 == "interface A { attribute long a; }"));
+        assert!(matches!(&defs[1], Definition::Interface(_)));
+        assert!(matches!(&defs[2], Definition::Namespace(namespace)
+            if namespace.members.body.len() == 1));
+    }
+
+    #[test]
+    fn should_end_members_with_unclosed_brackets() {
+        let (defs, errors) =
+            parse_recovering("interface A { [Foo attribute long a; }; interface B { f(; };");
+
+        let messages: Vec<_> = errors.iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Unrecognized extended attribute at line 1, column 20, expected `]`",
+                "Unrecognized argument at line 1, column 57, expected `)`",
+            ]
+        );
+
+        assert_eq!(defs.len(), 2);
+        assert!(matches!(&defs[0], Definition::Interface(interface)
+            if matches!(&interface.members.body[..], [InterfaceMember::Invalid(invalid)]
+                if invalid.0 == "[Foo attribute long a;")));
+        assert!(matches!(&defs[1], Definition::Interface(_)));
+    }
+
+    #[test]
+    fn should_report_missing_enum_separator() {
+        let (defs, errors) = parse_recovering("enum E { \"a\" \"b\" }; typedef long L;");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ErrorKind::MissingSeparator);
+        assert_eq!(errors[0].location().column, 14);
+        assert_eq!(defs.len(), 2);
+    }
+
+    #[test]
+    fn should_report_unexpected_end() {
+        let (defs, errors) = parse_recovering("interface A {");
+
+        assert_eq!(defs.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ErrorKind::UnrecognizedMemberDefinition);
+    }
+
+    #[test]
+    fn should_print_recovered_file_lossless() {
+        let source = "/* A */ interface A {\n  attribute long a\n  /* B */ attribute long b;\n};\n\
+                      typedef long /* C */\n";
+        let (file, errors) = crate::lossless::parse_recovering(source);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(file.to_string(), source);
+    }
+
+    #[test]
+    fn should_not_recover_when_parsing_strictly() {
+        let err = crate::parse("interface A { attribute long a }; typedef long L").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingSemicolon);
+    }
+}
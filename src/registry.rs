@@ -0,0 +1,695 @@
+//! Known extended attributes.
+//!
+//! The parser accepts any extended attribute written in one of the forms of
+//! [`ExtendedAttribute`]. This module gives a meaning to the known ones:
+//!
+//! - a [`KnownAttribute`] converts an attribute to a typed value, e.g. [`Exposed`] to the
+//!   globals it lists, and is looked up by [`ExtendedAttributeList::get`]
+//! - a [`Registry`] declares the forms of the known attributes and the constructs they can be
+//!   used on, and [`Registry::check`] reports the attributes that are unknown, written in
+//!   another form or used on another construct
+//!
+//! [`Registry::standard`] knows the extended attributes of the WebIDL specification. Others,
+//! e.g. the ones of a browser engine, can be registered, and implement [`KnownAttribute`] to be
+//! looked up.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::registry::{AttributeSchema, Position, Registry, Shape};
+//!
+//! let definitions = weedle::parse("
+//!     [Exposed=Window, Clamp] interface Node {
+//!         [Throws] undefined normalize();
+//!         [Reflect] attribute DOMString title;
+//!     };
+//! ").unwrap();
+//!
+//! let mut registry = Registry::standard();
+//! registry.register(AttributeSchema::new("Throws", &[Shape::NoArgs], &[Position::Operation]));
+//!
+//! let messages: Vec<_> = registry
+//!     .check(&definitions)
+//!     .into_iter()
+//!     .map(|diagnostic| diagnostic.message)
+//!     .collect();
+//! assert_eq!(
+//!     messages,
+//!     [
+//!         "Extended attribute `Clamp` cannot be used on an interface",
+//!         "Unknown extended attribute `Reflect`",
+//!     ]
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::argument::{ArgumentList, SingleArgument, VariadicArgument};
+use crate::attribute::{ExtendedAttribute, ExtendedAttributeList};
+use crate::diagnostic::Diagnostic;
+use crate::dictionary::FieldDictionaryMember;
+use crate::interface::{
+    ConstructorInterfaceMember, DoubleTypedAsyncIterable, DoubleTypedIterable,
+    MaplikeInterfaceMember, SetlikeInterfaceMember, SingleTypedAsyncIterable, SingleTypedIterable,
+    StringifierMember,
+};
+use crate::members::{
+    AttributeInterfaceMember, AttributeMixinMember, AttributeNamespaceMember, ConstMember,
+    OperationInterfaceMember, RegularOperationMember,
+};
+use crate::span::Spanned;
+use crate::types::{AttributedNonAnyType, AttributedType};
+use crate::visit::{Visit, Walk};
+use crate::{
+    CallbackDefinition, CallbackInterfaceDefinition, Definition, DictionaryDefinition,
+    EnumDefinition, IncludesStatementDefinition, InterfaceDefinition, InterfaceMixinDefinition,
+    NamespaceDefinition, PartialDictionaryDefinition, PartialInterfaceDefinition,
+    PartialInterfaceMixinDefinition, PartialNamespaceDefinition, TypedefDefinition,
+};
+
+/// An extended attribute with a typed value
+pub trait KnownAttribute<'b>: Sized {
+    /// The name of the attribute
+    const NAME: &'static str;
+
+    /// Returns the value of the attribute, or `None` if it is written in a form without one
+    fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self>;
+}
+
+macro_rules! flag_attributes {
+    ($($(#[$doc:meta])* $name:ident,)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+            pub struct $name;
+
+            impl<'b> KnownAttribute<'b> for $name {
+                const NAME: &'static str = stringify!($name);
+
+                fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self> {
+                    matches!(attribute, ExtendedAttribute::NoArgs(_)).then_some($name)
+                }
+            }
+        )*
+    };
+}
+
+flag_attributes! {
+    /// `[AllowResizable]`, accepting resizable buffers
+    AllowResizable,
+    /// `[AllowShared]`, accepting buffers backed by a `SharedArrayBuffer`
+    AllowShared,
+    /// `[Clamp]`, clamping out of range integers
+    Clamp,
+    /// `[CrossOriginIsolated]`, exposing the construct in cross-origin isolated contexts only
+    CrossOriginIsolated,
+    /// `[Default]`, giving a `toJSON` operation the default behavior
+    Default,
+    /// `[EnforceRange]`, throwing on out of range integers
+    EnforceRange,
+    /// `[NewObject]`, returning a new object on every call
+    NewObject,
+    /// `[Replaceable]`, letting an attribute be shadowed by assignment
+    Replaceable,
+    /// `[SameObject]`, returning the same object on every access
+    SameObject,
+    /// `[SecureContext]`, exposing the construct in secure contexts only
+    SecureContext,
+    /// `[Unscopable]`, hiding a member from `with` statements
+    Unscopable,
+    LegacyLenientSetter,
+    LegacyLenientThis,
+    LegacyNoInterfaceObject,
+    LegacyNullToEmptyString,
+    LegacyOverrideBuiltIns,
+    LegacyTreatNonObjectAsNull,
+    LegacyUnenumerableNamedProperties,
+    LegacyUnforgeable,
+}
+
+/// `[Exposed]`, listing the globals a construct is exposed in
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Exposed<'b> {
+    /// `[Exposed=*]`, exposed in every global
+    Wildcard,
+    Globals(Vec<&'b str>),
+}
+
+impl<'b> KnownAttribute<'b> for Exposed<'b> {
+    const NAME: &'static str = "Exposed";
+
+    fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self> {
+        match attribute {
+            ExtendedAttribute::Wildcard(_) => Some(Exposed::Wildcard),
+            _ => identifiers(attribute).map(Exposed::Globals),
+        }
+    }
+}
+
+/// `[Global]`, naming the globals an interface is the global object of
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Global<'b>(pub Vec<&'b str>);
+
+impl<'b> KnownAttribute<'b> for Global<'b> {
+    const NAME: &'static str = "Global";
+
+    fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self> {
+        identifiers(attribute).map(Global)
+    }
+}
+
+/// `[LegacyWindowAlias]`, naming the aliases of an interface on `Window`
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LegacyWindowAlias<'b>(pub Vec<&'b str>);
+
+impl<'b> KnownAttribute<'b> for LegacyWindowAlias<'b> {
+    const NAME: &'static str = "LegacyWindowAlias";
+
+    fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self> {
+        identifiers(attribute).map(LegacyWindowAlias)
+    }
+}
+
+/// `[PutForwards]`, naming the attribute assignments are forwarded to
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PutForwards<'b>(pub &'b str);
+
+impl<'b> KnownAttribute<'b> for PutForwards<'b> {
+    const NAME: &'static str = "PutForwards";
+
+    fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self> {
+        match attribute {
+            ExtendedAttribute::Ident(attribute) => Some(PutForwards(&attribute.rhs.0)),
+            _ => None,
+        }
+    }
+}
+
+/// `[LegacyNamespace]`, naming the namespace an interface is exposed in
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LegacyNamespace<'b>(pub &'b str);
+
+impl<'b> KnownAttribute<'b> for LegacyNamespace<'b> {
+    const NAME: &'static str = "LegacyNamespace";
+
+    fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self> {
+        match attribute {
+            ExtendedAttribute::Ident(attribute) => Some(LegacyNamespace(&attribute.rhs.0)),
+            _ => None,
+        }
+    }
+}
+
+/// `[LegacyFactoryFunction]`, declaring a named constructor, e.g. `Image`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LegacyFactoryFunction<'b> {
+    pub name: &'b str,
+    pub arguments: &'b ArgumentList<'b>,
+}
+
+impl<'b> KnownAttribute<'b> for LegacyFactoryFunction<'b> {
+    const NAME: &'static str = "LegacyFactoryFunction";
+
+    fn from_attribute(attribute: &'b ExtendedAttribute<'b>) -> Option<Self> {
+        match attribute {
+            ExtendedAttribute::NamedArgList(attribute) => Some(LegacyFactoryFunction {
+                name: &attribute.rhs_identifier.0,
+                arguments: &attribute.args.body,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the identifier or the identifiers of `[Name=Identifier]` or `[Name=(Identifiers)]`
+fn identifiers<'b>(attribute: &'b ExtendedAttribute<'b>) -> Option<Vec<&'b str>> {
+    match attribute {
+        ExtendedAttribute::Ident(attribute) => Some(vec![&attribute.rhs.0]),
+        ExtendedAttribute::IdentList(attribute) => Some(
+            attribute
+                .list
+                .body
+                .list
+                .iter()
+                .map(|identifier| identifier.0.as_ref())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// A form an extended attribute is written in
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Shape {
+    /// `[Name]`
+    NoArgs,
+    /// `[Name(arguments)]`
+    ArgList,
+    /// `[Name=Other(arguments)]`
+    NamedArgList,
+    /// `[Name=identifier]`
+    Ident,
+    /// `[Name=(identifiers)]`
+    IdentList,
+    /// `[Name=*]`
+    Wildcard,
+    /// `[Name="string"]`
+    String,
+    /// `[Name=("strings")]`
+    StringList,
+    /// `[Name=decimal]`
+    Float,
+    /// `[Name=(decimals)]`
+    FloatList,
+    /// `[Name=integer]`
+    Integer,
+    /// `[Name=(integers)]`
+    IntegerList,
+}
+
+impl Shape {
+    pub fn of(attribute: &ExtendedAttribute<'_>) -> Shape {
+        match attribute {
+            ExtendedAttribute::ArgList(_) => Shape::ArgList,
+            ExtendedAttribute::NamedArgList(_) => Shape::NamedArgList,
+            ExtendedAttribute::IdentList(_) => Shape::IdentList,
+            ExtendedAttribute::Ident(_) => Shape::Ident,
+            ExtendedAttribute::Wildcard(_) => Shape::Wildcard,
+            ExtendedAttribute::String(_) => Shape::String,
+            ExtendedAttribute::StringList(_) => Shape::StringList,
+            ExtendedAttribute::Float(_) => Shape::Float,
+            ExtendedAttribute::FloatList(_) => Shape::FloatList,
+            ExtendedAttribute::Integer(_) => Shape::Integer,
+            ExtendedAttribute::IntegerList(_) => Shape::IntegerList,
+            ExtendedAttribute::NoArgs(_) => Shape::NoArgs,
+        }
+    }
+
+    /// Returns the syntax of an attribute of the name in this form, as in the diagnostics
+    fn syntax(self, name: &str) -> String {
+        match self {
+            Shape::NoArgs => format!("[{name}]"),
+            Shape::ArgList => format!("[{name}(arguments)]"),
+            Shape::NamedArgList => format!("[{name}=Name(arguments)]"),
+            Shape::Ident => format!("[{name}=identifier]"),
+            Shape::IdentList => format!("[{name}=(identifiers)]"),
+            Shape::Wildcard => format!("[{name}=*]"),
+            Shape::String => format!("[{name}=\"string\"]"),
+            Shape::StringList => format!("[{name}=(\"strings\")]"),
+            Shape::Float => format!("[{name}=decimal]"),
+            Shape::FloatList => format!("[{name}=(decimals)]"),
+            Shape::Integer => format!("[{name}=integer]"),
+            Shape::IntegerList => format!("[{name}=(integers)]"),
+        }
+    }
+}
+
+/// A construct extended attributes are written on
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Position {
+    /// An interface or a partial interface
+    Interface,
+    CallbackInterface,
+    /// An interface mixin or a partial interface mixin
+    Mixin,
+    /// A namespace or a partial namespace
+    Namespace,
+    /// A dictionary or a partial dictionary
+    Dictionary,
+    Enum,
+    Typedef,
+    CallbackFunction,
+    Includes,
+    Constant,
+    /// An attribute of an interface, a mixin or a namespace
+    Attribute,
+    /// A regular, special or static operation
+    Operation,
+    Constructor,
+    Stringifier,
+    /// An iterable, async iterable, maplike or setlike declaration
+    Declaration,
+    DictionaryMember,
+    Argument,
+    Type,
+}
+
+impl Position {
+    fn description(self) -> &'static str {
+        match self {
+            Position::Interface => "an interface",
+            Position::CallbackInterface => "a callback interface",
+            Position::Mixin => "an interface mixin",
+            Position::Namespace => "a namespace",
+            Position::Dictionary => "a dictionary",
+            Position::Enum => "an enumeration",
+            Position::Typedef => "a typedef",
+            Position::CallbackFunction => "a callback function",
+            Position::Includes => "an includes statement",
+            Position::Constant => "a constant",
+            Position::Attribute => "an attribute",
+            Position::Operation => "an operation",
+            Position::Constructor => "a constructor",
+            Position::Stringifier => "a stringifier",
+            Position::Declaration => "an iterable, maplike or setlike declaration",
+            Position::DictionaryMember => "a dictionary member",
+            Position::Argument => "an argument",
+            Position::Type => "a type",
+        }
+    }
+}
+
+/// The forms an extended attribute can be written in, and the constructs it can be used on
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AttributeSchema {
+    pub name: String,
+    pub shapes: Vec<Shape>,
+    pub positions: Vec<Position>,
+}
+
+impl AttributeSchema {
+    pub fn new(name: impl Into<String>, shapes: &[Shape], positions: &[Position]) -> Self {
+        AttributeSchema {
+            name: name.into(),
+            shapes: shapes.to_vec(),
+            positions: positions.to_vec(),
+        }
+    }
+}
+
+/// The known extended attributes, by name
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Registry {
+    schemas: BTreeMap<String, AttributeSchema>,
+}
+
+impl Registry {
+    /// Returns a registry without any attribute
+    pub fn new() -> Self {
+        Registry {
+            schemas: BTreeMap::new(),
+        }
+    }
+
+    /// Returns a registry of the extended attributes of the WebIDL specification
+    pub fn standard() -> Self {
+        use Position::*;
+        use Shape::*;
+
+        // The constructs with an exposure set
+        const EXPOSED: &[Position] = &[
+            Interface,
+            CallbackInterface,
+            Mixin,
+            Namespace,
+            Constant,
+            Attribute,
+            Operation,
+            Constructor,
+        ];
+        // The attributes on arguments and dictionary members apply to their types
+        const TYPE: &[Position] = &[Type, Argument, DictionaryMember];
+        let schemas: [(&str, &[Shape], &[Position]); 25] = [
+            (AllowResizable::NAME, &[NoArgs], TYPE),
+            (AllowShared::NAME, &[NoArgs], TYPE),
+            (Clamp::NAME, &[NoArgs], TYPE),
+            (CrossOriginIsolated::NAME, &[NoArgs], EXPOSED),
+            (self::Default::NAME, &[NoArgs], &[Operation]),
+            (EnforceRange::NAME, &[NoArgs], TYPE),
+            (Exposed::NAME, &[Ident, IdentList, Wildcard], EXPOSED),
+            (Global::NAME, &[Ident, IdentList], &[Interface]),
+            (NewObject::NAME, &[NoArgs], &[Operation]),
+            (PutForwards::NAME, &[Ident], &[Attribute]),
+            (Replaceable::NAME, &[NoArgs], &[Attribute]),
+            (SameObject::NAME, &[NoArgs], &[Attribute]),
+            (SecureContext::NAME, &[NoArgs], EXPOSED),
+            (Unscopable::NAME, &[NoArgs], &[Attribute, Operation]),
+            (LegacyFactoryFunction::NAME, &[NamedArgList], &[Interface]),
+            (LegacyLenientSetter::NAME, &[NoArgs], &[Attribute]),
+            (LegacyLenientThis::NAME, &[NoArgs], &[Attribute]),
+            (LegacyNamespace::NAME, &[Ident], &[Interface]),
+            (LegacyNoInterfaceObject::NAME, &[NoArgs], &[Interface]),
+            (LegacyNullToEmptyString::NAME, &[NoArgs], TYPE),
+            (LegacyOverrideBuiltIns::NAME, &[NoArgs], &[Interface]),
+            (
+                LegacyTreatNonObjectAsNull::NAME,
+                &[NoArgs],
+                &[CallbackFunction],
+            ),
+            (
+                LegacyUnenumerableNamedProperties::NAME,
+                &[NoArgs],
+                &[Interface],
+            ),
+            (LegacyUnforgeable::NAME, &[NoArgs], &[Attribute, Operation]),
+            (LegacyWindowAlias::NAME, &[Ident, IdentList], &[Interface]),
+        ];
+
+        let mut registry = Registry::new();
+        for (name, shapes, positions) in schemas {
+            registry.register(AttributeSchema::new(name, shapes, positions));
+        }
+        registry
+    }
+
+    /// Registers an attribute, replacing the one of the same name if any
+    pub fn register(&mut self, schema: AttributeSchema) -> &mut Self {
+        self.schemas.insert(schema.name.clone(), schema);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AttributeSchema> {
+        self.schemas.get(name)
+    }
+
+    /// Checks the extended attributes of the definitions, returning the diagnostics sorted by
+    /// position
+    pub fn check(&self, definitions: &[Definition<'_>]) -> Vec<Diagnostic> {
+        let mut checker = Checker {
+            registry: self,
+            diagnostics: vec![],
+        };
+        for definition in definitions {
+            definition.accept(&mut checker);
+        }
+        let mut diagnostics = checker.diagnostics;
+        diagnostics.sort_by(|a, b| (a.span.start, &a.message).cmp(&(b.span.start, &b.message)));
+        diagnostics.dedup();
+        diagnostics
+    }
+}
+
+struct Checker<'r> {
+    registry: &'r Registry,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn check(&mut self, attributes: &Option<ExtendedAttributeList<'_>>, position: Position) {
+        for attribute in attributes.iter().flat_map(|list| &list.body.list) {
+            let identifier = attribute.identifier();
+            let name = identifier.as_str();
+            let Some(schema) = self.registry.get(name) else {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("Unknown extended attribute `{name}`"), identifier.1)
+                        .with_label("unknown attribute"),
+                );
+                continue;
+            };
+
+            if !schema.shapes.contains(&Shape::of(attribute)) {
+                let forms: Vec<_> = schema
+                    .shapes
+                    .iter()
+                    .map(|shape| format!("`{}`", shape.syntax(name)))
+                    .collect();
+                let forms = match forms.split_last() {
+                    Some((last, [])) => last.clone(),
+                    Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+                    None => "no form".to_owned(),
+                };
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("Extended attribute `{name}` must be written as {forms}"),
+                        attribute.span(),
+                    )
+                    .with_label("wrong form"),
+                );
+            }
+            if !schema.positions.contains(&position) {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "Extended attribute `{name}` cannot be used on {}",
+                            position.description()
+                        ),
+                        identifier.1,
+                    )
+                    .with_label("not allowed here"),
+                );
+            }
+        }
+    }
+}
+
+macro_rules! check_attributes {
+    ($($method:ident($node:ident) => $position:ident,)*) => {
+        impl<'a> Visit<'a> for Checker<'_> {
+            $(
+                fn $method(&mut self, node: &$node<'a>) {
+                    self.check(&node.attributes, Position::$position);
+                    node.walk(self);
+                }
+            )*
+        }
+    };
+}
+
+check_attributes! {
+    visit_interface_definition(InterfaceDefinition) => Interface,
+    visit_partial_interface_definition(PartialInterfaceDefinition) => Interface,
+    visit_callback_interface_definition(CallbackInterfaceDefinition) => CallbackInterface,
+    visit_interface_mixin_definition(InterfaceMixinDefinition) => Mixin,
+    visit_partial_interface_mixin_definition(PartialInterfaceMixinDefinition) => Mixin,
+    visit_namespace_definition(NamespaceDefinition) => Namespace,
+    visit_partial_namespace_definition(PartialNamespaceDefinition) => Namespace,
+    visit_dictionary_definition(DictionaryDefinition) => Dictionary,
+    visit_partial_dictionary_definition(PartialDictionaryDefinition) => Dictionary,
+    visit_enum_definition(EnumDefinition) => Enum,
+    visit_typedef_definition(TypedefDefinition) => Typedef,
+    visit_callback_definition(CallbackDefinition) => CallbackFunction,
+    visit_includes_statement_definition(IncludesStatementDefinition) => Includes,
+    visit_const_member(ConstMember) => Constant,
+    visit_attribute_interface_member(AttributeInterfaceMember) => Attribute,
+    visit_attribute_mixin_member(AttributeMixinMember) => Attribute,
+    visit_attribute_namespace_member(AttributeNamespaceMember) => Attribute,
+    visit_operation_interface_member(OperationInterfaceMember) => Operation,
+    visit_regular_operation_member(RegularOperationMember) => Operation,
+    visit_constructor_interface_member(ConstructorInterfaceMember) => Constructor,
+    visit_stringifier_member(StringifierMember) => Stringifier,
+    visit_single_typed_iterable(SingleTypedIterable) => Declaration,
+    visit_double_typed_iterable(DoubleTypedIterable) => Declaration,
+    visit_single_typed_async_iterable(SingleTypedAsyncIterable) => Declaration,
+    visit_double_typed_async_iterable(DoubleTypedAsyncIterable) => Declaration,
+    visit_maplike_interface_member(MaplikeInterfaceMember) => Declaration,
+    visit_setlike_interface_member(SetlikeInterfaceMember) => Declaration,
+    visit_field_dictionary_member(FieldDictionaryMember) => DictionaryMember,
+    visit_single_argument(SingleArgument) => Argument,
+    visit_variadic_argument(VariadicArgument) => Argument,
+    visit_attributed_type(AttributedType) => Type,
+    visit_attributed_non_any_type(AttributedNonAnyType) => Type,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Parse;
+
+    fn messages(registry: &Registry, source: &str) -> Vec<String> {
+        let definitions = crate::parse(source).unwrap();
+        registry
+            .check(&definitions)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn should_get_typed_attributes() {
+        let definitions = crate::parse(
+            "
+            [Exposed=*, LegacyFactoryFunction=Image(unsigned long width), LegacyNoInterfaceObject]
+            interface HTMLImageElement {};
+            ",
+        )
+        .unwrap();
+        let Definition::Interface(interface) = &definitions[0] else {
+            unreachable!()
+        };
+        let attributes = interface.attributes.as_ref().unwrap();
+
+        assert_eq!(attributes.get::<Exposed>(), Some(Exposed::Wildcard));
+        assert_eq!(
+            attributes.get::<LegacyNoInterfaceObject>(),
+            Some(LegacyNoInterfaceObject)
+        );
+        let factory = attributes.get::<LegacyFactoryFunction>().unwrap();
+        assert_eq!(factory.name, "Image");
+        assert_eq!(factory.arguments.list.len(), 1);
+        assert_eq!(attributes.get::<SecureContext>(), None);
+        assert_eq!(attributes.get::<Global>(), None);
+    }
+
+    #[test]
+    fn should_ignore_attributes_in_other_forms() {
+        let (_, attributes) =
+            ExtendedAttributeList::parse("[SecureContext=Window, Exposed]").unwrap();
+
+        assert_eq!(attributes.get::<SecureContext>(), None);
+        assert_eq!(attributes.get::<Exposed>(), None);
+        assert!(attributes.find("Exposed").is_some());
+    }
+
+    #[test]
+    fn should_check_standard_attributes() {
+        assert_eq!(
+            messages(
+                &Registry::standard(),
+                "
+                [Exposed=Window, Global=(Window, Worker), LegacyWindowAlias]
+                interface Window {
+                    [PutForwards=href, LegacyUnforgeable] readonly attribute Location location;
+                    [NewObject, Clamp] Node cloneNode([Clamp] optional unsigned long depth);
+                    attribute [EnforceRange, SameObject] long length;
+                    [Exposed=(Window)] iterable<Node>;
+                };
+                [LegacyTreatNonObjectAsNull] callback Handler = any (any event);
+                [Reflect] typedef long Count;
+                ",
+            ),
+            [
+                "Extended attribute `LegacyWindowAlias` must be written as \
+                 `[LegacyWindowAlias=identifier]` or `[LegacyWindowAlias=(identifiers)]`",
+                "Extended attribute `Clamp` cannot be used on an operation",
+                "Extended attribute `SameObject` cannot be used on a type",
+                "Extended attribute `Exposed` cannot be used on an iterable, maplike or setlike \
+                 declaration",
+                "Unknown extended attribute `Reflect`",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_check_registered_attributes() {
+        let mut registry = Registry::new();
+        registry
+            .register(AttributeSchema::new(
+                "Throws",
+                &[Shape::NoArgs],
+                &[Position::Operation, Position::Attribute],
+            ))
+            .register(AttributeSchema::new(
+                "Reflect",
+                &[Shape::NoArgs, Shape::String],
+                &[Position::Attribute],
+            ));
+
+        assert_eq!(
+            messages(
+                &registry,
+                r#"
+                interface A {
+                    [Throws, Reflect="for"] attribute DOMString htmlFor;
+                    [Throws=Always, Reflect] undefined f();
+                };
+                [Exposed=Window] interface B {};
+                "#,
+            ),
+            [
+                "Extended attribute `Throws` must be written as `[Throws]`",
+                "Extended attribute `Reflect` cannot be used on an operation",
+                "Unknown extended attribute `Exposed`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,620 @@
+//! Merges partial definitions and includes statements into a model indexed by name.
+//!
+//! The members of partial definitions are appended to their main definition, then the
+//! members of the mixins are appended to the interfaces including them, in the order of the
+//! sources.
+//!
+//! ### Example
+//!
+//! ```
+//! let window = weedle::parse("
+//!     interface Window {
+//!         readonly attribute Document document;
+//!     };
+//!     Window includes WindowSessionStorage;
+//! ").unwrap();
+//! let storage = weedle::parse("
+//!     interface mixin WindowSessionStorage {
+//!         readonly attribute Storage sessionStorage;
+//!     };
+//!     partial interface Window {
+//!         undefined close();
+//!     };
+//! ").unwrap();
+//!
+//! let (model, errors) = weedle::resolve::resolve(&[window, storage]);
+//! assert!(errors.is_empty());
+//!
+//! let window = &model.interfaces["Window"];
+//! assert_eq!(window.definition.members.body.len(), 3);
+//! assert_eq!(window.partials.len(), 1);
+//! assert_eq!(window.includes[0].0, "WindowSessionStorage");
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::common::Identifier;
+use crate::diagnostic::Diagnostic;
+use crate::dictionary::DictionaryMember;
+use crate::interface::{CallbackInterfaceMember, InterfaceMember};
+use crate::mixin::MixinMember;
+use crate::namespace::NamespaceMember;
+use crate::{
+    CallbackDefinition, CallbackInterfaceDefinition, Definition, DictionaryDefinition,
+    EnumDefinition, IncludesStatementDefinition, InterfaceDefinition, InterfaceMixinDefinition,
+    NamespaceDefinition, PartialDictionaryDefinition, PartialInterfaceDefinition,
+    PartialInterfaceMixinDefinition, PartialNamespaceDefinition, TypedefDefinition,
+};
+
+/// The definitions of one or more sources, indexed by name
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Model<'a> {
+    pub interfaces: BTreeMap<String, Interface<'a>>,
+    pub callback_interfaces: BTreeMap<String, CallbackInterfaceDefinition<'a>>,
+    pub mixins: BTreeMap<String, Mixin<'a>>,
+    pub namespaces: BTreeMap<String, Namespace<'a>>,
+    pub dictionaries: BTreeMap<String, Dictionary<'a>>,
+    pub callbacks: BTreeMap<String, CallbackDefinition<'a>>,
+    pub enums: BTreeMap<String, EnumDefinition<'a>>,
+    pub typedefs: BTreeMap<String, TypedefDefinition<'a>>,
+}
+
+/// An interface with the members of its partial definitions and of the mixins it includes
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Interface<'a> {
+    /// The main definition, with all the members
+    pub definition: InterfaceDefinition<'a>,
+    /// The partial definitions, e.g. for their extended attributes
+    pub partials: Vec<PartialInterfaceDefinition<'a>>,
+    /// The mixins included by the interface
+    pub includes: Vec<Identifier<'a>>,
+}
+
+/// A mixin with the members of its partial definitions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mixin<'a> {
+    /// The main definition, with all the members
+    pub definition: InterfaceMixinDefinition<'a>,
+    pub partials: Vec<PartialInterfaceMixinDefinition<'a>>,
+}
+
+/// A namespace with the members of its partial definitions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Namespace<'a> {
+    /// The main definition, with all the members
+    pub definition: NamespaceDefinition<'a>,
+    pub partials: Vec<PartialNamespaceDefinition<'a>>,
+}
+
+/// A dictionary with the members of its partial definitions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dictionary<'a> {
+    /// The main definition, with all the members
+    pub definition: DictionaryDefinition<'a>,
+    pub partials: Vec<PartialDictionaryDefinition<'a>>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ResolveErrorKind {
+    /// Two definitions have the same name
+    DuplicateDefinition,
+    /// A partial definition has no main definition of the same kind
+    OrphanPartial,
+    /// Two members of a definition have the same name, and are not overloaded operations
+    DuplicateMember,
+    /// The left side of an includes statement is not an interface
+    UnknownInterface,
+    /// The right side of an includes statement is not a mixin
+    UnknownMixin,
+}
+
+/// An error found while resolving definitions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResolveError<'a> {
+    pub kind: ResolveErrorKind,
+    /// The name the error is about
+    pub identifier: Identifier<'a>,
+    /// The index of the source of `identifier` in the sources given to [`resolve`]
+    pub source: usize,
+    /// The definition of the duplicate member
+    pub parent: Option<Identifier<'a>>,
+    /// The first declaration of the duplicate definition or member, with the index of its
+    /// source
+    pub previous: Option<(Identifier<'a>, usize)>,
+}
+
+impl ResolveError<'_> {
+    pub fn message(&self) -> String {
+        let name = &self.identifier.0;
+        match self.kind {
+            ResolveErrorKind::DuplicateDefinition => format!("`{name}` is defined more than once"),
+            ResolveErrorKind::OrphanPartial => {
+                format!("Partial definition of `{name}` has no main definition")
+            }
+            ResolveErrorKind::DuplicateMember => match &self.parent {
+                Some(parent) => format!("Duplicate member `{name}` in `{}`", parent.0),
+                None => format!("Duplicate member `{name}`"),
+            },
+            ResolveErrorKind::UnknownInterface => format!("Unknown interface `{name}`"),
+            ResolveErrorKind::UnknownMixin => format!("Unknown mixin `{name}`"),
+        }
+    }
+}
+
+impl fmt::Display for ResolveError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for ResolveError<'_> {}
+
+impl From<&ResolveError<'_>> for Diagnostic {
+    fn from(err: &ResolveError<'_>) -> Self {
+        let diagnostic = Diagnostic::error(err.message(), err.identifier.1);
+        match err.kind {
+            ResolveErrorKind::DuplicateDefinition | ResolveErrorKind::DuplicateMember => {
+                diagnostic.with_label("defined again here")
+            }
+            ResolveErrorKind::OrphanPartial => diagnostic.with_label("not defined elsewhere"),
+            ResolveErrorKind::UnknownInterface | ResolveErrorKind::UnknownMixin => diagnostic,
+        }
+    }
+}
+
+/// Merges the definitions of the sources into a model, with the errors found on the way
+///
+/// Invalid definitions, duplicate definitions and orphan partial definitions are left out of
+/// the model. Duplicate members are kept.
+pub fn resolve<'a, D>(sources: &[D]) -> (Model<'a>, Vec<ResolveError<'a>>)
+where
+    D: AsRef<[Definition<'a>]>,
+{
+    let mut resolver = Resolver::default();
+    let definitions = || {
+        sources
+            .iter()
+            .enumerate()
+            .flat_map(|(source, definitions)| {
+                definitions.as_ref().iter().map(move |def| (source, def))
+            })
+    };
+
+    // Partial definitions can come before their main definition
+    for (source, definition) in definitions() {
+        resolver.add_definition(source, definition);
+    }
+    for (source, definition) in definitions() {
+        resolver.add_partial(source, definition);
+    }
+    for (source, definition) in definitions() {
+        if let Definition::IncludesStatement(includes) = definition {
+            resolver.add_includes(source, includes);
+        }
+    }
+
+    let Resolver {
+        model, mut errors, ..
+    } = resolver;
+    errors.sort_by_key(|err| (err.source, err.identifier.1.start));
+    (model, errors)
+}
+
+/// A named member, with whether it is an operation that can be overloaded
+trait NamedMember<'a> {
+    fn name(&self) -> Option<(Identifier<'a>, bool)>;
+}
+
+impl<'a> NamedMember<'a> for InterfaceMember<'a> {
+    fn name(&self) -> Option<(Identifier<'a>, bool)> {
+        match self {
+            InterfaceMember::Const(member) => Some((member.identifier.clone(), false)),
+            InterfaceMember::Attribute(member) => Some((member.identifier.clone(), false)),
+            InterfaceMember::Operation(member) => member.identifier.clone().map(|id| (id, true)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> NamedMember<'a> for CallbackInterfaceMember<'a> {
+    fn name(&self) -> Option<(Identifier<'a>, bool)> {
+        match self {
+            CallbackInterfaceMember::Const(member) => Some((member.identifier.clone(), false)),
+            CallbackInterfaceMember::Operation(member) => {
+                member.identifier.clone().map(|id| (id, true))
+            }
+            CallbackInterfaceMember::Invalid(_) => None,
+        }
+    }
+}
+
+impl<'a> NamedMember<'a> for MixinMember<'a> {
+    fn name(&self) -> Option<(Identifier<'a>, bool)> {
+        match self {
+            MixinMember::Const(member) => Some((member.identifier.clone(), false)),
+            MixinMember::Attribute(member) => Some((member.identifier.clone(), false)),
+            MixinMember::Operation(member) => member.identifier.clone().map(|id| (id, true)),
+            MixinMember::Stringifier(_) | MixinMember::Invalid(_) => None,
+        }
+    }
+}
+
+impl<'a> NamedMember<'a> for NamespaceMember<'a> {
+    fn name(&self) -> Option<(Identifier<'a>, bool)> {
+        match self {
+            NamespaceMember::Const(member) => Some((member.identifier.clone(), false)),
+            NamespaceMember::Attribute(member) => Some((member.identifier.clone(), false)),
+            NamespaceMember::Operation(member) => member.identifier.clone().map(|id| (id, true)),
+            NamespaceMember::Invalid(_) => None,
+        }
+    }
+}
+
+impl<'a> NamedMember<'a> for DictionaryMember<'a> {
+    fn name(&self) -> Option<(Identifier<'a>, bool)> {
+        match self {
+            DictionaryMember::Field(member) => Some((member.identifier.clone(), false)),
+            DictionaryMember::Invalid(_) => None,
+        }
+    }
+}
+
+/// A member name, with its source and whether it is an operation
+type MemberName<'a> = (Identifier<'a>, usize, bool);
+
+#[derive(Default)]
+struct Resolver<'a> {
+    model: Model<'a>,
+    /// The main definitions by name, with their source
+    definitions: HashMap<String, (Identifier<'a>, usize)>,
+    /// The member names of every definition
+    members: HashMap<String, HashMap<String, MemberName<'a>>>,
+    /// The source of every member of the mixins, as members of partials are appended
+    mixin_member_sources: HashMap<String, Vec<usize>>,
+    errors: Vec<ResolveError<'a>>,
+}
+
+// Appends the members of a partial definition to the main definition in `$map`
+macro_rules! merge_partial {
+    ($resolver:ident, $source:ident, $partial:ident, $map:ident) => {{
+        let name = &$partial.identifier;
+        if $resolver.model.$map.contains_key(name.as_str()) {
+            $resolver.add_members(name, $source, &$partial.members.body);
+            let merged = $resolver.model.$map.get_mut(name.as_str()).unwrap();
+            merged
+                .definition
+                .members
+                .body
+                .extend($partial.members.body.iter().cloned());
+            merged.partials.push($partial.clone());
+            true
+        } else {
+            $resolver.error(
+                ResolveErrorKind::OrphanPartial,
+                $partial.identifier.clone(),
+                $source,
+            );
+            false
+        }
+    }};
+}
+
+impl<'a> Resolver<'a> {
+    fn add_definition(&mut self, source: usize, definition: &Definition<'a>) {
+        let identifier = match definition {
+            Definition::Callback(def) => &def.identifier,
+            Definition::CallbackInterface(def) => &def.identifier,
+            Definition::Interface(def) => &def.identifier,
+            Definition::InterfaceMixin(def) => &def.identifier,
+            Definition::Namespace(def) => &def.identifier,
+            Definition::Dictionary(def) => &def.identifier,
+            Definition::Enum(def) => &def.identifier,
+            Definition::Typedef(def) => &def.identifier,
+            _ => return,
+        };
+        if let Some(previous) = self.definitions.get(identifier.as_str()) {
+            self.errors.push(ResolveError {
+                kind: ResolveErrorKind::DuplicateDefinition,
+                identifier: identifier.clone(),
+                source,
+                parent: None,
+                previous: Some(previous.clone()),
+            });
+            return;
+        }
+        self.definitions
+            .insert(identifier.0.to_string(), (identifier.clone(), source));
+
+        let name = identifier.0.to_string();
+        match definition {
+            Definition::Callback(def) => {
+                self.model.callbacks.insert(name, def.clone());
+            }
+            Definition::CallbackInterface(def) => {
+                self.add_members(identifier, source, &def.members.body);
+                self.model.callback_interfaces.insert(name, def.clone());
+            }
+            Definition::Interface(def) => {
+                self.add_members(identifier, source, &def.members.body);
+                let interface = Interface {
+                    definition: def.clone(),
+                    partials: vec![],
+                    includes: vec![],
+                };
+                self.model.interfaces.insert(name, interface);
+            }
+            Definition::InterfaceMixin(def) => {
+                self.add_members(identifier, source, &def.members.body);
+                let mixin = Mixin {
+                    definition: def.clone(),
+                    partials: vec![],
+                };
+                self.mixin_member_sources
+                    .insert(name.clone(), vec![source; def.members.body.len()]);
+                self.model.mixins.insert(name, mixin);
+            }
+            Definition::Namespace(def) => {
+                self.add_members(identifier, source, &def.members.body);
+                let namespace = Namespace {
+                    definition: def.clone(),
+                    partials: vec![],
+                };
+                self.model.namespaces.insert(name, namespace);
+            }
+            Definition::Dictionary(def) => {
+                self.add_members(identifier, source, &def.members.body);
+                let dictionary = Dictionary {
+                    definition: def.clone(),
+                    partials: vec![],
+                };
+                self.model.dictionaries.insert(name, dictionary);
+            }
+            Definition::Enum(def) => {
+                self.model.enums.insert(name, def.clone());
+            }
+            Definition::Typedef(def) => {
+                self.model.typedefs.insert(name, def.clone());
+            }
+            _ => unreachable!("Only main definitions are named above"),
+        }
+    }
+
+    fn add_partial(&mut self, source: usize, definition: &Definition<'a>) {
+        match definition {
+            Definition::PartialInterface(partial) => {
+                merge_partial!(self, source, partial, interfaces);
+            }
+            Definition::PartialInterfaceMixin(partial) => {
+                if merge_partial!(self, source, partial, mixins) {
+                    let sources = self
+                        .mixin_member_sources
+                        .entry(partial.identifier.0.to_string())
+                        .or_default();
+                    sources.extend(std::iter::repeat_n(source, partial.members.body.len()));
+                }
+            }
+            Definition::PartialNamespace(partial) => {
+                merge_partial!(self, source, partial, namespaces);
+            }
+            Definition::PartialDictionary(partial) => {
+                merge_partial!(self, source, partial, dictionaries);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_includes(&mut self, source: usize, includes: &IncludesStatementDefinition<'a>) {
+        let interface = &includes.lhs_identifier;
+        let mixin = &includes.rhs_identifier;
+        if !self.model.interfaces.contains_key(interface.as_str()) {
+            self.error(
+                ResolveErrorKind::UnknownInterface,
+                interface.clone(),
+                source,
+            );
+            return;
+        }
+        let members = match self.model.mixins.get(mixin.as_str()) {
+            Some(mixin) => mixin.definition.members.body.clone(),
+            None => {
+                self.error(ResolveErrorKind::UnknownMixin, mixin.clone(), source);
+                return;
+            }
+        };
+
+        let sources = self.mixin_member_sources[mixin.as_str()].clone();
+        for (member, &member_source) in members.iter().zip(&sources) {
+            self.add_members(interface, member_source, std::slice::from_ref(member));
+        }
+        let merged = self.model.interfaces.get_mut(interface.as_str()).unwrap();
+        merged
+            .definition
+            .members
+            .body
+            .extend(members.into_iter().map(InterfaceMember::from));
+        merged.includes.push(mixin.clone());
+    }
+
+    /// Records the member names of a definition, reporting the duplicates
+    fn add_members<M: NamedMember<'a>>(
+        &mut self,
+        parent: &Identifier<'a>,
+        source: usize,
+        members: &[M],
+    ) {
+        let names = self.members.entry(parent.0.to_string()).or_default();
+        for (identifier, operation) in members.iter().filter_map(NamedMember::name) {
+            match names.get(identifier.as_str()) {
+                // Overloaded operations
+                Some((_, _, true)) if operation => {}
+                Some((previous, previous_source, _)) => self.errors.push(ResolveError {
+                    kind: ResolveErrorKind::DuplicateMember,
+                    identifier,
+                    source,
+                    parent: Some(parent.clone()),
+                    previous: Some((previous.clone(), *previous_source)),
+                }),
+                None => {
+                    names.insert(identifier.0.to_string(), (identifier, source, operation));
+                }
+            }
+        }
+    }
+
+    fn error(&mut self, kind: ResolveErrorKind, identifier: Identifier<'a>, source: usize) {
+        self.errors.push(ResolveError {
+            kind,
+            identifier,
+            source,
+            parent: None,
+            previous: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kinds<'e>(errors: &'e [ResolveError<'_>]) -> Vec<(ResolveErrorKind, &'e str)> {
+        errors
+            .iter()
+            .map(|err| (err.kind, err.identifier.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn should_merge_partials_in_source_order() {
+        let first = crate::parse(
+            "
+            partial dictionary Options { long b; };
+            dictionary Options { long a; };
+            namespace Console { undefined log(); };
+            ",
+        )
+        .unwrap();
+        let second = crate::parse(
+            "
+            partial dictionary Options { long c; };
+            partial namespace Console { undefined warn(); };
+            ",
+        )
+        .unwrap();
+
+        let (model, errors) = resolve(&[first, second]);
+        assert_eq!(errors, []);
+
+        let options = &model.dictionaries["Options"];
+        let names: Vec<_> = options
+            .definition
+            .members
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                DictionaryMember::Field(member) => Some(member.identifier.as_str()),
+                DictionaryMember::Invalid(_) => None,
+            })
+            .collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(options.partials.len(), 2);
+        assert_eq!(model.namespaces["Console"].definition.members.body.len(), 2);
+    }
+
+    #[test]
+    fn should_include_mixins() {
+        let defs = crate::parse(
+            "
+            interface Window { attribute long a; };
+            Window includes Mixin;
+            interface mixin Mixin { stringifier attribute DOMString b; };
+            partial interface mixin Mixin { undefined c(); };
+            ",
+        )
+        .unwrap();
+
+        let (model, errors) = resolve(&[defs]);
+        assert_eq!(errors, []);
+
+        let members = &model.interfaces["Window"].definition.members.body;
+        assert_eq!(members.len(), 3);
+        match &members[1] {
+            InterfaceMember::Attribute(attribute) => {
+                assert_eq!(attribute.identifier.0, "b");
+                assert!(attribute.modifier.is_some());
+            }
+            member => panic!("Expected an attribute, got {member:?}"),
+        }
+        assert!(matches!(&members[2], InterfaceMember::Operation(_)));
+        assert_eq!(model.mixins["Mixin"].definition.members.body.len(), 2);
+    }
+
+    #[test]
+    fn should_report_unresolved_names() {
+        let defs = crate::parse(
+            "
+            partial interface Missing {};
+            interface Window {};
+            Window includes Unknown;
+            Nothing includes Window;
+            partial dictionary Window {};
+            ",
+        )
+        .unwrap();
+
+        let (model, errors) = resolve(&[defs]);
+        assert_eq!(
+            kinds(&errors),
+            [
+                (ResolveErrorKind::OrphanPartial, "Missing"),
+                (ResolveErrorKind::UnknownMixin, "Unknown"),
+                (ResolveErrorKind::UnknownInterface, "Nothing"),
+                (ResolveErrorKind::OrphanPartial, "Window"),
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Partial definition of `Missing` has no main definition"
+        );
+        assert_eq!(model.interfaces.len(), 1);
+    }
+
+    #[test]
+    fn should_report_duplicates() {
+        let first = crate::parse(
+            "
+            interface A {
+                attribute long a;
+                undefined f();
+                undefined f(long x);
+            };
+            interface mixin M { const long a = 1; };
+            A includes M;
+            ",
+        )
+        .unwrap();
+        let second = crate::parse(
+            "
+            partial interface A { attribute long f; };
+            enum A { \"a\" };
+            ",
+        )
+        .unwrap();
+
+        let (model, errors) = resolve(&[first, second]);
+        assert_eq!(
+            kinds(&errors),
+            [
+                (ResolveErrorKind::DuplicateMember, "a"),
+                (ResolveErrorKind::DuplicateMember, "f"),
+                (ResolveErrorKind::DuplicateDefinition, "A"),
+            ]
+        );
+        assert_eq!(errors[0].to_string(), "Duplicate member `a` in `A`");
+        assert_eq!(errors[1].source, 1);
+        assert_eq!(errors[1].previous.as_ref().unwrap().1, 0);
+        assert!(model.enums.is_empty());
+        assert_eq!(model.interfaces["A"].definition.members.body.len(), 5);
+    }
+}
@@ -0,0 +1,281 @@
+//! Source locations of the parsed nodes.
+//!
+//! Every terminal symbol stores the byte range it was lexed from, and every node
+//! implements [`Spanned`] which joins the spans of its children.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::span::Spanned;
+//!
+//! let source = "
+//!     interface Window {
+//!         readonly attribute Storage sessionStorage;
+//!     };
+//! ";
+//! let parsed = weedle::parse(source).unwrap();
+//! let span = parsed[0].span();
+//!
+//! assert_eq!(&source[span.start..span.end], source.trim());
+//! assert_eq!(span.start_location(source).line, 2);
+//! assert_eq!(span.start_location(source).column, 5);
+//! ```
+//!
+//! Note that spans do not participate in comparisons, hashing or `Debug` output of the
+//! nodes, so two nodes parsed from different places still compare equal.
+
+use std::ops::Range;
+
+/// A byte range in the parsed source
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns true if the span covers no input, which is also the case for nodes that
+    /// were not produced by the parser, e.g. `term!(;)`.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the smallest span covering both spans. Empty spans are ignored.
+    pub fn join(self, other: Span) -> Span {
+        if self.is_empty() {
+            return other;
+        }
+        if other.is_empty() {
+            return self;
+        }
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the span moved by `delta` bytes
+    pub fn shifted(self, delta: isize) -> Span {
+        Span {
+            start: self.start.wrapping_add_signed(delta),
+            end: self.end.wrapping_add_signed(delta),
+        }
+    }
+
+    /// Returns the line and column of the start of this span within `source`
+    pub fn start_location(&self, source: &str) -> LineColumn {
+        LineColumn::from_offset(source, self.start)
+    }
+
+    /// Returns the line and column of the end of this span within `source`
+    pub fn end_location(&self, source: &str) -> LineColumn {
+        LineColumn::from_offset(source, self.end)
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+/// A position in the source, both line and column start from 1.
+///
+/// Columns are counted in characters, not in bytes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LineColumn {
+    /// Converts a byte offset of `source` into a line and column pair
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        LineColumn {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// Provides the source location of a node
+pub trait Spanned {
+    /// Returns the byte range of the node, which is empty if the node holds no token.
+    fn span(&self) -> Span;
+}
+
+impl Spanned for Span {
+    fn span(&self) -> Span {
+        *self
+    }
+}
+
+impl<T: Spanned> Spanned for Option<T> {
+    fn span(&self) -> Span {
+        self.as_ref().map(Spanned::span).unwrap_or_default()
+    }
+}
+
+impl<T: Spanned> Spanned for Box<T> {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T: Spanned> Spanned for Vec<T> {
+    fn span(&self) -> Span {
+        self.iter()
+            .fold(Span::default(), |span, item| span.join(item.span()))
+    }
+}
+
+impl<T: Spanned, U: Spanned> Spanned for (T, U) {
+    fn span(&self) -> Span {
+        self.0.span().join(self.1.span())
+    }
+}
+
+impl<T: Spanned, U: Spanned, V: Spanned> Spanned for (T, U, V) {
+    fn span(&self) -> Span {
+        self.0.span().join(self.1.span()).join(self.2.span())
+    }
+}
+
+/// Moves the source location of a node, e.g. after an edit of the source before it
+pub trait Shift {
+    /// Moves every span of the node that is not empty by `delta` bytes
+    fn shift(&mut self, delta: isize);
+}
+
+/// Empty spans are left alone, as they belong to nodes that were not produced by the parser
+impl Shift for Span {
+    fn shift(&mut self, delta: isize) {
+        if !self.is_empty() {
+            *self = self.shifted(delta);
+        }
+    }
+}
+
+impl<T: Shift> Shift for Option<T> {
+    fn shift(&mut self, delta: isize) {
+        if let Some(inner) = self {
+            inner.shift(delta);
+        }
+    }
+}
+
+impl<T: Shift> Shift for Box<T> {
+    fn shift(&mut self, delta: isize) {
+        (**self).shift(delta)
+    }
+}
+
+impl<T: Shift> Shift for Vec<T> {
+    fn shift(&mut self, delta: isize) {
+        for item in self {
+            item.shift(delta);
+        }
+    }
+}
+
+impl<T: Shift, U: Shift> Shift for (T, U) {
+    fn shift(&mut self, delta: isize) {
+        self.0.shift(delta);
+        self.1.shift(delta);
+    }
+}
+
+impl<T: Shift, U: Shift, V: Shift> Shift for (T, U, V) {
+    fn shift(&mut self, delta: isize) {
+        self.0.shift(delta);
+        self.1.shift(delta);
+        self.2.shift(delta);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Parse;
+
+    #[test]
+    fn should_join_spans() {
+        assert_eq!(Span::new(3, 5).join(Span::new(8, 13)), Span::new(3, 13));
+        assert_eq!(Span::default().join(Span::new(8, 13)), Span::new(8, 13));
+        assert_eq!(Span::new(3, 5).join(Span::default()), Span::new(3, 5));
+    }
+
+    #[test]
+    fn should_convert_offsets_to_line_column() {
+        let source = "interface A {\n  attribute long b;\n};";
+        assert_eq!(
+            LineColumn::from_offset(source, 0),
+            LineColumn { line: 1, column: 1 }
+        );
+        assert_eq!(
+            LineColumn::from_offset(source, 16),
+            LineColumn { line: 2, column: 3 }
+        );
+        assert_eq!(
+            LineColumn::from_offset(source, source.len()),
+            LineColumn { line: 3, column: 3 }
+        );
+    }
+
+    #[test]
+    fn should_span_definitions() {
+        let source = "/* comment */ interface A {};\n\ndictionary B { long c; };\n";
+        let parsed = crate::parse(source).unwrap();
+        assert_eq!(&source[parsed[0].span().range()], "interface A {};");
+        assert_eq!(
+            &source[parsed[1].span().range()],
+            "dictionary B { long c; };"
+        );
+        assert_eq!(parsed[1].span().start_location(source).line, 3);
+    }
+
+    #[test]
+    fn should_span_members() {
+        let source = "interface A { [Throws] undefined f(optional long x = 3); };";
+        let parsed = crate::parse(source).unwrap();
+        let interface = match &parsed[0] {
+            crate::Definition::Interface(interface) => interface,
+            _ => unreachable!(),
+        };
+        let member = &interface.members.body[0];
+        assert_eq!(
+            &source[member.span().range()],
+            "[Throws] undefined f(optional long x = 3);"
+        );
+        assert_eq!(&source[interface.identifier.span().range()], "A");
+        assert_eq!(&source[interface.semi_colon.span().range()], ";");
+    }
+
+    #[test]
+    fn should_span_string_with_quotes() {
+        let (_, parsed) = crate::literal::StringLit::parse(r#""value""#).unwrap();
+        assert_eq!(parsed.span(), Span::new(0, 7));
+    }
+
+    #[test]
+    fn should_ignore_spans_on_comparison() {
+        let first = crate::parse("interface A {};").unwrap();
+        let second = crate::parse("  interface   A{ } ;").unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first[0].span(), second[0].span());
+    }
+}
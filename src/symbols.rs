@@ -0,0 +1,754 @@
+//! Links the names used in types to their definitions, and expands typedefs.
+//!
+//! A [`SymbolTable`] indexes the definitions that can be referred to by name. It turns a
+//! [`Type`] of the syntax tree into a [`ResolvedType`], where every name is known to be an
+//! interface, a dictionary, an enumeration, a callback function or a callback interface, and
+//! where typedefs are replaced by the types they stand for.
+//!
+//! The resolved types are normalized:
+//!
+//! - unions are flattened, so `(A or (B or C))` is the same as `(A or B or C)`
+//! - a union with a nullable member type is nullable itself, so `(A? or B)` is the same as
+//!   `(A or B)?`
+//! - nullable types are nullable once, so `T?` where `typedef long? T;` is `long?`
+//!
+//! Extended attributes on types, like `[Clamp] long`, are not kept.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::symbols::{ResolvedType, Symbol, SymbolTable};
+//!
+//! let definitions = weedle::parse("
+//!     interface Blob {};
+//!     typedef (BufferSource or Blob or USVString) BlobPart;
+//!     typedef sequence<BlobPart>? BlobParts;
+//! ").unwrap();
+//! let symbols = SymbolTable::new(&definitions);
+//!
+//! let Some(Symbol::Typedef(parts)) = symbols.get("BlobParts") else {
+//!     panic!("BlobParts is a typedef");
+//! };
+//! // BufferSource is defined by another specification
+//! assert_eq!(
+//!     symbols.expand_lenient(&parts.type_.type_).unwrap(),
+//!     ResolvedType::Nullable(Box::new(ResolvedType::Sequence(Box::new(
+//!         ResolvedType::Union(vec![
+//!             ResolvedType::Unknown("BufferSource".into()),
+//!             ResolvedType::Interface("Blob".into()),
+//!             ResolvedType::USVString,
+//!         ])
+//!     ))))
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::common::Identifier;
+use crate::diagnostic::Diagnostic;
+use crate::types::{
+    ConstType, DistinguishableType, FloatingPointType, IntegerType, MayBeNull, RecordKeyType,
+    SingleType, Type, UnionMemberType, UnionType,
+};
+use crate::visit::{Visit, Walk};
+use crate::{
+    CallbackDefinition, CallbackInterfaceDefinition, Definition, DictionaryDefinition,
+    EnumDefinition, InterfaceDefinition, InterfaceMixinDefinition, NamespaceDefinition,
+    TypedefDefinition,
+};
+
+/// A definition that has a name
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Symbol<'d, 'a> {
+    Interface(&'d InterfaceDefinition<'a>),
+    CallbackInterface(&'d CallbackInterfaceDefinition<'a>),
+    Dictionary(&'d DictionaryDefinition<'a>),
+    Enum(&'d EnumDefinition<'a>),
+    Callback(&'d CallbackDefinition<'a>),
+    Typedef(&'d TypedefDefinition<'a>),
+    /// Not a type, but its name cannot be used by a type either
+    InterfaceMixin(&'d InterfaceMixinDefinition<'a>),
+    /// Not a type, but its name cannot be used by a type either
+    Namespace(&'d NamespaceDefinition<'a>),
+}
+
+impl<'d, 'a> Symbol<'d, 'a> {
+    pub fn identifier(&self) -> &'d Identifier<'a> {
+        match self {
+            Symbol::Interface(def) => &def.identifier,
+            Symbol::CallbackInterface(def) => &def.identifier,
+            Symbol::Dictionary(def) => &def.identifier,
+            Symbol::Enum(def) => &def.identifier,
+            Symbol::Callback(def) => &def.identifier,
+            Symbol::Typedef(def) => &def.identifier,
+            Symbol::InterfaceMixin(def) => &def.identifier,
+            Symbol::Namespace(def) => &def.identifier,
+        }
+    }
+}
+
+/// A type with its names resolved and its typedefs expanded
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ResolvedType {
+    Any,
+    Undefined,
+    Boolean,
+    Byte,
+    Octet,
+    Short,
+    UnsignedShort,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+    Float,
+    UnrestrictedFloat,
+    Double,
+    UnrestrictedDouble,
+    BigInt,
+    ByteString,
+    DOMString,
+    USVString,
+    Object,
+    Symbol,
+    /// `ArrayBuffer`, `DataView` or a typed array, by name
+    BufferSource(&'static str),
+    Sequence(Box<ResolvedType>),
+    FrozenArray(Box<ResolvedType>),
+    ObservableArray(Box<ResolvedType>),
+    /// The key type, then the value type
+    Record(Box<ResolvedType>, Box<ResolvedType>),
+    Promise(Box<ResolvedType>),
+    /// The inner type is never nullable
+    Nullable(Box<ResolvedType>),
+    /// The flattened member types, none of them nullable
+    Union(Vec<ResolvedType>),
+    Interface(String),
+    CallbackInterface(String),
+    Dictionary(String),
+    Enum(String),
+    Callback(String),
+    /// A name with no definition, when expanding leniently
+    Unknown(String),
+}
+
+impl ResolvedType {
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, ResolvedType::Nullable(_))
+    }
+
+    fn nullable(self) -> Self {
+        match self {
+            ResolvedType::Nullable(_) => self,
+            _ => ResolvedType::Nullable(Box::new(self)),
+        }
+    }
+
+    fn union(members: Vec<Self>) -> Self {
+        let mut flattened = vec![];
+        let mut nullable = false;
+        for member in members {
+            let member = match member {
+                ResolvedType::Nullable(inner) => {
+                    nullable = true;
+                    *inner
+                }
+                member => member,
+            };
+            match member {
+                ResolvedType::Union(members) => flattened.extend(members),
+                member => flattened.push(member),
+            }
+        }
+
+        let union = ResolvedType::Union(flattened);
+        if nullable {
+            union.nullable()
+        } else {
+            union
+        }
+    }
+}
+
+/// Writes the type in WebIDL syntax
+impl fmt::Display for ResolvedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ResolvedType as R;
+
+        let keyword = match self {
+            R::Any => "any",
+            R::Undefined => "undefined",
+            R::Boolean => "boolean",
+            R::Byte => "byte",
+            R::Octet => "octet",
+            R::Short => "short",
+            R::UnsignedShort => "unsigned short",
+            R::Long => "long",
+            R::UnsignedLong => "unsigned long",
+            R::LongLong => "long long",
+            R::UnsignedLongLong => "unsigned long long",
+            R::Float => "float",
+            R::UnrestrictedFloat => "unrestricted float",
+            R::Double => "double",
+            R::UnrestrictedDouble => "unrestricted double",
+            R::BigInt => "bigint",
+            R::ByteString => "ByteString",
+            R::DOMString => "DOMString",
+            R::USVString => "USVString",
+            R::Object => "object",
+            R::Symbol => "symbol",
+            R::BufferSource(name) => name,
+            R::Sequence(inner) => return write!(f, "sequence<{inner}>"),
+            R::FrozenArray(inner) => return write!(f, "FrozenArray<{inner}>"),
+            R::ObservableArray(inner) => return write!(f, "ObservableArray<{inner}>"),
+            R::Record(key, value) => return write!(f, "record<{key}, {value}>"),
+            R::Promise(inner) => return write!(f, "Promise<{inner}>"),
+            R::Nullable(inner) => return write!(f, "{inner}?"),
+            R::Union(members) => {
+                f.write_str("(")?;
+                for (i, member) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" or ")?;
+                    }
+                    write!(f, "{member}")?;
+                }
+                return f.write_str(")");
+            }
+            R::Interface(name)
+            | R::CallbackInterface(name)
+            | R::Dictionary(name)
+            | R::Enum(name)
+            | R::Callback(name)
+            | R::Unknown(name) => name,
+        };
+        f.write_str(keyword)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum SymbolErrorKind {
+    /// No definition has the name
+    UnknownName,
+    /// The name is the one of a mixin or a namespace
+    NotAType,
+    /// The typedef refers to itself, directly or through other typedefs
+    RecursiveTypedef,
+}
+
+/// An error found while resolving the names of types
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolError<'a> {
+    pub kind: SymbolErrorKind,
+    /// The name the error is about
+    pub identifier: Identifier<'a>,
+}
+
+impl SymbolError<'_> {
+    pub fn message(&self) -> String {
+        let name = &self.identifier.0;
+        match self.kind {
+            SymbolErrorKind::UnknownName => format!("Unknown type `{name}`"),
+            SymbolErrorKind::NotAType => format!("`{name}` is not a type"),
+            SymbolErrorKind::RecursiveTypedef => format!("Typedef `{name}` refers to itself"),
+        }
+    }
+}
+
+impl fmt::Display for SymbolError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for SymbolError<'_> {}
+
+impl From<&SymbolError<'_>> for Diagnostic {
+    fn from(err: &SymbolError<'_>) -> Self {
+        let diagnostic = Diagnostic::error(err.message(), err.identifier.1);
+        match err.kind {
+            SymbolErrorKind::UnknownName => diagnostic.with_label("not defined"),
+            SymbolErrorKind::NotAType => diagnostic.with_label(
+                "only interfaces, dictionaries, enums, callbacks and typedefs are types",
+            ),
+            SymbolErrorKind::RecursiveTypedef => diagnostic.with_label("recursive typedef"),
+        }
+    }
+}
+
+/// The named definitions, indexed by name
+///
+/// Partial definitions and includes statements are left out. When several definitions have
+/// the same name, the first one is kept.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable<'d, 'a> {
+    symbols: BTreeMap<&'d str, Symbol<'d, 'a>>,
+}
+
+impl<'d, 'a> SymbolTable<'d, 'a> {
+    pub fn new(definitions: &'d [Definition<'a>]) -> Self {
+        let mut symbols = BTreeMap::new();
+        for definition in definitions {
+            let symbol = match definition {
+                Definition::Interface(def) => Symbol::Interface(def),
+                Definition::CallbackInterface(def) => Symbol::CallbackInterface(def),
+                Definition::Dictionary(def) => Symbol::Dictionary(def),
+                Definition::Enum(def) => Symbol::Enum(def),
+                Definition::Callback(def) => Symbol::Callback(def),
+                Definition::Typedef(def) => Symbol::Typedef(def),
+                Definition::InterfaceMixin(def) => Symbol::InterfaceMixin(def),
+                Definition::Namespace(def) => Symbol::Namespace(def),
+                _ => continue,
+            };
+            symbols
+                .entry(symbol.identifier().as_str())
+                .or_insert(symbol);
+        }
+        SymbolTable { symbols }
+    }
+
+    /// Returns the definition with the given name
+    pub fn get(&self, name: &str) -> Option<Symbol<'d, 'a>> {
+        self.symbols.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Symbol<'d, 'a>> + '_ {
+        self.symbols.values().copied()
+    }
+
+    /// Resolves the names of the type and expands its typedefs
+    ///
+    /// Fails on the first unknown name or recursive typedef.
+    pub fn expand(&self, type_: &Type<'a>) -> Result<ResolvedType, SymbolError<'a>> {
+        Expander {
+            symbols: self,
+            lenient: false,
+            typedefs: vec![],
+        }
+        .type_(type_)
+    }
+
+    /// Like [`expand`](Self::expand), but resolves unknown names to [`ResolvedType::Unknown`]
+    ///
+    /// This is useful when the definitions refer to other specifications.
+    pub fn expand_lenient(&self, type_: &Type<'a>) -> Result<ResolvedType, SymbolError<'a>> {
+        Expander {
+            symbols: self,
+            lenient: true,
+            typedefs: vec![],
+        }
+        .type_(type_)
+    }
+
+    /// Returns the unknown names used as types in the definitions, the names of mixins and
+    /// namespaces used as types, and the recursive typedefs, in the order of the definitions
+    pub fn check(&self, definitions: &[Definition<'a>]) -> Vec<SymbolError<'a>> {
+        let mut checker = Checker {
+            symbols: self,
+            errors: vec![],
+        };
+        for definition in definitions {
+            definition.accept(&mut checker);
+        }
+        checker.errors
+    }
+}
+
+struct Expander<'t, 'd, 'a> {
+    symbols: &'t SymbolTable<'d, 'a>,
+    lenient: bool,
+    /// The typedefs being expanded
+    typedefs: Vec<&'d str>,
+}
+
+impl<'d, 'a> Expander<'_, 'd, 'a> {
+    fn type_(&mut self, type_: &Type<'a>) -> Result<ResolvedType, SymbolError<'a>> {
+        match type_ {
+            Type::Single(SingleType::Any(_)) => Ok(ResolvedType::Any),
+            Type::Single(SingleType::Promise(promise)) => Ok(ResolvedType::Promise(Box::new(
+                self.type_(&promise.generics.body)?,
+            ))),
+            Type::Single(SingleType::Distinguishable(type_)) => self.distinguishable(type_),
+            Type::Union(union) => self.union(union),
+        }
+    }
+
+    fn union(&mut self, union: &MayBeNull<UnionType<'a>>) -> Result<ResolvedType, SymbolError<'a>> {
+        let members = union
+            .type_
+            .body
+            .list
+            .iter()
+            .map(|member| match member {
+                UnionMemberType::Single(member) => self.distinguishable(&member.type_),
+                UnionMemberType::Union(union) => self.union(union),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(nullable(ResolvedType::union(members), union.q_mark))
+    }
+
+    fn distinguishable(
+        &mut self,
+        type_: &DistinguishableType<'a>,
+    ) -> Result<ResolvedType, SymbolError<'a>> {
+        use DistinguishableType as D;
+        use ResolvedType as R;
+
+        let resolved = match type_ {
+            D::Integer(MayBeNull { type_, q_mark }) => nullable(integer(type_), *q_mark),
+            D::FloatingPoint(MayBeNull { type_, q_mark }) => {
+                nullable(floating_point(type_), *q_mark)
+            }
+            D::Boolean(MayBeNull { q_mark, .. }) => nullable(R::Boolean, *q_mark),
+            D::Byte(MayBeNull { q_mark, .. }) => nullable(R::Byte, *q_mark),
+            D::Octet(MayBeNull { q_mark, .. }) => nullable(R::Octet, *q_mark),
+            D::Bigint(MayBeNull { q_mark, .. }) => nullable(R::BigInt, *q_mark),
+            D::ByteString(MayBeNull { q_mark, .. }) => nullable(R::ByteString, *q_mark),
+            D::DOMString(MayBeNull { q_mark, .. }) => nullable(R::DOMString, *q_mark),
+            D::USVString(MayBeNull { q_mark, .. }) => nullable(R::USVString, *q_mark),
+            D::Sequence(MayBeNull { type_, q_mark }) => nullable(
+                R::Sequence(Box::new(self.type_(&type_.generics.body.type_)?)),
+                *q_mark,
+            ),
+            D::Object(MayBeNull { q_mark, .. }) => nullable(R::Object, *q_mark),
+            D::Symbol(MayBeNull { q_mark, .. }) => nullable(R::Symbol, *q_mark),
+            D::ArrayBuffer(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("ArrayBuffer"), *q_mark)
+            }
+            D::DataView(MayBeNull { q_mark, .. }) => nullable(R::BufferSource("DataView"), *q_mark),
+            D::Int8Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Int8Array"), *q_mark)
+            }
+            D::Int16Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Int16Array"), *q_mark)
+            }
+            D::Int32Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Int32Array"), *q_mark)
+            }
+            D::Uint8Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Uint8Array"), *q_mark)
+            }
+            D::Uint16Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Uint16Array"), *q_mark)
+            }
+            D::Uint32Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Uint32Array"), *q_mark)
+            }
+            D::Uint8ClampedArray(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Uint8ClampedArray"), *q_mark)
+            }
+            D::BigInt64Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("BigInt64Array"), *q_mark)
+            }
+            D::BigUint64Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("BigUint64Array"), *q_mark)
+            }
+            D::Float32Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Float32Array"), *q_mark)
+            }
+            D::Float64Array(MayBeNull { q_mark, .. }) => {
+                nullable(R::BufferSource("Float64Array"), *q_mark)
+            }
+            D::FrozenArrayType(MayBeNull { type_, q_mark }) => nullable(
+                R::FrozenArray(Box::new(self.type_(&type_.generics.body.type_)?)),
+                *q_mark,
+            ),
+            D::ObservableArrayType(MayBeNull { type_, q_mark }) => nullable(
+                R::ObservableArray(Box::new(self.type_(&type_.generics.body.type_)?)),
+                *q_mark,
+            ),
+            D::RecordType(MayBeNull { type_, q_mark }) => {
+                let (key, _, value) = &type_.generics.body;
+                let key = match key {
+                    RecordKeyType::Byte(_) => R::ByteString,
+                    RecordKeyType::DOM(_) => R::DOMString,
+                    RecordKeyType::USV(_) => R::USVString,
+                };
+                let value = self.type_(&value.type_)?;
+                nullable(R::Record(Box::new(key), Box::new(value)), *q_mark)
+            }
+            D::Undefined(MayBeNull { q_mark, .. }) => nullable(R::Undefined, *q_mark),
+            D::Identifier(MayBeNull { type_, q_mark }) => {
+                nullable(self.identifier(type_)?, *q_mark)
+            }
+        };
+        Ok(resolved)
+    }
+
+    fn identifier(&mut self, identifier: &Identifier<'a>) -> Result<ResolvedType, SymbolError<'a>> {
+        let error = |kind| SymbolError {
+            kind,
+            identifier: identifier.clone(),
+        };
+        let name = identifier.0.to_string();
+        match self.symbols.get(&name) {
+            Some(Symbol::Interface(_)) => Ok(ResolvedType::Interface(name)),
+            Some(Symbol::CallbackInterface(_)) => Ok(ResolvedType::CallbackInterface(name)),
+            Some(Symbol::Dictionary(_)) => Ok(ResolvedType::Dictionary(name)),
+            Some(Symbol::Enum(_)) => Ok(ResolvedType::Enum(name)),
+            Some(Symbol::Callback(_)) => Ok(ResolvedType::Callback(name)),
+            Some(Symbol::Typedef(typedef)) => {
+                let name = typedef.identifier.as_str();
+                if self.typedefs.contains(&name) {
+                    return Err(error(SymbolErrorKind::RecursiveTypedef));
+                }
+                self.typedefs.push(name);
+                let expanded = self.type_(&typedef.type_.type_);
+                self.typedefs.pop();
+                expanded
+            }
+            Some(Symbol::InterfaceMixin(_) | Symbol::Namespace(_)) => {
+                Err(error(SymbolErrorKind::NotAType))
+            }
+            None if self.lenient => Ok(ResolvedType::Unknown(name)),
+            None => Err(error(SymbolErrorKind::UnknownName)),
+        }
+    }
+}
+
+fn nullable(type_: ResolvedType, q_mark: Option<crate::term::QMark>) -> ResolvedType {
+    match q_mark {
+        Some(_) => type_.nullable(),
+        None => type_,
+    }
+}
+
+fn integer(type_: &IntegerType) -> ResolvedType {
+    match type_ {
+        IntegerType::LongLong(type_) if type_.unsigned.is_some() => ResolvedType::UnsignedLongLong,
+        IntegerType::LongLong(_) => ResolvedType::LongLong,
+        IntegerType::Long(type_) if type_.unsigned.is_some() => ResolvedType::UnsignedLong,
+        IntegerType::Long(_) => ResolvedType::Long,
+        IntegerType::Short(type_) if type_.unsigned.is_some() => ResolvedType::UnsignedShort,
+        IntegerType::Short(_) => ResolvedType::Short,
+    }
+}
+
+fn floating_point(type_: &FloatingPointType) -> ResolvedType {
+    match type_ {
+        FloatingPointType::Float(type_) if type_.unrestricted.is_some() => {
+            ResolvedType::UnrestrictedFloat
+        }
+        FloatingPointType::Float(_) => ResolvedType::Float,
+        FloatingPointType::Double(type_) if type_.unrestricted.is_some() => {
+            ResolvedType::UnrestrictedDouble
+        }
+        FloatingPointType::Double(_) => ResolvedType::Double,
+    }
+}
+
+struct Checker<'t, 'd, 'a> {
+    symbols: &'t SymbolTable<'d, 'a>,
+    errors: Vec<SymbolError<'a>>,
+}
+
+impl<'a> Checker<'_, '_, 'a> {
+    fn check_name(&mut self, identifier: &Identifier<'a>) {
+        let kind = match self.symbols.get(identifier.as_str()) {
+            None => SymbolErrorKind::UnknownName,
+            Some(Symbol::InterfaceMixin(_) | Symbol::Namespace(_)) => SymbolErrorKind::NotAType,
+            Some(_) => return,
+        };
+        self.errors.push(SymbolError {
+            kind,
+            identifier: identifier.clone(),
+        });
+    }
+}
+
+impl<'a> Visit<'a> for Checker<'_, '_, 'a> {
+    fn visit_typedef_definition(&mut self, node: &TypedefDefinition<'a>) {
+        // Unknown names are reported where they are used, by the other methods
+        let err = Expander {
+            symbols: self.symbols,
+            lenient: true,
+            typedefs: vec![],
+        }
+        .identifier(&node.identifier)
+        .err();
+        if let Some(err) = err {
+            let is_cycle = err.kind == SymbolErrorKind::RecursiveTypedef
+                && err.identifier.0 == node.identifier.0;
+            if is_cycle {
+                self.errors.push(SymbolError {
+                    kind: SymbolErrorKind::RecursiveTypedef,
+                    identifier: node.identifier.clone(),
+                });
+            }
+        }
+        node.walk(self);
+    }
+
+    fn visit_distinguishable_type(&mut self, node: &DistinguishableType<'a>) {
+        if let DistinguishableType::Identifier(type_) = node {
+            self.check_name(&type_.type_);
+        }
+        node.walk(self);
+    }
+
+    fn visit_const_type(&mut self, node: &ConstType<'a>) {
+        if let ConstType::Identifier(identifier) = node {
+            self.check_name(identifier);
+        }
+        node.walk(self);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ResolvedType as R;
+
+    fn typedef<'d, 'a>(symbols: &SymbolTable<'d, 'a>, name: &str) -> &'d Type<'a> {
+        match symbols.get(name) {
+            Some(Symbol::Typedef(typedef)) => &typedef.type_.type_,
+            symbol => panic!("Expected a typedef, got {symbol:?}"),
+        }
+    }
+
+    #[test]
+    fn should_resolve_names() {
+        let definitions = crate::parse(
+            r#"
+            interface Node {};
+            callback interface Listener { undefined handle(); };
+            dictionary Options {};
+            enum Mode { "open" };
+            callback Handler = undefined ();
+            typedef (Node or Listener or Options or Mode or Handler) All;
+            "#,
+        )
+        .unwrap();
+        let symbols = SymbolTable::new(&definitions);
+
+        assert_eq!(symbols.iter().count(), 6);
+        assert_eq!(
+            symbols.expand(typedef(&symbols, "All")),
+            Ok(R::Union(vec![
+                R::Interface("Node".into()),
+                R::CallbackInterface("Listener".into()),
+                R::Dictionary("Options".into()),
+                R::Enum("Mode".into()),
+                R::Callback("Handler".into()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn should_expand_typedefs() {
+        let definitions = crate::parse(
+            "
+            typedef unsigned long long Size;
+            typedef Size? MaybeSize;
+            typedef (DOMString or (Size or boolean)?) A;
+            typedef (MaybeSize or A)? B;
+            typedef record<USVString, sequence<MaybeSize?>> C;
+            typedef Promise<FrozenArray<[Clamp] octet>> D;
+            ",
+        )
+        .unwrap();
+        let symbols = SymbolTable::new(&definitions);
+
+        let nullable = |type_| R::Nullable(Box::new(type_));
+        let union = || R::Union(vec![R::DOMString, R::UnsignedLongLong, R::Boolean]);
+        assert_eq!(
+            symbols.expand(typedef(&symbols, "A")),
+            Ok(nullable(union()))
+        );
+        assert_eq!(
+            symbols.expand(typedef(&symbols, "B")),
+            Ok(nullable(R::Union(vec![
+                R::UnsignedLongLong,
+                R::DOMString,
+                R::UnsignedLongLong,
+                R::Boolean
+            ])))
+        );
+        assert_eq!(
+            symbols.expand(typedef(&symbols, "C")),
+            Ok(R::Record(
+                Box::new(R::USVString),
+                Box::new(R::Sequence(Box::new(nullable(R::UnsignedLongLong))))
+            ))
+        );
+        assert_eq!(
+            symbols.expand(typedef(&symbols, "D")),
+            Ok(R::Promise(Box::new(R::FrozenArray(Box::new(R::Octet)))))
+        );
+    }
+
+    #[test]
+    fn should_display_resolved_types() {
+        let definitions = crate::parse(
+            "
+            interface Node {};
+            typedef (Node or unsigned long long)? A;
+            typedef record<DOMString, sequence<A>> B;
+            typedef Promise<Uint8Array?> C;
+            ",
+        )
+        .unwrap();
+        let symbols = SymbolTable::new(&definitions);
+        let display = |name| symbols.expand(typedef(&symbols, name)).unwrap().to_string();
+
+        assert_eq!(display("A"), "(Node or unsigned long long)?");
+        assert_eq!(
+            display("B"),
+            "record<DOMString, sequence<(Node or unsigned long long)?>>"
+        );
+        assert_eq!(display("C"), "Promise<Uint8Array?>");
+    }
+
+    #[test]
+    fn should_fail_to_expand_unknown_names() {
+        let definitions = crate::parse("typedef sequence<Node> Nodes;").unwrap();
+        let symbols = SymbolTable::new(&definitions);
+        let nodes = typedef(&symbols, "Nodes");
+
+        let err = symbols.expand(nodes).unwrap_err();
+        assert_eq!(err.kind, SymbolErrorKind::UnknownName);
+        assert_eq!(err.to_string(), "Unknown type `Node`");
+        assert_eq!(
+            symbols.expand_lenient(nodes),
+            Ok(R::Sequence(Box::new(R::Unknown("Node".into()))))
+        );
+    }
+
+    #[test]
+    fn should_check_names() {
+        let definitions = crate::parse(
+            "
+            interface mixin M {};
+            typedef sequence<A> A;
+            typedef (C or long) B;
+            typedef B C;
+            typedef C D;
+            dictionary Options {
+                M m;
+                Unknown unknown;
+            };
+            interface I { const Unknown value = 1; };
+            ",
+        )
+        .unwrap();
+        let symbols = SymbolTable::new(&definitions);
+
+        let messages: Vec<_> = symbols
+            .check(&definitions)
+            .iter()
+            .map(|err| err.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Typedef `A` refers to itself",
+                "Typedef `B` refers to itself",
+                "Typedef `C` refers to itself",
+                "`M` is not a type",
+                "Unknown type `Unknown`",
+                "Unknown type `Unknown`",
+            ]
+        );
+    }
+}
@@ -2,8 +2,12 @@
  * The following will ultimate generate:
  *
  * ```rust
- * #[derive(Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
- * pub struct OpenParen;
+ * #[derive(Copy, Default, Clone)]
+ * pub struct OpenParen {
+ *     pub span: Span,
+ * }
+ *
+ * pub const OpenParen: OpenParen = OpenParen { span: Span { start: 0, end: 0 } };
  *
  * impl OpenParen {
  *     pub fn value(&self) -> &'static str {
@@ -11,6 +15,8 @@
  *     }
  * }
  *
+ * /* Debug, Eq, PartialEq, Ord, PartialOrd, Hash ignoring the span, Spanned and Terminals */
+ *
  * impl<'a> $crate::Parse<'a> for OpenParen {
  *     parser!(eat_key!(OpenParen));
  * }
@@ -47,10 +53,18 @@
  *             _ => None
  *         }
  *     }
+ *     pub fn with_span(self, span: Span) -> Keyword {
+ *         match self {
+ *             Keyword::OpenParen(_) => Keyword::OpenParen(OpenParen { span }),
+ *             /* ... */
+ *         }
+ *     }
  * }
  * ```
  *
  * Use `cargo-expand` to see the full macro expansion.
+ *
+ * The const with the same name as the struct keeps `term!(;)` usable as a value.
  */
 
 #[cfg(test)]
@@ -103,8 +117,16 @@ macro_rules! generate_tests {
 macro_rules! generate_keyword_struct {
     ($(#[$attr:meta])* $typ:ident => $tok:expr) => {
         $(#[$attr])*
-        #[derive(Copy, Default, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-        pub struct $typ;
+        #[derive(Copy, Default, Clone)]
+        pub struct $typ {
+            pub span: $crate::span::Span,
+        }
+
+        $(#[$attr])*
+        #[allow(non_upper_case_globals)]
+        pub const $typ: $typ = $typ {
+            span: $crate::span::Span { start: 0, end: 0 },
+        };
 
         impl $typ {
             pub fn value(&self) -> &'static str {
@@ -112,9 +134,97 @@ macro_rules! generate_keyword_struct {
             }
         }
 
+        impl ::std::fmt::Debug for $typ {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(stringify!($typ))
+            }
+        }
+
+        impl PartialEq for $typ {
+            fn eq(&self, _other: &Self) -> bool {
+                true
+            }
+        }
+
+        impl Eq for $typ {}
+
+        impl PartialOrd for $typ {
+            fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $typ {
+            fn cmp(&self, _other: &Self) -> ::std::cmp::Ordering {
+                ::std::cmp::Ordering::Equal
+            }
+        }
+
+        impl ::std::hash::Hash for $typ {
+            fn hash<H: ::std::hash::Hasher>(&self, _state: &mut H) {}
+        }
+
+        impl $crate::span::Spanned for $typ {
+            fn span(&self) -> $crate::span::Span {
+                self.span
+            }
+        }
+
+        impl $crate::span::Shift for $typ {
+            fn shift(&mut self, delta: isize) {
+                $crate::span::Shift::shift(&mut self.span, delta)
+            }
+        }
+
+        impl $crate::lossless::Terminals for $typ {
+            fn for_each_terminal(&self, f: &mut dyn FnMut(&str, $crate::span::Span)) {
+                f($tok, self.span)
+            }
+        }
+
         impl<'a> $crate::Parse<'a> for $typ {
             parser!(eat_key!($typ));
         }
+
+        impl<'a> $crate::visit::Walk<'a> for $typ {
+            fn accept<V: $crate::visit::Visit<'a> + ?Sized>(&self, _visitor: &mut V) {}
+
+            fn accept_mut<V: $crate::visit::VisitMut<'a> + ?Sized>(&mut self, _visitor: &mut V) {}
+
+            fn walk<V: $crate::visit::Visit<'a> + ?Sized>(&self, _visitor: &mut V) {}
+
+            fn walk_mut<V: $crate::visit::VisitMut<'a> + ?Sized>(&mut self, _visitor: &mut V) {}
+        }
+
+        impl $crate::owned::IntoOwned for $typ {
+            type Owned = $typ;
+
+            fn into_owned(self) -> Self::Owned {
+                self
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $typ {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str($tok)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $typ {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <String as ::serde::Deserialize>::deserialize(deserializer)?;
+                if value == $tok {
+                    Ok($typ)
+                } else {
+                    Err(<D::Error as ::serde::de::Error>::invalid_value(
+                        ::serde::de::Unexpected::Str(&value),
+                        &$tok,
+                    ))
+                }
+            }
+        }
     };
 }
 
@@ -134,7 +244,11 @@ macro_rules! generate_keywords_enum {
             $typ_word => $tok_word
         );)*
 
+        /// A keyword or a punctuation mark, as lexed in [`Terminal::Keyword`]
+        ///
+        /// [`Terminal::Keyword`]: crate::lexer::Terminal::Keyword
         #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        #[non_exhaustive]
         pub enum Keyword {
             $(
                 #[$attr]
@@ -165,6 +279,34 @@ macro_rules! generate_keywords_enum {
                     _ => None,
                 }
             }
+
+            pub fn with_span(self, span: $crate::span::Span) -> Keyword {
+                match self {
+                    $(Keyword::$typ_punc(_) => Keyword::$typ_punc($typ_punc { span }),)*
+                    $(Keyword::$typ_word(_) => Keyword::$typ_word($typ_word { span }),)*
+                }
+            }
+
+            pub fn value(&self) -> &'static str {
+                match self {
+                    $(Keyword::$typ_punc(_) => $tok_punc,)*
+                    $(Keyword::$typ_word(_) => $tok_word,)*
+                }
+            }
+
+            /// Returns whether the keyword is a punctuation mark rather than a word
+            pub fn is_punctuation(&self) -> bool {
+                matches!(self, $(Keyword::$typ_punc(_))|*)
+            }
+        }
+
+        impl $crate::span::Spanned for Keyword {
+            fn span(&self) -> $crate::span::Span {
+                match self {
+                    $(Keyword::$typ_punc(keyword) => keyword.span,)*
+                    $(Keyword::$typ_word(keyword) => keyword.span,)*
+                }
+            }
         }
 
         #[cfg(test)]
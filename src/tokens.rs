@@ -1,17 +1,54 @@
 use std::{
-    iter::{Copied, Enumerate},
+    iter::Enumerate,
     ops::{Range, RangeFrom, RangeFull, RangeTo},
 };
 
 use nom::{InputIter, InputLength, InputTake, Needed, Slice};
 
+use crate::dialect::ParseOptions;
+use crate::error::ParseError;
 use crate::lexer::Token;
+use crate::recovery::Recovery;
 
 // Using custom struct as an input format requires implementations for the following traits
 // https://github.com/Geal/nom/blob/main/doc/custom_input_types.md
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Tokens<'slice, 'token>(pub &'slice [Token<'token>]);
+/// The tokens to parse, with the place to collect the errors when parsing with recovery and
+/// the grammar extensions to accept
+#[derive(Copy, Clone, Debug)]
+pub struct Tokens<'slice, 'token>(
+    pub &'slice [Token<'token>],
+    pub Option<&'slice Recovery>,
+    pub &'slice ParseOptions,
+);
+
+impl Tokens<'_, '_> {
+    /// Notes that the parser read the first `count` tokens, when parsing with recovery
+    pub(crate) fn read(&self, count: usize) {
+        if let Some(recovery) = self.1 {
+            recovery.read(self.0.len().saturating_sub(count));
+        }
+    }
+}
+
+/// Iterates over the tokens, noting the ones read like [`Tokens::read`]
+pub struct TokenIter<'slice, 'token> {
+    tokens: std::slice::Iter<'slice, Token<'token>>,
+    recovery: Option<&'slice Recovery>,
+}
+
+impl<'token> Iterator for TokenIter<'_, 'token> {
+    type Item = Token<'token>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.tokens.next()?;
+        if let Some(recovery) = self.recovery {
+            recovery.read(self.tokens.len());
+        }
+        Some(token.clone())
+    }
+}
 
 impl<'slice, 'token> From<Tokens<'slice, 'token>> for &'token str {
     fn from(value: Tokens<'slice, 'token>) -> Self {
@@ -34,6 +71,26 @@ impl<'slice, 'token> From<Tokens<'slice, 'token>> for &'token str {
     }
 }
 
+/// Returns the source text of `skipped`, without the leading trivia, given the tokens
+/// following it
+pub fn source_text<'token>(skipped: Tokens<'_, 'token>, rest: Tokens<'_, 'token>) -> &'token str {
+    let (first, next) = match (skipped.0.first(), rest.0.first()) {
+        (Some(first), Some(next)) => (first, next),
+        _ => return "",
+    };
+
+    // Same assumption as above, the tokens are serially ordered slices of a single string.
+    unsafe {
+        let start_ptr = first.trivia.as_ptr().add(first.trivia.len());
+        let end_ptr = next.trivia.as_ptr();
+
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(
+            start_ptr,
+            end_ptr.offset_from(start_ptr) as usize,
+        ))
+    }
+}
+
 impl<'slice, 'token> InputLength for Tokens<'slice, 'token> {
     #[inline]
     fn input_len(&self) -> usize {
@@ -44,13 +101,13 @@ impl<'slice, 'token> InputLength for Tokens<'slice, 'token> {
 impl<'slice, 'token> InputTake for Tokens<'slice, 'token> {
     #[inline]
     fn take(&self, count: usize) -> Self {
-        Self(&self.0[..count])
+        Self(&self.0[..count], self.1, self.2)
     }
 
     #[inline]
     fn take_split(&self, count: usize) -> (Self, Self) {
         let (prefix, suffix) = self.0.split_at(count);
-        (Self(suffix), Self(prefix))
+        (Self(suffix, self.1, self.2), Self(prefix, self.1, self.2))
     }
 }
 
@@ -64,35 +121,35 @@ impl<'a> InputLength for Token<'a> {
 impl<'slice, 'token> Slice<Range<usize>> for Tokens<'slice, 'token> {
     #[inline]
     fn slice(&self, range: Range<usize>) -> Self {
-        Self(self.0.slice(range))
+        Self(self.0.slice(range), self.1, self.2)
     }
 }
 
 impl<'slice, 'token> Slice<RangeTo<usize>> for Tokens<'slice, 'token> {
     #[inline]
     fn slice(&self, range: RangeTo<usize>) -> Self {
-        Self(self.0.slice(range))
+        Self(self.0.slice(range), self.1, self.2)
     }
 }
 
 impl<'slice, 'token> Slice<RangeFrom<usize>> for Tokens<'slice, 'token> {
     #[inline]
     fn slice(&self, range: RangeFrom<usize>) -> Self {
-        Self(self.0.slice(range))
+        Self(self.0.slice(range), self.1, self.2)
     }
 }
 
 impl<'slice, 'token> Slice<RangeFull> for Tokens<'slice, 'token> {
     #[inline]
     fn slice(&self, range: RangeFull) -> Self {
-        Self(self.0.slice(range))
+        Self(self.0.slice(range), self.1, self.2)
     }
 }
 
 impl<'slice, 'token> InputIter for Tokens<'slice, 'token> {
     type Item = Token<'token>;
     type Iter = Enumerate<Self::IterElem>;
-    type IterElem = Copied<::std::slice::Iter<'slice, Token<'token>>>;
+    type IterElem = TokenIter<'slice, 'token>;
 
     #[inline]
     fn iter_indices(&self) -> Self::Iter {
@@ -100,17 +157,21 @@ impl<'slice, 'token> InputIter for Tokens<'slice, 'token> {
     }
     #[inline]
     fn iter_elements(&self) -> Self::IterElem {
-        self.0.iter().copied()
+        TokenIter {
+            tokens: self.0.iter(),
+            recovery: self.1,
+        }
     }
     #[inline]
     fn position<P>(&self, predicate: P) -> Option<usize>
     where
         P: Fn(Self::Item) -> bool,
     {
-        self.0.iter().position(|b| predicate(*b))
+        self.iter_elements().position(predicate)
     }
     #[inline]
     fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+        self.read(count);
         if self.0.len() >= count {
             Ok(count)
         } else {
@@ -119,36 +180,16 @@ impl<'slice, 'token> InputIter for Tokens<'slice, 'token> {
     }
 }
 
-// This exists because nom::error::Error doesn't have a From/Into implementation and nom::combinator::into requires it.
-pub fn nom_error_into<T, U: From<T>>(
-    err: nom::Err<nom::error::VerboseError<T>>,
-) -> nom::Err<nom::error::VerboseError<U>> {
-    match err {
-        nom::Err::Incomplete(need) => nom::Err::Incomplete(need),
-        nom::Err::Error(err) | nom::Err::Failure(err) => {
-            nom::Err::Error(nom::error::VerboseError {
-                errors: err.errors.into_iter().map(|(i, e)| (i.into(), e)).collect(),
-            })
-        }
-    }
-}
-
 pub fn contextful_cut<I, O, F>(
     ctx: &'static str,
     mut parser: F,
-) -> impl FnMut(I) -> nom::IResult<I, O, nom::error::VerboseError<I>>
+) -> impl FnMut(I) -> nom::IResult<I, O, ParseError<I>>
 where
-    F: nom::Parser<I, O, nom::error::VerboseError<I>>,
-    I: Copy,
+    F: nom::Parser<I, O, ParseError<I>>,
+    I: Copy + InputLength,
 {
-    use nom::error::ContextError;
     move |input: I| match parser.parse(input) {
-        Err(nom::Err::Error(mut e)) => {
-            e.errors.clear();
-            Err(nom::Err::Failure(nom::error::VerboseError::add_context(
-                input, ctx, e,
-            )))
-        }
+        Err(nom::Err::Error(e)) => Err(nom::Err::Failure(ParseError::cut(input, ctx, e))),
         rest => rest,
     }
 }
@@ -10,6 +10,7 @@ use crate::{term, VerboseResult};
 pub type UnionType<'a> = Parenthesized<Punctuated<UnionMemberType<'a>, term!(or)>>;
 
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct UnionTypeMultiple<'a> {
     open_paren: term!(OpenParen),
     first: UnionMemberType<'a>,
@@ -25,18 +26,18 @@ impl<'a> From<UnionTypeMultiple<'a>> for UnionType<'a> {
         // XXX: request nom::multi::separated_list_m_n?
         let mut list = vec![value.first];
         list.extend(value.more.list);
+        let mut separators = vec![value.or];
+        separators.extend(value.more.separators);
         Self {
             open_paren: value.open_paren,
-            body: Punctuated {
-                list,
-                separator: term!(or),
-            },
+            body: Punctuated { list, separators },
             close_paren: value.close_paren,
         }
     }
 }
 
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SingleType<'a> {
     #[weedle(post_check = "prevent_unexpected_nullable")]
     Any(term!(any)),
@@ -56,6 +57,7 @@ fn prevent_unexpected_nullable<'slice, 'a>(
 
 /// Parses either single type or a union type
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Type<'a> {
     Single(SingleType<'a>),
     #[weedle(from = "MayBeNull<UnionTypeMultiple<'a>>", generic_into)]
@@ -64,6 +66,7 @@ pub enum Type<'a> {
 
 // Parses any single non-any type
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DistinguishableType<'a> {
     Integer(MayBeNull<IntegerType>),
     FloatingPoint(MayBeNull<FloatingPointType>),
@@ -99,6 +102,7 @@ pub enum DistinguishableType<'a> {
 
 /// Parses `sequence<Type>`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SequenceType<'a> {
     pub sequence: term!(sequence),
     pub generics: Generics<Box<AttributedType<'a>>>,
@@ -106,6 +110,7 @@ pub struct SequenceType<'a> {
 
 /// Parses `FrozenArray<Type>`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrozenArrayType<'a> {
     pub frozen_array: term!(FrozenArray),
     pub generics: Generics<Box<AttributedType<'a>>>,
@@ -113,6 +118,7 @@ pub struct FrozenArrayType<'a> {
 
 /// Parses `ObservableArray<Type>`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ObservableArrayType<'a> {
     pub observable_array: term!(ObservableArray),
     pub generics: Generics<Box<AttributedType<'a>>>,
@@ -122,6 +128,7 @@ pub struct ObservableArrayType<'a> {
 ///
 /// `??` means an actual ? not an optional requirement
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(impl_bound = "where T: Parse<'a>")]
 pub struct MayBeNull<T> {
     pub type_: T,
@@ -140,6 +147,7 @@ impl<T> MayBeNull<T> {
 
 /// Parses a `Promise<Type|undefined>` type
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PromiseType<'a> {
     pub promise: term!(Promise),
     pub generics: Generics<Box<Type<'a>>>,
@@ -147,6 +155,7 @@ pub struct PromiseType<'a> {
 
 /// Parses `unsigned? long long`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LongLongType {
     pub unsigned: Option<term!(unsigned)>,
     pub long_long: (term!(long), term!(long)),
@@ -154,6 +163,7 @@ pub struct LongLongType {
 
 /// Parses `unsigned? long`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LongType {
     pub unsigned: Option<term!(unsigned)>,
     pub long: term!(long),
@@ -161,6 +171,7 @@ pub struct LongType {
 
 /// Parses `unsigned? short`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShortType {
     pub unsigned: Option<term!(unsigned)>,
     pub short: term!(short),
@@ -168,6 +179,7 @@ pub struct ShortType {
 
 /// Parses `unsigned? short|long|(long long)`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntegerType {
     LongLong(LongLongType),
     Long(LongType),
@@ -176,6 +188,7 @@ pub enum IntegerType {
 
 /// Parses `unrestricted? float`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FloatType {
     pub unrestricted: Option<term!(unrestricted)>,
     pub float: term!(float),
@@ -183,6 +196,7 @@ pub struct FloatType {
 
 /// Parses `unrestricted? double`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DoubleType {
     pub unrestricted: Option<term!(unrestricted)>,
     pub double: term!(double),
@@ -190,6 +204,7 @@ pub struct DoubleType {
 
 /// Parses `unrestricted? float|double`
 #[derive(Weedle, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FloatingPointType {
     Float(FloatType),
     Double(DoubleType),
@@ -197,6 +212,7 @@ pub enum FloatingPointType {
 
 /// Parses `record<StringType, Type>`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RecordType<'a> {
     pub record: term!(record),
     pub generics: Generics<(RecordKeyType, term!(,), Box<AttributedType<'a>>)>,
@@ -204,6 +220,7 @@ pub struct RecordType<'a> {
 
 /// Parses one of the string types `ByteString|DOMString|USVString` or any other type.
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(
     cut = "Record key must be one of: ByteString, DOMString, USVString with no extended attributes"
 )]
@@ -215,6 +232,7 @@ pub enum RecordKeyType {
 
 /// Parses one of the member of a union type
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[weedle(cut = "Expected a union member type that is not an `any` or `Promise`")]
 pub enum UnionMemberType<'a> {
     Single(AttributedNonAnyType<'a>),
@@ -223,6 +241,7 @@ pub enum UnionMemberType<'a> {
 
 /// Parses a const type
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConstType<'a> {
     Integer(IntegerType),
     FloatingPoint(FloatingPointType),
@@ -235,6 +254,7 @@ pub enum ConstType<'a> {
 
 /// Parses `[attributes]? type`
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributedType<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub type_: Type<'a>,
@@ -242,6 +262,7 @@ pub struct AttributedType<'a> {
 
 /// Parses `[attributes]? type` where the type is a single non-any type
 #[derive(Weedle, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributedNonAnyType<'a> {
     pub attributes: Option<ExtendedAttributeList<'a>>,
     pub type_: DistinguishableType<'a>,
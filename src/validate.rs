@@ -0,0 +1,1404 @@
+//! Checks the static rules of WebIDL that the grammar does not enforce.
+//!
+//! [`validate`] reports, as [`Diagnostic`]s pointing at the offending nodes:
+//!
+//! - definitions, members, arguments and enum values declared more than once
+//! - interfaces and dictionaries inheriting from themselves
+//! - `required` dictionary members with a default value
+//! - required arguments following optional ones, and variadic arguments that are not last
+//! - nullable member types in a nullable union, more than one nullable member type in a
+//!   union, and dictionaries in a nullable union
+//! - member types of a union that are not distinguishable
+//! - overloaded operations and constructors that are not distinguishable
+//! - constant and default values that are not valid for their type: numbers out of the range
+//!   of the type, strings that are not values of the enumeration, `null` for types that are not
+//!   nullable, and `[]` and `{}` for types that are not sequences or dictionaries
+//!
+//! Names referring to definitions outside of the validated ones are assumed to be valid:
+//! a type of unknown name is distinguishable from any other type.
+//!
+//! ### Example
+//!
+//! ```
+//! let source = "
+//!     interface Storage {
+//!         undefined setItem(DOMString key, (DOMString or USVString) value);
+//!     };
+//! ";
+//! let definitions = weedle::parse(source).unwrap();
+//!
+//! let diagnostics = weedle::validate::validate(&definitions);
+//! assert_eq!(diagnostics.len(), 1);
+//! assert_eq!(
+//!     diagnostics[0].message,
+//!     "`DOMString` and `USVString` are not distinguishable in a union type"
+//! );
+//! ```
+
+use std::collections::BTreeSet;
+
+use crate::argument::{Argument, ArgumentList, SingleArgument};
+use crate::attribute::{ExtendedAttributeArgList, ExtendedAttributeNamedArgList};
+use crate::common::Identifier;
+use crate::diagnostic::Diagnostic;
+use crate::dictionary::FieldDictionaryMember;
+use crate::fmt::{format, FormatOptions};
+use crate::interface::{CallbackInterfaceMember, ConstructorInterfaceMember, InterfaceMember};
+use crate::literal::{ConstValue, DefaultValue, FloatLit, IdlValue};
+use crate::lossless::Terminals;
+use crate::members::{ConstMember, Modifier, OperationInterfaceMember, RegularOperationMember};
+use crate::mixin::MixinMember;
+use crate::namespace::NamespaceMember;
+use crate::resolve::{resolve, Model, ResolveErrorKind};
+use crate::span::{Span, Spanned};
+use crate::types::{
+    ConstType, DistinguishableType, FloatingPointType, IntegerType, MayBeNull, SingleType, Type,
+    UnionMemberType, UnionType,
+};
+use crate::visit::{Visit, Walk};
+use crate::{CallbackDefinition, Definition, EnumDefinition};
+
+/// Checks the definitions, returning the diagnostics sorted by position
+pub fn validate(definitions: &[Definition<'_>]) -> Vec<Diagnostic> {
+    let (model, errors) = resolve(&[definitions]);
+    let mut validator = Validator {
+        model: &model,
+        diagnostics: errors
+            .iter()
+            .filter(|err| {
+                matches!(
+                    err.kind,
+                    ResolveErrorKind::DuplicateDefinition | ResolveErrorKind::DuplicateMember
+                )
+            })
+            .map(Diagnostic::from)
+            .collect(),
+    };
+
+    for definition in definitions {
+        definition.accept(&mut validator);
+    }
+    validator.check_inheritance();
+    validator.check_overloads();
+
+    let mut diagnostics = validator.diagnostics;
+    // Members of a mixin are checked again in every interface including it
+    diagnostics.sort_by(|a, b| (a.span.start, &a.message).cmp(&(b.span.start, &b.message)));
+    diagnostics.dedup();
+    diagnostics
+}
+
+/// The rows of the distinguishability table of the specification
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Category<'m> {
+    /// `any` and promise types, which are not distinguishable from any type
+    Any,
+    Undefined,
+    Boolean,
+    Numeric,
+    BigInt,
+    /// String types and enumerations
+    String,
+    Object,
+    Symbol,
+    /// Interfaces and buffer source types, by name
+    Interface(&'m str),
+    CallbackFunction,
+    Dictionary,
+    /// Records and callback interfaces
+    DictionaryLike,
+    /// Sequences, frozen arrays and observable arrays
+    SequenceLike,
+    /// Names that are not defined by the validated definitions
+    Unknown,
+}
+
+impl Category<'_> {
+    fn is_object(&self) -> bool {
+        matches!(
+            self,
+            Category::Object
+                | Category::Interface(_)
+                | Category::CallbackFunction
+                | Category::Dictionary
+                | Category::DictionaryLike
+                | Category::SequenceLike
+        )
+    }
+
+    fn is_dictionary_like(&self) -> bool {
+        matches!(self, Category::Dictionary | Category::DictionaryLike)
+    }
+}
+
+/// A flattened member type of a union, or a single type
+#[derive(Clone, Debug)]
+struct MemberType<'m> {
+    category: Category<'m>,
+    text: String,
+    span: Span,
+}
+
+/// A type with its unions flattened and its typedefs expanded
+#[derive(Clone, Debug, Default)]
+struct Flattened<'m> {
+    members: Vec<MemberType<'m>>,
+    /// The nullable types met on the way, including nullable unions
+    nullables: Vec<(String, Span)>,
+}
+
+impl Flattened<'_> {
+    fn has_dictionary(&self) -> bool {
+        self.members
+            .iter()
+            .any(|member| member.category == Category::Dictionary)
+    }
+}
+
+/// Why a value is not valid for a type, from the least to the most specific reason. Integers
+/// that do not fit in an `i128` and decimals too large for a `double` are out of range.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum Mismatch {
+    Invalid,
+    NotEnumValue,
+    OutOfRange,
+}
+
+/// The numeric types, by the values they accept
+#[derive(Copy, Clone, Debug)]
+enum Numeric {
+    Integer { min: i128, max: i128 },
+    Float { single: bool, unrestricted: bool },
+    BigInt,
+}
+
+impl Numeric {
+    fn of_integer(type_: &IntegerType) -> Numeric {
+        let (min, max) = match type_ {
+            IntegerType::Short(t) if t.unsigned.is_some() => (0, u16::MAX.into()),
+            IntegerType::Short(_) => (i16::MIN.into(), i16::MAX.into()),
+            IntegerType::Long(t) if t.unsigned.is_some() => (0, u32::MAX.into()),
+            IntegerType::Long(_) => (i32::MIN.into(), i32::MAX.into()),
+            IntegerType::LongLong(t) if t.unsigned.is_some() => (0, u64::MAX.into()),
+            IntegerType::LongLong(_) => (i64::MIN.into(), i64::MAX.into()),
+        };
+        Numeric::Integer { min, max }
+    }
+
+    fn of_float(type_: &FloatingPointType) -> Numeric {
+        match type_ {
+            FloatingPointType::Float(t) => Numeric::Float {
+                single: true,
+                unrestricted: t.unrestricted.is_some(),
+            },
+            FloatingPointType::Double(t) => Numeric::Float {
+                single: false,
+                unrestricted: t.unrestricted.is_some(),
+            },
+        }
+    }
+
+    fn accepts(self, value: IdlValue<'_>) -> Result<(), Mismatch> {
+        let in_range = |value: f64, single: bool| match single {
+            true => (value as f32).is_finite(),
+            false => value.is_finite(),
+        };
+        let valid = match (self, value) {
+            (Numeric::Integer { min, max }, IdlValue::Integer(value)) => {
+                (min..=max).contains(&value)
+            }
+            (Numeric::BigInt, IdlValue::Integer(_)) => true,
+            (Numeric::Float { single, .. }, IdlValue::Integer(value)) => {
+                in_range(value as f64, single)
+            }
+            // `Infinity`, `-Infinity` and `NaN`
+            (Numeric::Float { unrestricted, .. }, IdlValue::Float(value)) if !value.is_finite() => {
+                return accepts(unrestricted)
+            }
+            (Numeric::Float { single, .. }, IdlValue::Float(value)) => in_range(value, single),
+            _ => return Err(Mismatch::Invalid),
+        };
+        valid.then_some(()).ok_or(Mismatch::OutOfRange)
+    }
+}
+
+fn text<T: Terminals + ?Sized>(node: &T) -> String {
+    format(node, &FormatOptions::default())
+}
+
+/// The value of a default value, see [`float_value`]
+fn default_value<'v>(value: &'v DefaultValue<'_>) -> Result<IdlValue<'v>, Mismatch> {
+    match value {
+        DefaultValue::Float(lit) => float_value(lit),
+        value => value.value().map_err(|_| Mismatch::OutOfRange),
+    }
+}
+
+/// The value of a constant, see [`float_value`]
+fn const_value<'v>(value: &'v ConstValue<'_>) -> Result<IdlValue<'v>, Mismatch> {
+    match value {
+        ConstValue::Float(lit) => float_value(lit),
+        value => value.value().map_err(|_| Mismatch::OutOfRange),
+    }
+}
+
+/// The value of a float literal, where a decimal rounding to an infinity is out of range rather
+/// than `Infinity`
+fn float_value<'v>(lit: &FloatLit<'_>) -> Result<IdlValue<'v>, Mismatch> {
+    match lit {
+        FloatLit::Value(lit) if !lit.value().is_finite() => Err(Mismatch::OutOfRange),
+        lit => Ok(IdlValue::Float(lit.value())),
+    }
+}
+
+fn accepts(valid: bool) -> Result<(), Mismatch> {
+    valid.then_some(()).ok_or(Mismatch::Invalid)
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+struct Validator<'m, 'a> {
+    model: &'m Model<'a>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'m, 'a> Validator<'m, 'a> {
+    fn error(&mut self, message: String, span: Span, label: &str) {
+        self.diagnostics
+            .push(Diagnostic::error(message, span).with_label(label));
+    }
+
+    fn flatten_type(
+        &self,
+        type_: &Type<'a>,
+        expanding: &mut Vec<&'m str>,
+        out: &mut Flattened<'m>,
+    ) {
+        match type_ {
+            Type::Single(SingleType::Distinguishable(type_)) => {
+                self.flatten_distinguishable(type_, expanding, out)
+            }
+            Type::Single(single) => out.members.push(MemberType {
+                category: Category::Any,
+                text: text(single),
+                span: single.span(),
+            }),
+            Type::Union(union) => self.flatten_union(union, expanding, out),
+        }
+    }
+
+    fn flatten_union(
+        &self,
+        union: &MayBeNull<UnionType<'a>>,
+        expanding: &mut Vec<&'m str>,
+        out: &mut Flattened<'m>,
+    ) {
+        if union.q_mark.is_some() {
+            out.nullables.push((text(union), union.span()));
+        }
+        self.flatten_union_members(&union.type_, expanding, out);
+    }
+
+    fn flatten_union_members(
+        &self,
+        union: &UnionType<'a>,
+        expanding: &mut Vec<&'m str>,
+        out: &mut Flattened<'m>,
+    ) {
+        for member in &union.body.list {
+            match member {
+                UnionMemberType::Single(member) => {
+                    self.flatten_distinguishable(&member.type_, expanding, out)
+                }
+                UnionMemberType::Union(union) => self.flatten_union(union, expanding, out),
+            }
+        }
+    }
+
+    fn flatten_distinguishable(
+        &self,
+        type_: &DistinguishableType<'a>,
+        expanding: &mut Vec<&'m str>,
+        out: &mut Flattened<'m>,
+    ) {
+        use DistinguishableType as D;
+
+        let (category, q_mark) = match type_ {
+            D::Integer(MayBeNull { q_mark, .. })
+            | D::FloatingPoint(MayBeNull { q_mark, .. })
+            | D::Byte(MayBeNull { q_mark, .. })
+            | D::Octet(MayBeNull { q_mark, .. }) => (Category::Numeric, q_mark),
+            D::Boolean(MayBeNull { q_mark, .. }) => (Category::Boolean, q_mark),
+            D::Bigint(MayBeNull { q_mark, .. }) => (Category::BigInt, q_mark),
+            D::ByteString(MayBeNull { q_mark, .. })
+            | D::DOMString(MayBeNull { q_mark, .. })
+            | D::USVString(MayBeNull { q_mark, .. }) => (Category::String, q_mark),
+            D::Sequence(MayBeNull { q_mark, .. })
+            | D::FrozenArrayType(MayBeNull { q_mark, .. })
+            | D::ObservableArrayType(MayBeNull { q_mark, .. }) => (Category::SequenceLike, q_mark),
+            D::Object(MayBeNull { q_mark, .. }) => (Category::Object, q_mark),
+            D::Symbol(MayBeNull { q_mark, .. }) => (Category::Symbol, q_mark),
+            D::ArrayBuffer(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("ArrayBuffer"), q_mark)
+            }
+            D::DataView(MayBeNull { q_mark, .. }) => (Category::Interface("DataView"), q_mark),
+            D::Int8Array(MayBeNull { q_mark, .. }) => (Category::Interface("Int8Array"), q_mark),
+            D::Int16Array(MayBeNull { q_mark, .. }) => (Category::Interface("Int16Array"), q_mark),
+            D::Int32Array(MayBeNull { q_mark, .. }) => (Category::Interface("Int32Array"), q_mark),
+            D::Uint8Array(MayBeNull { q_mark, .. }) => (Category::Interface("Uint8Array"), q_mark),
+            D::Uint16Array(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("Uint16Array"), q_mark)
+            }
+            D::Uint32Array(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("Uint32Array"), q_mark)
+            }
+            D::Uint8ClampedArray(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("Uint8ClampedArray"), q_mark)
+            }
+            D::BigInt64Array(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("BigInt64Array"), q_mark)
+            }
+            D::BigUint64Array(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("BigUint64Array"), q_mark)
+            }
+            D::Float32Array(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("Float32Array"), q_mark)
+            }
+            D::Float64Array(MayBeNull { q_mark, .. }) => {
+                (Category::Interface("Float64Array"), q_mark)
+            }
+            D::RecordType(MayBeNull { q_mark, .. }) => (Category::DictionaryLike, q_mark),
+            D::Undefined(MayBeNull { q_mark, .. }) => (Category::Undefined, q_mark),
+            D::Identifier(MayBeNull {
+                type_: name,
+                q_mark,
+            }) => {
+                match self.model.typedefs.get_key_value(name.as_str()) {
+                    Some((key, typedef)) if !expanding.contains(&key.as_str()) => {
+                        if q_mark.is_some() {
+                            out.nullables.push((text(type_), type_.span()));
+                        }
+                        // Errors are reported at the name of the typedef, not in its definition
+                        let mut expanded = Flattened::default();
+                        expanding.push(key);
+                        self.flatten_type(&typedef.type_.type_, expanding, &mut expanded);
+                        expanding.pop();
+                        out.members
+                            .extend(expanded.members.into_iter().map(|member| MemberType {
+                                span: type_.span(),
+                                ..member
+                            }));
+                        out.nullables.extend(
+                            expanded
+                                .nullables
+                                .into_iter()
+                                .map(|(text, _)| (text, type_.span())),
+                        );
+                        return;
+                    }
+                    _ => (self.category_of(name), q_mark),
+                }
+            }
+        };
+
+        if q_mark.is_some() {
+            out.nullables.push((text(type_), type_.span()));
+        }
+        out.members.push(MemberType {
+            category,
+            text: text(type_),
+            span: type_.span(),
+        });
+    }
+
+    fn category_of(&self, name: &Identifier<'a>) -> Category<'m> {
+        let model = self.model;
+        let name = name.as_str();
+        if let Some((name, _)) = model.interfaces.get_key_value(name) {
+            Category::Interface(name)
+        } else if model.dictionaries.contains_key(name) {
+            Category::Dictionary
+        } else if model.callback_interfaces.contains_key(name) {
+            Category::DictionaryLike
+        } else if model.callbacks.contains_key(name) {
+            Category::CallbackFunction
+        } else if model.enums.contains_key(name) {
+            Category::String
+        } else {
+            Category::Unknown
+        }
+    }
+
+    /// Whether the interface `name` inherits from the interface `ancestor`
+    fn inherits(&self, name: &str, ancestor: &str) -> bool {
+        let mut seen = BTreeSet::new();
+        let mut current = name;
+        while seen.insert(current) {
+            let parent = self
+                .model
+                .interfaces
+                .get(current)
+                .and_then(|interface| interface.definition.inheritance.as_ref());
+            match parent {
+                Some(parent) if parent.identifier.0 == ancestor => return true,
+                Some(parent) => current = parent.identifier.as_str(),
+                None => break,
+            }
+        }
+        false
+    }
+
+    fn distinguishable_categories(&self, a: Category<'m>, b: Category<'m>) -> bool {
+        use Category::*;
+
+        match (a, b) {
+            (Unknown, _) | (_, Unknown) => true,
+            (Any, _) | (_, Any) => false,
+            (Interface(a), Interface(b)) => a != b && !self.inherits(a, b) && !self.inherits(b, a),
+            (Object, other) | (other, Object) => !other.is_object(),
+            (a, b) if a.is_dictionary_like() && b.is_dictionary_like() => false,
+            (Undefined, other) | (other, Undefined) => !other.is_dictionary_like(),
+            (CallbackFunction, other) | (other, CallbackFunction) => {
+                !other.is_dictionary_like() && other != CallbackFunction
+            }
+            (a, b) => a != b,
+        }
+    }
+
+    fn distinguishable(&self, a: &Flattened<'m>, b: &Flattened<'m>) -> bool {
+        let nullable_conflict = |x: &Flattened<'m>, y: &Flattened<'m>| {
+            !x.nullables.is_empty() && (!y.nullables.is_empty() || y.has_dictionary())
+        };
+        if nullable_conflict(a, b) || nullable_conflict(b, a) {
+            return false;
+        }
+        a.members.iter().all(|x| {
+            b.members
+                .iter()
+                .all(|y| self.distinguishable_categories(x.category, y.category))
+        })
+    }
+
+    fn check_union(&mut self, union: &MayBeNull<UnionType<'a>>) {
+        let mut flattened = Flattened::default();
+        self.flatten_union_members(&union.type_, &mut vec![], &mut flattened);
+
+        let nullable = union.q_mark.is_some();
+        if nullable {
+            for (text, span) in &flattened.nullables {
+                self.error(
+                    format!("A nullable union type cannot include the nullable type `{text}`"),
+                    *span,
+                    "nullable member type",
+                );
+            }
+        } else {
+            for (text, span) in flattened.nullables.iter().skip(1) {
+                self.error(
+                    format!(
+                        "A union type cannot include more than one nullable type, found `{text}`"
+                    ),
+                    *span,
+                    "second nullable member type",
+                );
+            }
+        }
+        if nullable || !flattened.nullables.is_empty() {
+            for member in &flattened.members {
+                if member.category == Category::Dictionary {
+                    self.error(
+                        format!(
+                            "A nullable union type cannot include the dictionary `{}`",
+                            member.text
+                        ),
+                        member.span,
+                        "dictionary member type",
+                    );
+                }
+            }
+        }
+
+        for (i, member) in flattened.members.iter().enumerate() {
+            let previous = flattened.members[..i].iter().find(|previous| {
+                !self.distinguishable_categories(previous.category, member.category)
+            });
+            if let Some(previous) = previous {
+                self.error(
+                    format!(
+                        "`{}` and `{}` are not distinguishable in a union type",
+                        previous.text, member.text
+                    ),
+                    member.span,
+                    "not distinguishable",
+                );
+            }
+        }
+    }
+
+    fn check_arguments(&mut self, args: &ArgumentList<'a>) {
+        let mut names: Vec<&Identifier<'a>> = vec![];
+        let mut optional: Option<&Identifier<'a>> = None;
+        for (i, arg) in args.list.iter().enumerate() {
+            let identifier = match arg {
+                Argument::Single(arg) => &arg.identifier,
+                Argument::Variadic(arg) => &arg.identifier,
+            };
+            if names.iter().any(|name| name.0 == identifier.0) {
+                self.error(
+                    format!("Duplicate argument `{}`", identifier.0),
+                    identifier.1,
+                    "defined again here",
+                );
+            }
+            names.push(identifier);
+
+            match arg {
+                Argument::Single(arg) if arg.optional.is_some() => {
+                    optional.get_or_insert(identifier);
+                }
+                Argument::Single(_) => {
+                    if let Some(optional) = optional {
+                        self.error(
+                            format!(
+                                "Required argument `{}` follows optional argument `{}`",
+                                identifier.0, optional.0
+                            ),
+                            identifier.1,
+                            "required argument",
+                        );
+                    }
+                }
+                Argument::Variadic(_) if i + 1 < args.list.len() => {
+                    self.error(
+                        format!(
+                            "Variadic argument `{}` must be the last argument",
+                            identifier.0
+                        ),
+                        identifier.1,
+                        "variadic argument",
+                    );
+                }
+                Argument::Variadic(_) => {}
+            }
+        }
+    }
+
+    /// Reports a constant or default value that is not valid for its type
+    fn check_value(
+        &mut self,
+        result: Result<(), Mismatch>,
+        value: &dyn Terminals,
+        span: Span,
+        type_: &dyn Terminals,
+    ) {
+        let (value, type_) = (text(value), text(type_));
+        match result {
+            Ok(()) => {}
+            Err(Mismatch::Invalid) => self.error(
+                format!("`{value}` is not a valid value for `{type_}`"),
+                span,
+                "invalid value",
+            ),
+            Err(Mismatch::NotEnumValue) => self.error(
+                format!("`{value}` is not one of the values of `{type_}`"),
+                span,
+                "unknown enum value",
+            ),
+            Err(Mismatch::OutOfRange) => self.error(
+                format!("`{value}` is out of the range of `{type_}`"),
+                span,
+                "out of range",
+            ),
+        }
+    }
+
+    fn const_type_accepts(
+        &self,
+        type_: &ConstType<'a>,
+        value: IdlValue<'_>,
+    ) -> Result<(), Mismatch> {
+        let numeric = match type_ {
+            ConstType::Integer(type_) => Numeric::of_integer(type_),
+            ConstType::FloatingPoint(type_) => Numeric::of_float(type_),
+            ConstType::Byte(_) => Numeric::Integer {
+                min: i8::MIN.into(),
+                max: i8::MAX.into(),
+            },
+            ConstType::Octet(_) => Numeric::Integer {
+                min: 0,
+                max: u8::MAX.into(),
+            },
+            ConstType::Bigint(_) => Numeric::BigInt,
+            ConstType::Boolean(_) => return accepts(matches!(value, IdlValue::Boolean(_))),
+            ConstType::Identifier(name) => {
+                return match self.model.typedefs.get(name.as_str()) {
+                    Some(typedef) => self.type_accepts(&typedef.type_.type_, value, &mut vec![]),
+                    None => self.named_type_accepts(name, value),
+                }
+            }
+        };
+        numeric.accepts(value)
+    }
+
+    fn type_accepts(
+        &self,
+        type_: &Type<'a>,
+        value: IdlValue<'_>,
+        expanding: &mut Vec<&'m str>,
+    ) -> Result<(), Mismatch> {
+        match type_ {
+            Type::Single(SingleType::Any(_)) => Ok(()),
+            Type::Single(SingleType::Promise(_)) => Err(Mismatch::Invalid),
+            Type::Single(SingleType::Distinguishable(type_)) => {
+                self.distinguishable_accepts(type_, value, expanding)
+            }
+            Type::Union(union) => self.union_accepts(union, value, expanding),
+        }
+    }
+
+    /// Accepts the values valid for one of the member types
+    fn union_accepts(
+        &self,
+        union: &MayBeNull<UnionType<'a>>,
+        value: IdlValue<'_>,
+        expanding: &mut Vec<&'m str>,
+    ) -> Result<(), Mismatch> {
+        if union.q_mark.is_some() && matches!(value, IdlValue::Null) {
+            return Ok(());
+        }
+        let mut mismatch = Mismatch::Invalid;
+        for member in &union.type_.body.list {
+            let result = match member {
+                UnionMemberType::Single(member) => {
+                    self.distinguishable_accepts(&member.type_, value, expanding)
+                }
+                UnionMemberType::Union(union) => self.union_accepts(union, value, expanding),
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(reason) => mismatch = mismatch.max(reason),
+            }
+        }
+        Err(mismatch)
+    }
+
+    fn distinguishable_accepts(
+        &self,
+        type_: &DistinguishableType<'a>,
+        value: IdlValue<'_>,
+        expanding: &mut Vec<&'m str>,
+    ) -> Result<(), Mismatch> {
+        use DistinguishableType as D;
+
+        let q_mark = match type_ {
+            D::Integer(MayBeNull { q_mark, .. })
+            | D::FloatingPoint(MayBeNull { q_mark, .. })
+            | D::Boolean(MayBeNull { q_mark, .. })
+            | D::Byte(MayBeNull { q_mark, .. })
+            | D::Octet(MayBeNull { q_mark, .. })
+            | D::Bigint(MayBeNull { q_mark, .. })
+            | D::ByteString(MayBeNull { q_mark, .. })
+            | D::DOMString(MayBeNull { q_mark, .. })
+            | D::USVString(MayBeNull { q_mark, .. })
+            | D::Sequence(MayBeNull { q_mark, .. })
+            | D::Object(MayBeNull { q_mark, .. })
+            | D::Symbol(MayBeNull { q_mark, .. })
+            | D::ArrayBuffer(MayBeNull { q_mark, .. })
+            | D::DataView(MayBeNull { q_mark, .. })
+            | D::Int8Array(MayBeNull { q_mark, .. })
+            | D::Int16Array(MayBeNull { q_mark, .. })
+            | D::Int32Array(MayBeNull { q_mark, .. })
+            | D::Uint8Array(MayBeNull { q_mark, .. })
+            | D::Uint16Array(MayBeNull { q_mark, .. })
+            | D::Uint32Array(MayBeNull { q_mark, .. })
+            | D::Uint8ClampedArray(MayBeNull { q_mark, .. })
+            | D::BigInt64Array(MayBeNull { q_mark, .. })
+            | D::BigUint64Array(MayBeNull { q_mark, .. })
+            | D::Float32Array(MayBeNull { q_mark, .. })
+            | D::Float64Array(MayBeNull { q_mark, .. })
+            | D::FrozenArrayType(MayBeNull { q_mark, .. })
+            | D::ObservableArrayType(MayBeNull { q_mark, .. })
+            | D::RecordType(MayBeNull { q_mark, .. })
+            | D::Undefined(MayBeNull { q_mark, .. })
+            | D::Identifier(MayBeNull { q_mark, .. }) => q_mark,
+        };
+        if q_mark.is_some() && matches!(value, IdlValue::Null) {
+            return Ok(());
+        }
+
+        let numeric = match type_ {
+            D::Integer(type_) => Numeric::of_integer(&type_.type_),
+            D::FloatingPoint(type_) => Numeric::of_float(&type_.type_),
+            D::Byte(_) => Numeric::Integer {
+                min: i8::MIN.into(),
+                max: i8::MAX.into(),
+            },
+            D::Octet(_) => Numeric::Integer {
+                min: 0,
+                max: u8::MAX.into(),
+            },
+            D::Bigint(_) => Numeric::BigInt,
+            D::Boolean(_) => return accepts(matches!(value, IdlValue::Boolean(_))),
+            D::ByteString(_) | D::DOMString(_) | D::USVString(_) => {
+                return accepts(matches!(value, IdlValue::String(_)))
+            }
+            D::Sequence(_) => return accepts(matches!(value, IdlValue::EmptySequence)),
+            D::Identifier(MayBeNull { type_: name, .. }) => {
+                return match self.model.typedefs.get_key_value(name.as_str()) {
+                    Some((key, typedef)) if !expanding.contains(&key.as_str()) => {
+                        expanding.push(key);
+                        let result = self.type_accepts(&typedef.type_.type_, value, expanding);
+                        expanding.pop();
+                        result
+                    }
+                    Some(_) => Ok(()),
+                    None => self.named_type_accepts(name, value),
+                };
+            }
+            _ => return Err(Mismatch::Invalid),
+        };
+        numeric.accepts(value)
+    }
+
+    /// Accepts the values valid for an enumeration or a dictionary, and any value for the names
+    /// that are not defined
+    fn named_type_accepts(
+        &self,
+        name: &Identifier<'a>,
+        value: IdlValue<'_>,
+    ) -> Result<(), Mismatch> {
+        match self.category_of(name) {
+            Category::String => {
+                let values = &self.model.enums[name.as_str()].values.body.list;
+                match value {
+                    IdlValue::String(value) if values.iter().any(|v| v.0 == value) => Ok(()),
+                    IdlValue::String(_) => Err(Mismatch::NotEnumValue),
+                    _ => Err(Mismatch::Invalid),
+                }
+            }
+            Category::Dictionary => accepts(matches!(value, IdlValue::EmptyDictionary)),
+            Category::Unknown => Ok(()),
+            _ => Err(Mismatch::Invalid),
+        }
+    }
+
+    fn check_inheritance(&mut self) {
+        let interfaces = self.model.interfaces.iter().map(|(name, interface)| {
+            (
+                name.as_str(),
+                interface.definition.inheritance.as_ref(),
+                "Interface",
+            )
+        });
+        let dictionaries = self.model.dictionaries.iter().map(|(name, dictionary)| {
+            (
+                name.as_str(),
+                dictionary.definition.inheritance.as_ref(),
+                "Dictionary",
+            )
+        });
+        let parent = |kind: &str, name: &str| {
+            let inheritance = match kind {
+                "Interface" => self
+                    .model
+                    .interfaces
+                    .get(name)?
+                    .definition
+                    .inheritance
+                    .as_ref(),
+                _ => self
+                    .model
+                    .dictionaries
+                    .get(name)?
+                    .definition
+                    .inheritance
+                    .as_ref(),
+            };
+            inheritance.map(|inheritance| inheritance.identifier.as_str())
+        };
+
+        let mut errors = vec![];
+        for (name, inheritance, kind) in interfaces.chain(dictionaries) {
+            let Some(inheritance) = inheritance else {
+                continue;
+            };
+            let mut seen = BTreeSet::new();
+            let mut current = inheritance.identifier.as_str();
+            while current != name && seen.insert(current) {
+                match parent(kind, current) {
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+            if current == name {
+                errors.push((
+                    format!("{kind} `{name}` inherits from itself"),
+                    inheritance.identifier.1,
+                ));
+            }
+        }
+        for (message, span) in errors {
+            self.error(message, span, "cyclic inheritance");
+        }
+    }
+
+    fn check_overloads(&mut self) {
+        let model = self.model;
+        for interface in model.interfaces.values() {
+            let mut regular = Overloads::default();
+            let mut statics = Overloads::default();
+            let mut constructors = Overloads::default();
+            for member in &interface.definition.members.body {
+                match member {
+                    InterfaceMember::Operation(OperationInterfaceMember {
+                        modifier,
+                        identifier: Some(identifier),
+                        args,
+                        ..
+                    }) => {
+                        let overloads = match modifier {
+                            Some(Modifier::Static(_)) => &mut statics,
+                            _ => &mut regular,
+                        };
+                        overloads.add(identifier.as_str(), identifier.1, &args.body);
+                    }
+                    InterfaceMember::Constructor(ConstructorInterfaceMember {
+                        constructor,
+                        args,
+                        ..
+                    }) => constructors.add("constructor", constructor.span(), &args.body),
+                    _ => {}
+                }
+            }
+            self.check_overload_sets(regular);
+            self.check_overload_sets(statics);
+            self.check_overload_sets(constructors);
+        }
+
+        for callback_interface in model.callback_interfaces.values() {
+            let mut overloads = Overloads::default();
+            for member in &callback_interface.members.body {
+                if let CallbackInterfaceMember::Operation(operation) = member {
+                    overloads.add_regular(operation);
+                }
+            }
+            self.check_overload_sets(overloads);
+        }
+        for mixin in model.mixins.values() {
+            let mut overloads = Overloads::default();
+            for member in &mixin.definition.members.body {
+                if let MixinMember::Operation(operation) = member {
+                    overloads.add_regular(operation);
+                }
+            }
+            self.check_overload_sets(overloads);
+        }
+        for namespace in model.namespaces.values() {
+            let mut overloads = Overloads::default();
+            for member in &namespace.definition.members.body {
+                if let NamespaceMember::Operation(operation) = member {
+                    overloads.add_regular(operation);
+                }
+            }
+            self.check_overload_sets(overloads);
+        }
+    }
+
+    fn check_overload_sets(&mut self, overloads: Overloads<'_, 'a>) {
+        let mut names: Vec<&str> = overloads.0.iter().map(|overload| overload.name).collect();
+        names.sort_unstable();
+        names.dedup();
+
+        for name in names {
+            let set: Vec<&Overload<'_, 'a>> = overloads
+                .0
+                .iter()
+                .filter(|overload| overload.name == name)
+                .collect();
+            if set.len() < 2 {
+                continue;
+            }
+            self.check_overload_set(&set);
+        }
+    }
+
+    /// Checks the effective overload set of the operations sharing a name
+    fn check_overload_set(&mut self, set: &[&Overload<'_, 'a>]) {
+        let max_args = set.iter().map(|overload| overload.args.list.len()).max();
+        let max_args = max_args.unwrap_or(0);
+
+        // The lists of argument types each operation can be called with
+        let mut entries: Vec<(usize, Vec<Flattened<'m>>)> = vec![];
+        for (index, overload) in set.iter().enumerate() {
+            let args = &overload.args.list;
+            let types: Vec<Flattened<'m>> = args
+                .iter()
+                .map(|arg| {
+                    let type_ = match arg {
+                        Argument::Single(arg) => &arg.type_.type_,
+                        Argument::Variadic(arg) => &arg.type_,
+                    };
+                    let mut flattened = Flattened::default();
+                    self.flatten_type(type_, &mut vec![], &mut flattened);
+                    flattened
+                })
+                .collect();
+
+            if let (Some(Argument::Variadic(_)), Some(last)) = (args.last(), types.last()) {
+                for len in types.len() + 1..=max_args {
+                    let mut types = types.clone();
+                    types.resize(len, last.clone());
+                    entries.push((index, types));
+                }
+            }
+            entries.push((index, types.clone()));
+            for (i, arg) in args.iter().enumerate().rev() {
+                let skippable = match arg {
+                    Argument::Single(arg) => arg.optional.is_some(),
+                    Argument::Variadic(_) => true,
+                };
+                if !skippable {
+                    break;
+                }
+                entries.push((index, types[..i].to_vec()));
+            }
+        }
+
+        let mut reported = BTreeSet::new();
+        for (i, (a, a_types)) in entries.iter().enumerate() {
+            for (b, b_types) in &entries[..i] {
+                if a == b || a_types.len() != b_types.len() || reported.contains(&(*b, *a)) {
+                    continue;
+                }
+                let distinguishable = a_types
+                    .iter()
+                    .zip(b_types)
+                    .any(|(a, b)| self.distinguishable(a, b));
+                if !distinguishable {
+                    reported.insert((*b, *a));
+                    let (first, second) = (set[*a.min(b)], set[*a.max(b)]);
+                    let count = a_types.len();
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!(
+                                "Overloads of `{}` are not distinguishable with {count} argument{}",
+                                second.name,
+                                plural(count)
+                            ),
+                            second.span,
+                        )
+                        .with_label("overload defined here")
+                        .with_note(format!(
+                            "`{}({})` is already declared",
+                            first.name,
+                            text(first.args)
+                        )),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// An operation or constructor, by name
+struct Overload<'m, 'a> {
+    name: &'m str,
+    span: Span,
+    args: &'m ArgumentList<'a>,
+}
+
+#[derive(Default)]
+struct Overloads<'m, 'a>(Vec<Overload<'m, 'a>>);
+
+impl<'m, 'a> Overloads<'m, 'a> {
+    fn add(&mut self, name: &'m str, span: Span, args: &'m ArgumentList<'a>) {
+        self.0.push(Overload { name, span, args });
+    }
+
+    fn add_regular(&mut self, operation: &'m RegularOperationMember<'a>) {
+        if let Some(identifier) = &operation.identifier {
+            self.add(identifier.as_str(), identifier.1, &operation.args.body);
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Validator<'_, 'a> {
+    fn visit_callback_definition(&mut self, node: &CallbackDefinition<'a>) {
+        self.check_arguments(&node.arguments.body);
+        node.walk(self);
+    }
+
+    fn visit_enum_definition(&mut self, node: &EnumDefinition<'a>) {
+        let values = &node.values.body.list;
+        for (i, value) in values.iter().enumerate() {
+            if values[..i].iter().any(|previous| previous.0 == value.0) {
+                self.error(
+                    format!(
+                        "Duplicate value \"{}\" in enum `{}`",
+                        value.0, node.identifier.0
+                    ),
+                    value.1,
+                    "defined again here",
+                );
+            }
+        }
+        node.walk(self);
+    }
+
+    fn visit_extended_attribute_arg_list(&mut self, node: &ExtendedAttributeArgList<'a>) {
+        self.check_arguments(&node.args.body);
+        node.walk(self);
+    }
+
+    fn visit_extended_attribute_named_arg_list(
+        &mut self,
+        node: &ExtendedAttributeNamedArgList<'a>,
+    ) {
+        self.check_arguments(&node.args.body);
+        node.walk(self);
+    }
+
+    fn visit_field_dictionary_member(&mut self, node: &FieldDictionaryMember<'a>) {
+        // The parser rejects these, but the tree can also be built or edited by hand
+        if let (Some(_), Some(default)) = (&node.required, &node.default) {
+            self.error(
+                format!(
+                    "Required member `{}` cannot have a default value",
+                    node.identifier.0
+                ),
+                default.span(),
+                "default value",
+            );
+        }
+        if let Some(default) = &node.default {
+            let result = default_value(&default.value)
+                .and_then(|value| self.type_accepts(&node.type_, value, &mut vec![]));
+            self.check_value(result, &default.value, default.value.span(), &node.type_);
+        }
+        node.walk(self);
+    }
+
+    fn visit_single_argument(&mut self, node: &SingleArgument<'a>) {
+        if let Some(default) = &node.default {
+            let result = default_value(&default.value)
+                .and_then(|value| self.type_accepts(&node.type_.type_, value, &mut vec![]));
+            self.check_value(
+                result,
+                &default.value,
+                default.value.span(),
+                &node.type_.type_,
+            );
+        }
+        node.walk(self);
+    }
+
+    fn visit_const_member(&mut self, node: &ConstMember<'a>) {
+        let result = const_value(&node.const_value)
+            .and_then(|value| self.const_type_accepts(&node.const_type, value));
+        self.check_value(
+            result,
+            &node.const_value,
+            node.const_value.span(),
+            &node.const_type,
+        );
+        node.walk(self);
+    }
+
+    fn visit_constructor_interface_member(&mut self, node: &ConstructorInterfaceMember<'a>) {
+        self.check_arguments(&node.args.body);
+        node.walk(self);
+    }
+
+    fn visit_operation_interface_member(&mut self, node: &OperationInterfaceMember<'a>) {
+        self.check_arguments(&node.args.body);
+        node.walk(self);
+    }
+
+    fn visit_regular_operation_member(&mut self, node: &RegularOperationMember<'a>) {
+        self.check_arguments(&node.args.body);
+        node.walk(self);
+    }
+
+    fn visit_type(&mut self, node: &Type<'a>) {
+        if let Type::Union(union) = node {
+            self.check_union(union);
+        }
+        node.walk(self);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dictionary::DictionaryMember;
+
+    fn messages(source: &str) -> Vec<String> {
+        let definitions = crate::parse(source).unwrap();
+        validate(&definitions)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn should_report_duplicates() {
+        assert_eq!(
+            messages(
+                r#"
+                interface Node { attribute long a; attribute long a; };
+                enum Mode { "open", "closed", "open" };
+                callback Handler = undefined (long x, long x);
+                dictionary Node {};
+                "#
+            ),
+            [
+                "Duplicate member `a` in `Node`",
+                "Duplicate value \"open\" in enum `Mode`",
+                "Duplicate argument `x`",
+                "`Node` is defined more than once",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_inheritance_cycles() {
+        assert_eq!(
+            messages(
+                "
+                dictionary A : B {};
+                dictionary B : A {};
+                dictionary C : A {};
+                interface I : I {};
+                "
+            ),
+            [
+                "Dictionary `A` inherits from itself",
+                "Dictionary `B` inherits from itself",
+                "Interface `I` inherits from itself",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_required_member_with_default() {
+        let mut definitions =
+            crate::parse("dictionary A { required long a; long b = 1; };").unwrap();
+        if let Definition::Dictionary(dictionary) = &mut definitions[0] {
+            let [DictionaryMember::Field(a), DictionaryMember::Field(b)] =
+                &mut dictionary.members.body[..]
+            else {
+                unreachable!()
+            };
+            a.default = b.default.take();
+        }
+
+        let diagnostics = validate(&definitions);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Required member `a` cannot have a default value"
+        );
+    }
+
+    #[test]
+    fn should_report_argument_order() {
+        assert_eq!(
+            messages(
+                "
+                interface A {
+                    undefined f(optional long a, long b);
+                    undefined g(long... a, long b);
+                    undefined h(long a, optional long b, long... c);
+                };
+                "
+            ),
+            [
+                "Required argument `b` follows optional argument `a`",
+                "Variadic argument `a` must be the last argument",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_nullable_unions() {
+        assert_eq!(
+            messages(
+                "
+                dictionary D {};
+                typedef (long or DOMString?)? A;
+                typedef (long? or (DOMString or Node)?) B;
+                typedef (long or D)? C;
+                typedef (long? or Node) E;
+                "
+            ),
+            [
+                "A nullable union type cannot include the nullable type `DOMString?`",
+                "A union type cannot include more than one nullable type, found `(DOMString or Node)?`",
+                "A nullable union type cannot include the dictionary `D`",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_indistinguishable_union_members() {
+        assert_eq!(
+            messages(
+                r#"
+                enum Mode { "open" };
+                interface Node {};
+                interface Element : Node {};
+                callback Handler = undefined ();
+                dictionary Options {};
+                typedef (long or byte) A;
+                typedef (DOMString or Mode) B;
+                typedef (Node or Element) C;
+                typedef (object or sequence<long>) D;
+                typedef (Handler or Options) E;
+                typedef (Node or Handler or Options or DOMString or long) F;
+                typedef (Node or Window) G;
+                "#
+            ),
+            [
+                "`long` and `byte` are not distinguishable in a union type",
+                "`DOMString` and `Mode` are not distinguishable in a union type",
+                "`Node` and `Element` are not distinguishable in a union type",
+                "`object` and `sequence<long>` are not distinguishable in a union type",
+                "`Handler` and `Options` are not distinguishable in a union type",
+                "`Handler` and `Options` are not distinguishable in a union type",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_indistinguishable_overloads() {
+        assert_eq!(
+            messages(
+                "
+                interface A {
+                    constructor();
+                    constructor(long a);
+                    undefined f(long a);
+                    undefined f(DOMString a);
+                    undefined g(long a, optional long b);
+                    undefined g(long a);
+                    undefined h(long... a);
+                    undefined h(long a, long b);
+                    static undefined f(byte a);
+                    undefined i(any a);
+                    undefined i(long a, long b);
+                };
+                "
+            ),
+            [
+                "Overloads of `g` are not distinguishable with 1 argument",
+                "Overloads of `h` are not distinguishable with 2 arguments",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_values_out_of_range() {
+        assert_eq!(
+            messages(
+                "
+                typedef unsigned long Count;
+                interface A {
+                    const octet B = 300;
+                    const byte C = -0x81;
+                    const long long D = -9223372036854775808;
+                    const unsigned long long E = 0xFFFFFFFFFFFFFFFF;
+                    const unsigned long F = -1;
+                    const float G = 1e39;
+                    const double H = 1e39;
+                    const Count I = 4294967296;
+                    const double J = 1e400;
+                    const unrestricted double K = -1e400;
+                    undefined f(optional short a = 40000, optional double b = 1e400);
+                };
+                "
+            ),
+            [
+                "`300` is out of the range of `octet`",
+                "`-0x81` is out of the range of `byte`",
+                "`-1` is out of the range of `unsigned long`",
+                "`1e39` is out of the range of `float`",
+                "`4294967296` is out of the range of `Count`",
+                "`1e400` is out of the range of `double`",
+                "`-1e400` is out of the range of `unrestricted double`",
+                "`40000` is out of the range of `short`",
+                "`1e400` is out of the range of `double`",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_values() {
+        assert_eq!(
+            messages(
+                r#"
+                enum Mode { "open", "closed" };
+                dictionary Options {
+                    boolean a = "yes";
+                    long b = 1.5;
+                    Mode c = "opened";
+                    DOMString d = null;
+                    DOMString? e = null;
+                    sequence<long> f = {};
+                    sequence<long> g = [];
+                    Options h = {};
+                    record<DOMString, long> i = {};
+                    (long or Mode)? j = "closed";
+                    (long or Mode)? k = null;
+                    Unknown l = "anything";
+                    any m = [];
+                };
+                interface A {
+                    const float B = Infinity;
+                    const unrestricted double C = NaN;
+                    const boolean D = 1;
+                };
+                "#
+            ),
+            [
+                "`\"yes\"` is not a valid value for `boolean`",
+                "`1.5` is not a valid value for `long`",
+                "`\"opened\"` is not one of the values of `Mode`",
+                "`null` is not a valid value for `DOMString`",
+                "`{}` is not a valid value for `sequence<long>`",
+                "`{}` is not a valid value for `record<DOMString, long>`",
+                "`Infinity` is not a valid value for `float`",
+                "`1` is not a valid value for `boolean`",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_check_overloads_from_mixins() {
+        let diagnostics = validate(
+            &crate::parse(
+                "
+                interface A { undefined f(long a); };
+                interface B {};
+                A includes M;
+                B includes M;
+                interface mixin M { undefined f(short a); undefined g(); undefined g(); };
+                ",
+            )
+            .unwrap(),
+        );
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Overloads of `f` are not distinguishable with 1 argument",
+                "Overloads of `g` are not distinguishable with 0 arguments",
+            ]
+        );
+    }
+}
@@ -0,0 +1,406 @@
+//! Traversal of the syntax tree
+//!
+//! [`Visit`] and [`VisitMut`] have a method for each node type, called when the node is
+//! reached. By default the method walks the children of the node, so an implementation only
+//! overrides the methods of the nodes it is interested in, and calls [`Walk::walk`] (or
+//! [`Walk::walk_mut`]) to keep going down the tree.
+//!
+//! The traversal is derived along with the parser, and every node deriving `Weedle` gets its
+//! methods in the visitors without further ado.
+//!
+//! ### Example
+//!
+//! Collecting the names of the types referenced by the definitions:
+//!
+//! ```
+//! use weedle::types::DistinguishableType;
+//! use weedle::visit::{Visit, Walk};
+//!
+//! struct TypeNames(Vec<String>);
+//!
+//! impl<'a> Visit<'a> for TypeNames {
+//!     fn visit_distinguishable_type(&mut self, node: &DistinguishableType<'a>) {
+//!         if let DistinguishableType::Identifier(type_) = node {
+//!             self.0.push(type_.type_.0.to_string());
+//!         }
+//!         node.walk(self);
+//!     }
+//! }
+//!
+//! let definitions = weedle::parse("
+//!     interface Window {
+//!         readonly attribute Storage sessionStorage;
+//!         Promise<Response> fetch(RequestInfo input);
+//!     };
+//! ").unwrap();
+//!
+//! let mut names = TypeNames(vec![]);
+//! definitions.accept(&mut names);
+//! assert_eq!(names.0, ["Storage", "Response", "RequestInfo"]);
+//! ```
+//!
+//! Rewriting every `DOMString` to `USVString`:
+//!
+//! ```
+//! use weedle::term;
+//! use weedle::types::{DistinguishableType, MayBeNull};
+//! use weedle::visit::{VisitMut, Walk};
+//!
+//! struct ToUsvString;
+//!
+//! impl<'a> VisitMut<'a> for ToUsvString {
+//!     fn visit_distinguishable_type_mut(&mut self, node: &mut DistinguishableType<'a>) {
+//!         if let DistinguishableType::DOMString(MayBeNull { type_, q_mark }) = *node {
+//!             *node = DistinguishableType::USVString(MayBeNull {
+//!                 type_: term::USVString { span: type_.span },
+//!                 q_mark,
+//!             });
+//!         }
+//!         node.walk_mut(self);
+//!     }
+//! }
+//!
+//! let mut definitions = weedle::parse("typedef sequence<DOMString?> Names;").unwrap();
+//! definitions.accept_mut(&mut ToUsvString);
+//! assert_eq!(
+//!     definitions,
+//!     weedle::parse("typedef sequence<USVString?> Names;").unwrap()
+//! );
+//! ```
+
+use crate::common::{Identifier, Invalid, Punctuated, PunctuatedNonEmpty};
+use crate::literal::{BooleanLit, DecLit, FloatValueLit, HexLit, IntegerLit, OctLit, StringLit};
+
+/// A node of the syntax tree that can be traversed by a visitor
+pub trait Walk<'a> {
+    /// Calls the method of the visitor for this node
+    fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V);
+
+    /// Calls the method of the visitor for this node
+    fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V);
+
+    /// Visits the children of this node
+    fn walk<V: Visit<'a> + ?Sized>(&self, visitor: &mut V);
+
+    /// Visits the children of this node
+    fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V);
+}
+
+// Implements `Walk` for containers, which have no method in the visitors and visit the
+// nodes yielded by the given expression
+macro_rules! walk_container {
+    ($(impl<$($param:ident),+> for $typ:ty { |$this:ident| $nodes:expr, $nodes_mut:expr })*) => {
+        $(
+            impl<'a, $($param: Walk<'a>),+> Walk<'a> for $typ {
+                fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+                    self.walk(visitor)
+                }
+
+                fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+                    self.walk_mut(visitor)
+                }
+
+                fn walk<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+                    let $this = self;
+                    $nodes.into_iter().for_each(|node| node.accept(visitor));
+                }
+
+                fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+                    let $this = self;
+                    $nodes_mut.into_iter().for_each(|node| node.accept_mut(visitor));
+                }
+            }
+        )*
+    };
+}
+
+walk_container! {
+    impl<T> for Option<T> { |this| this.iter(), this.iter_mut() }
+    impl<T> for Vec<T> { |this| this.iter(), this.iter_mut() }
+    impl<T> for Box<T> { |this| [&**this], [&mut **this] }
+}
+
+impl<'a, T: Walk<'a>, U: Walk<'a>> Walk<'a> for (T, U) {
+    fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        self.walk(visitor)
+    }
+
+    fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        self.walk_mut(visitor)
+    }
+
+    fn walk<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        self.0.accept(visitor);
+        self.1.accept(visitor);
+    }
+
+    fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        self.0.accept_mut(visitor);
+        self.1.accept_mut(visitor);
+    }
+}
+
+impl<'a, T: Walk<'a>, U: Walk<'a>, W: Walk<'a>> Walk<'a> for (T, U, W) {
+    fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        self.walk(visitor)
+    }
+
+    fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        self.walk_mut(visitor)
+    }
+
+    fn walk<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        self.0.accept(visitor);
+        self.1.accept(visitor);
+        self.2.accept(visitor);
+    }
+
+    fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        self.0.accept_mut(visitor);
+        self.1.accept_mut(visitor);
+        self.2.accept_mut(visitor);
+    }
+}
+
+impl<'a, T: Walk<'a>, S: Walk<'a>> Walk<'a> for Punctuated<T, S> {
+    fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        self.walk(visitor)
+    }
+
+    fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        self.walk_mut(visitor)
+    }
+
+    fn walk<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        walk_punctuated(&self.list, &self.separators, visitor)
+    }
+
+    fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        walk_punctuated_mut(&mut self.list, &mut self.separators, visitor)
+    }
+}
+
+impl<'a, T: Walk<'a>, S: Walk<'a>> Walk<'a> for PunctuatedNonEmpty<T, S> {
+    fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        self.walk(visitor)
+    }
+
+    fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        self.walk_mut(visitor)
+    }
+
+    fn walk<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        walk_punctuated(&self.list, &self.separators, visitor)
+    }
+
+    fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        walk_punctuated_mut(&mut self.list, &mut self.separators, visitor)
+    }
+}
+
+fn walk_punctuated<'a, T: Walk<'a>, S: Walk<'a>, V: Visit<'a> + ?Sized>(
+    list: &[T],
+    separators: &[S],
+    visitor: &mut V,
+) {
+    for (i, item) in list.iter().enumerate() {
+        item.accept(visitor);
+        if let Some(separator) = separators.get(i) {
+            separator.accept(visitor);
+        }
+    }
+}
+
+fn walk_punctuated_mut<'a, T: Walk<'a>, S: Walk<'a>, V: VisitMut<'a> + ?Sized>(
+    list: &mut [T],
+    separators: &mut [S],
+    visitor: &mut V,
+) {
+    for (i, item) in list.iter_mut().enumerate() {
+        item.accept_mut(visitor);
+        if let Some(separator) = separators.get_mut(i) {
+            separator.accept_mut(visitor);
+        }
+    }
+}
+
+// Implements `Walk` for terminals, which have no children
+macro_rules! walk_terminal {
+    ($($typ:ident$(<$lt:lifetime>)? => $visit:ident, $visit_mut:ident;)*) => {
+        $(
+            impl<'a> Walk<'a> for $typ$(<$lt>)? {
+                fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+                    visitor.$visit(self)
+                }
+
+                fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+                    visitor.$visit_mut(self)
+                }
+
+                fn walk<V: Visit<'a> + ?Sized>(&self, _visitor: &mut V) {}
+
+                fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, _visitor: &mut V) {}
+            }
+        )*
+    };
+}
+
+walk_terminal! {
+    Identifier<'a> => visit_identifier, visit_identifier_mut;
+    Invalid<'a> => visit_invalid, visit_invalid_mut;
+    StringLit<'a> => visit_string_lit, visit_string_lit_mut;
+    DecLit<'a> => visit_dec_lit, visit_dec_lit_mut;
+    HexLit<'a> => visit_hex_lit, visit_hex_lit_mut;
+    OctLit<'a> => visit_oct_lit, visit_oct_lit_mut;
+    FloatValueLit<'a> => visit_float_value_lit, visit_float_value_lit_mut;
+    BooleanLit => visit_boolean_lit, visit_boolean_lit_mut;
+}
+
+impl<'a> Walk<'a> for IntegerLit<'a> {
+    fn accept<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_integer_lit(self)
+    }
+
+    fn accept_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        visitor.visit_integer_lit_mut(self)
+    }
+
+    fn walk<V: Visit<'a> + ?Sized>(&self, visitor: &mut V) {
+        match self {
+            IntegerLit::Dec(lit) => lit.accept(visitor),
+            IntegerLit::Hex(lit) => lit.accept(visitor),
+            IntegerLit::Oct(lit) => lit.accept(visitor),
+        }
+    }
+
+    fn walk_mut<V: VisitMut<'a> + ?Sized>(&mut self, visitor: &mut V) {
+        match self {
+            IntegerLit::Dec(lit) => lit.accept_mut(visitor),
+            IntegerLit::Hex(lit) => lit.accept_mut(visitor),
+            IntegerLit::Oct(lit) => lit.accept_mut(visitor),
+        }
+    }
+}
+
+// The nodes deriving `Weedle` get their methods from the sources of the crate, the ones
+// walked by hand above, or defined by a `macro_rules!` macro, are listed here
+weedle_derive::visitors! {
+    Identifier<'a>,
+    Invalid<'a>,
+    StringLit<'a>,
+    IntegerLit<'a>,
+    DecLit<'a>,
+    HexLit<'a>,
+    OctLit<'a>,
+    FloatValueLit<'a>,
+    BooleanLit,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::InterfaceMember;
+    use crate::{InterfaceDefinition, Parse};
+
+    #[derive(Default)]
+    struct Identifiers(Vec<String>);
+
+    impl<'a> Visit<'a> for Identifiers {
+        fn visit_identifier(&mut self, node: &Identifier<'a>) {
+            self.0.push(node.0.to_string());
+        }
+    }
+
+    #[test]
+    fn should_visit_in_source_order() {
+        let defs = crate::parse(
+            "
+            [Exposed=Window]
+            interface Node : EventTarget {
+                attribute Node? parent;
+                undefined append((Node or DOMString) node);
+            };
+            Node includes Mixin;
+            ",
+        )
+        .unwrap();
+
+        let mut identifiers = Identifiers::default();
+        defs.accept(&mut identifiers);
+        assert_eq!(
+            identifiers.0,
+            [
+                "Exposed",
+                "Window",
+                "Node",
+                "EventTarget",
+                "Node",
+                "parent",
+                "append",
+                "Node",
+                "node",
+                "Node",
+                "Mixin"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_stop_at_overridden_node() {
+        struct Members(usize);
+
+        impl<'a> Visit<'a> for Members {
+            fn visit_interface_member(&mut self, _node: &InterfaceMember<'a>) {
+                self.0 += 1;
+            }
+
+            fn visit_identifier(&mut self, _node: &Identifier<'a>) {
+                panic!("Should not walk into the members");
+            }
+        }
+
+        let (_, interface) =
+            InterfaceDefinition::parse("interface A { attribute long a; const long b = 1; };")
+                .unwrap();
+        let mut members = Members(0);
+        interface.members.accept(&mut members);
+        assert_eq!(members.0, 2);
+    }
+
+    #[test]
+    fn should_visit_invalid_nodes() {
+        #[derive(Default)]
+        struct Invalids(Vec<String>);
+
+        impl<'a> Visit<'a> for Invalids {
+            fn visit_invalid(&mut self, node: &Invalid<'a>) {
+                self.0.push(node.0.to_string());
+            }
+        }
+
+        let (defs, _) = crate::parse_recovering("interface A { attribute long; }; typedef long");
+        let mut invalids = Invalids::default();
+        defs.accept(&mut invalids);
+        assert_eq!(invalids.0, ["attribute long;", "typedef long"]);
+    }
+
+    #[test]
+    fn should_rewrite_identifiers() {
+        struct Rename;
+
+        impl<'a> VisitMut<'a> for Rename {
+            fn visit_identifier_mut(&mut self, node: &mut Identifier<'a>) {
+                if node.0 == "Node" {
+                    node.0 = "Element".into();
+                }
+            }
+        }
+
+        let mut defs = crate::parse("interface Node { Node? next(Node node); };").unwrap();
+        defs.accept_mut(&mut Rename);
+        assert_eq!(
+            defs,
+            crate::parse("interface Element { Element? next(Element node); };").unwrap()
+        );
+    }
+}
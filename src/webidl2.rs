@@ -0,0 +1,1610 @@
+//! Conversion from and to the JSON syntax tree of [webidl2.js](https://github.com/w3c/webidl2.js)
+//!
+//! [`to_json`] writes definitions in the shape produced by `WebIDL2.parse()`, with `type`,
+//! `name`, `idlType`, `extAttrs`, `members` and so on, ending with the `eof` node. Names are
+//! unescaped the way webidl2.js does, so `_interface` is written as `interface`. Type names
+//! that would then read as a builtin type are the exception: webidl2.js writes the type `_any`
+//! as `any`, which cannot be told apart from the builtin `any`, so it is kept as `_any`.
+//!
+//! [`from_json`] reads such a tree back. It writes the WebIDL source the tree stands for,
+//! which [`to_webidl`] returns, and parses it. The spans of the returned definitions refer to
+//! that source. Names, strings and values that are not a single token of their kind are
+//! rejected with [`ImportError::Shape`], so that a tree cannot smuggle other definitions into
+//! the source.
+//!
+//! Definitions skipped by [`parse_recovering`](crate::parse_recovering) are left out, and so
+//! are the [`legacy`](crate::legacy) nodes, which webidl2.js does not parse.
+//!
+//! ### Example
+//!
+//! ```
+//! use weedle::webidl2::{from_json, to_json};
+//!
+//! let definitions = weedle::parse("enum Mode { \"open\", \"closed\" };").unwrap();
+//! let json = to_json(&definitions);
+//! assert_eq!(
+//!     json[0],
+//!     serde_json::json!({
+//!         "type": "enum",
+//!         "name": "Mode",
+//!         "values": [
+//!             { "type": "enum-value", "value": "open" },
+//!             { "type": "enum-value", "value": "closed" },
+//!         ],
+//!         "extAttrs": [],
+//!     })
+//! );
+//!
+//! assert_eq!(from_json(&json).unwrap(), definitions);
+//! ```
+
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::argument::{Argument, ArgumentList};
+use crate::attribute::{ExtendedAttribute, ExtendedAttributeList};
+use crate::common::{Default, Identifier};
+use crate::dictionary::DictionaryMember;
+use crate::interface::{
+    AsyncIterableInterfaceMember, CallbackInterfaceMember, Inheritance, InterfaceMember,
+    IterableInterfaceMember,
+};
+use crate::lexer::{Lexer, Terminal};
+use crate::literal::{ConstValue, DefaultValue, FloatLit, IntegerLit};
+use crate::members::{Modifier, RegularOperationMember, StringifierOrInheritOrStatic};
+use crate::mixin::MixinMember;
+use crate::namespace::NamespaceMember;
+use crate::owned::IntoOwned;
+use crate::term::Keyword;
+use crate::types::{
+    AttributedType, ConstType, DistinguishableType, FloatingPointType, IntegerType, MayBeNull,
+    RecordKeyType, SingleType, Type, UnionMemberType, UnionType,
+};
+use crate::{Definition, Definitions};
+
+/// Writes the definitions as a webidl2.js syntax tree
+pub fn to_json(definitions: &[Definition<'_>]) -> Value {
+    let mut nodes: Vec<Value> = definitions.iter().filter_map(definition).collect();
+    nodes.push(json!({ "type": "eof", "value": "" }));
+    Value::Array(nodes)
+}
+
+/// Reads a webidl2.js syntax tree
+pub fn from_json(json: &Value) -> Result<Definitions<'static>, ImportError> {
+    let source = to_webidl(json)?;
+    let definitions = crate::parse(&source).map_err(ImportError::Parse)?;
+    Ok(definitions.into_owned())
+}
+
+/// Writes the WebIDL source a webidl2.js syntax tree stands for
+pub fn to_webidl(json: &Value) -> Result<String, ImportError> {
+    let nodes = json
+        .as_array()
+        .ok_or_else(|| ImportError::shape("Expected an array of definitions"))?;
+    let mut out = String::new();
+    for node in nodes {
+        write_definition(&mut out, node)?;
+    }
+    Ok(out)
+}
+
+/// An error reading a webidl2.js syntax tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ImportError {
+    /// A node is not shaped like a webidl2.js node
+    Shape(String),
+    /// The WebIDL source written from the tree does not parse
+    Parse(crate::Error),
+}
+
+impl ImportError {
+    fn shape(message: impl Into<String>) -> Self {
+        ImportError::Shape(message.into())
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Shape(message) => f.write_str(message),
+            ImportError::Parse(err) => write!(f, "Invalid definitions: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+// Export
+
+fn unescape<'i>(identifier: &'i Identifier<'_>) -> &'i str {
+    let name = identifier.as_str();
+    name.strip_prefix('_').unwrap_or(name)
+}
+
+fn definition(definition: &Definition<'_>) -> Option<Value> {
+    let value = match definition {
+        Definition::Callback(def) => json!({
+            "type": "callback",
+            "name": unescape(&def.identifier),
+            "idlType": type_(&def.return_type, Some("return-type"), &None),
+            "arguments": arguments(&def.arguments.body),
+            "extAttrs": ext_attrs(&def.attributes),
+        }),
+        Definition::CallbackInterface(def) => container(
+            "callback interface",
+            &def.identifier,
+            None,
+            def.members
+                .body
+                .iter()
+                .filter_map(callback_interface_member)
+                .collect(),
+            &def.attributes,
+            false,
+        ),
+        Definition::InterfaceMixin(def) => container(
+            "interface mixin",
+            &def.identifier,
+            None,
+            def.members.body.iter().filter_map(mixin_member).collect(),
+            &def.attributes,
+            false,
+        ),
+        Definition::Interface(def) => container(
+            "interface",
+            &def.identifier,
+            def.inheritance.as_ref(),
+            def.members
+                .body
+                .iter()
+                .filter_map(interface_member)
+                .collect(),
+            &def.attributes,
+            false,
+        ),
+        Definition::Namespace(def) => container(
+            "namespace",
+            &def.identifier,
+            None,
+            def.members
+                .body
+                .iter()
+                .filter_map(namespace_member)
+                .collect(),
+            &def.attributes,
+            false,
+        ),
+        Definition::Dictionary(def) => container(
+            "dictionary",
+            &def.identifier,
+            def.inheritance.as_ref(),
+            def.members.body.iter().filter_map(field).collect(),
+            &def.attributes,
+            false,
+        ),
+        Definition::PartialInterfaceMixin(def) => container(
+            "interface mixin",
+            &def.identifier,
+            None,
+            def.members.body.iter().filter_map(mixin_member).collect(),
+            &def.attributes,
+            true,
+        ),
+        Definition::PartialInterface(def) => container(
+            "interface",
+            &def.identifier,
+            None,
+            def.members
+                .body
+                .iter()
+                .filter_map(interface_member)
+                .collect(),
+            &def.attributes,
+            true,
+        ),
+        Definition::PartialDictionary(def) => container(
+            "dictionary",
+            &def.identifier,
+            None,
+            def.members.body.iter().filter_map(field).collect(),
+            &def.attributes,
+            true,
+        ),
+        Definition::PartialNamespace(def) => container(
+            "namespace",
+            &def.identifier,
+            None,
+            def.members
+                .body
+                .iter()
+                .filter_map(namespace_member)
+                .collect(),
+            &def.attributes,
+            true,
+        ),
+        Definition::Enum(def) => json!({
+            "type": "enum",
+            "name": unescape(&def.identifier),
+            "values": def
+                .values
+                .body
+                .list
+                .iter()
+                .map(|value| json!({ "type": "enum-value", "value": value.as_str() }))
+                .collect::<Vec<_>>(),
+            "extAttrs": ext_attrs(&def.attributes),
+        }),
+        Definition::Typedef(def) => json!({
+            "type": "typedef",
+            "name": unescape(&def.identifier),
+            "idlType": attributed_type(&def.type_, Some("typedef-type")),
+            "extAttrs": ext_attrs(&def.attributes),
+        }),
+        Definition::IncludesStatement(def) => json!({
+            "type": "includes",
+            "target": unescape(&def.lhs_identifier),
+            "includes": unescape(&def.rhs_identifier),
+            "extAttrs": ext_attrs(&def.attributes),
+        }),
+        Definition::Implements(_) | Definition::Exception(_) | Definition::Invalid(_) => {
+            return None
+        }
+    };
+    Some(value)
+}
+
+fn container(
+    kind: &str,
+    identifier: &Identifier<'_>,
+    inheritance: Option<&Inheritance<'_>>,
+    members: Vec<Value>,
+    attributes: &Option<ExtendedAttributeList<'_>>,
+    partial: bool,
+) -> Value {
+    json!({
+        "type": kind,
+        "name": unescape(identifier),
+        "inheritance": inheritance.map(|inheritance| unescape(&inheritance.identifier)),
+        "members": members,
+        "extAttrs": ext_attrs(attributes),
+        "partial": partial,
+    })
+}
+
+fn interface_member(member: &InterfaceMember<'_>) -> Option<Value> {
+    let value = match member {
+        InterfaceMember::Const(member) => const_(
+            &member.attributes,
+            &member.const_type,
+            &member.identifier,
+            &member.const_value,
+        ),
+        InterfaceMember::Attribute(member) => attribute(
+            &member.attributes,
+            match member.modifier {
+                Some(StringifierOrInheritOrStatic::Stringifier(_)) => "stringifier",
+                Some(StringifierOrInheritOrStatic::Inherit(_)) => "inherit",
+                Some(StringifierOrInheritOrStatic::Static(_)) => "static",
+                None => "",
+            },
+            member.readonly.is_some(),
+            &member.type_,
+            &member.identifier,
+        ),
+        InterfaceMember::Constructor(member) => json!({
+            "type": "constructor",
+            "arguments": arguments(&member.args.body),
+            "extAttrs": ext_attrs(&member.attributes),
+        }),
+        InterfaceMember::Operation(member) => operation(
+            &member.attributes,
+            match member.modifier {
+                Some(Modifier::Getter(_)) => "getter",
+                Some(Modifier::Setter(_)) => "setter",
+                Some(Modifier::Deleter(_)) => "deleter",
+                Some(Modifier::Static(_)) => "static",
+                None => "",
+            },
+            Some(&member.return_type),
+            member.identifier.as_ref(),
+            &member.args.body.list,
+        ),
+        InterfaceMember::Iterable(IterableInterfaceMember::Single(member)) => declaration(
+            "iterable",
+            &member.attributes,
+            vec![attributed_type(&member.generics.body, None)],
+            None,
+            false,
+        ),
+        InterfaceMember::Iterable(IterableInterfaceMember::Double(member)) => declaration(
+            "iterable",
+            &member.attributes,
+            vec![
+                attributed_type(&member.generics.body.0, None),
+                attributed_type(&member.generics.body.2, None),
+            ],
+            None,
+            false,
+        ),
+        InterfaceMember::AsyncIterable(AsyncIterableInterfaceMember::Single(member)) => {
+            declaration(
+                "async_iterable",
+                &member.attributes,
+                vec![attributed_type(&member.generics.body, None)],
+                member.args.as_ref().map(|args| &args.body),
+                false,
+            )
+        }
+        InterfaceMember::AsyncIterable(AsyncIterableInterfaceMember::Double(member)) => {
+            declaration(
+                "async_iterable",
+                &member.attributes,
+                vec![
+                    attributed_type(&member.generics.body.0, None),
+                    attributed_type(&member.generics.body.2, None),
+                ],
+                member.args.as_ref().map(|args| &args.body),
+                false,
+            )
+        }
+        InterfaceMember::Maplike(member) => declaration(
+            "maplike",
+            &member.attributes,
+            vec![
+                attributed_type(&member.generics.body.0, None),
+                attributed_type(&member.generics.body.2, None),
+            ],
+            None,
+            member.readonly.is_some(),
+        ),
+        InterfaceMember::Setlike(member) => declaration(
+            "setlike",
+            &member.attributes,
+            vec![attributed_type(&member.generics.body, None)],
+            None,
+            member.readonly.is_some(),
+        ),
+        InterfaceMember::Stringifier(member) => {
+            operation(&member.attributes, "stringifier", None, None, &[])
+        }
+        InterfaceMember::LegacyIterable(_) | InterfaceMember::Invalid(_) => return None,
+    };
+    Some(value)
+}
+
+fn mixin_member(member: &MixinMember<'_>) -> Option<Value> {
+    let value = match member {
+        MixinMember::Const(member) => const_(
+            &member.attributes,
+            &member.const_type,
+            &member.identifier,
+            &member.const_value,
+        ),
+        MixinMember::Operation(member) => regular_operation(member),
+        MixinMember::Attribute(member) => attribute(
+            &member.attributes,
+            if member.stringifier.is_some() {
+                "stringifier"
+            } else {
+                ""
+            },
+            member.readonly.is_some(),
+            &member.type_,
+            &member.identifier,
+        ),
+        MixinMember::Stringifier(member) => {
+            operation(&member.attributes, "stringifier", None, None, &[])
+        }
+        MixinMember::Invalid(_) => return None,
+    };
+    Some(value)
+}
+
+fn namespace_member(member: &NamespaceMember<'_>) -> Option<Value> {
+    let value = match member {
+        NamespaceMember::Operation(member) => regular_operation(member),
+        NamespaceMember::Attribute(member) => attribute(
+            &member.attributes,
+            "",
+            true,
+            &member.type_,
+            &member.identifier,
+        ),
+        NamespaceMember::Const(member) => const_(
+            &member.attributes,
+            &member.const_type,
+            &member.identifier,
+            &member.const_value,
+        ),
+        NamespaceMember::Invalid(_) => return None,
+    };
+    Some(value)
+}
+
+fn callback_interface_member(member: &CallbackInterfaceMember<'_>) -> Option<Value> {
+    let value = match member {
+        CallbackInterfaceMember::Const(member) => const_(
+            &member.attributes,
+            &member.const_type,
+            &member.identifier,
+            &member.const_value,
+        ),
+        CallbackInterfaceMember::Operation(member) => regular_operation(member),
+        CallbackInterfaceMember::Invalid(_) => return None,
+    };
+    Some(value)
+}
+
+fn field(member: &DictionaryMember<'_>) -> Option<Value> {
+    let DictionaryMember::Field(member) = member else {
+        return None;
+    };
+    Some(json!({
+        "type": "field",
+        "name": unescape(&member.identifier),
+        "extAttrs": ext_attrs(&member.attributes),
+        "idlType": type_(&member.type_, Some("dictionary-type"), &None),
+        "default": default(member.default.as_ref()),
+        "required": member.required.is_some(),
+    }))
+}
+
+fn const_(
+    attributes: &Option<ExtendedAttributeList<'_>>,
+    const_type: &ConstType<'_>,
+    identifier: &Identifier<'_>,
+    value: &ConstValue<'_>,
+) -> Value {
+    let type_name = match const_type {
+        ConstType::Integer(type_) => integer_type(type_),
+        ConstType::FloatingPoint(type_) => floating_point_type(type_),
+        ConstType::Boolean(term) => term.value().to_string(),
+        ConstType::Byte(term) => term.value().to_string(),
+        ConstType::Octet(term) => term.value().to_string(),
+        ConstType::Bigint(term) => term.value().to_string(),
+        ConstType::Identifier(identifier) => unescape(identifier).to_string(),
+    };
+    json!({
+        "type": "const",
+        "name": unescape(identifier),
+        "idlType": type_node(Some("const-type"), &None, "", false, false, type_name.into()),
+        "extAttrs": ext_attrs(attributes),
+        "value": const_value(value),
+    })
+}
+
+fn attribute(
+    attributes: &Option<ExtendedAttributeList<'_>>,
+    special: &str,
+    readonly: bool,
+    type_: &AttributedType<'_>,
+    identifier: &Identifier<'_>,
+) -> Value {
+    json!({
+        "type": "attribute",
+        "name": unescape(identifier),
+        "idlType": attributed_type(type_, Some("attribute-type")),
+        "extAttrs": ext_attrs(attributes),
+        "special": special,
+        "readonly": readonly,
+    })
+}
+
+fn regular_operation(member: &RegularOperationMember<'_>) -> Value {
+    operation(
+        &member.attributes,
+        "",
+        Some(&member.return_type),
+        member.identifier.as_ref(),
+        &member.args.body.list,
+    )
+}
+
+fn operation(
+    attributes: &Option<ExtendedAttributeList<'_>>,
+    special: &str,
+    return_type: Option<&Type<'_>>,
+    identifier: Option<&Identifier<'_>>,
+    args: &[Argument<'_>],
+) -> Value {
+    json!({
+        "type": "operation",
+        "name": identifier.map_or("", unescape),
+        "idlType": return_type.map(|return_type| type_(return_type, Some("return-type"), &None)),
+        "arguments": args.iter().map(argument).collect::<Vec<_>>(),
+        "extAttrs": ext_attrs(attributes),
+        "special": special,
+    })
+}
+
+fn declaration(
+    kind: &str,
+    attributes: &Option<ExtendedAttributeList<'_>>,
+    types: Vec<Value>,
+    args: Option<&ArgumentList<'_>>,
+    readonly: bool,
+) -> Value {
+    json!({
+        "type": kind,
+        "idlType": types,
+        "arguments": args.map_or_else(Vec::new, |args| args.list.iter().map(argument).collect()),
+        "extAttrs": ext_attrs(attributes),
+        "readonly": readonly,
+        "async": kind == "async_iterable",
+    })
+}
+
+fn arguments(args: &ArgumentList<'_>) -> Value {
+    args.list.iter().map(argument).collect()
+}
+
+fn argument(argument: &Argument<'_>) -> Value {
+    match argument {
+        Argument::Single(arg) => json!({
+            "type": "argument",
+            "name": unescape(&arg.identifier),
+            "extAttrs": ext_attrs(&arg.attributes),
+            "idlType": attributed_type(&arg.type_, Some("argument-type")),
+            "default": default(arg.default.as_ref()),
+            "optional": arg.optional.is_some(),
+            "variadic": false,
+        }),
+        Argument::Variadic(arg) => json!({
+            "type": "argument",
+            "name": unescape(&arg.identifier),
+            "extAttrs": ext_attrs(&arg.attributes),
+            "idlType": type_(&arg.type_, Some("argument-type"), &None),
+            "default": null,
+            "optional": false,
+            "variadic": true,
+        }),
+    }
+}
+
+fn ext_attrs(attributes: &Option<ExtendedAttributeList<'_>>) -> Value {
+    let Some(attributes) = attributes else {
+        return json!([]);
+    };
+    attributes.body.list.iter().map(ext_attr).collect()
+}
+
+fn ext_attr(attribute: &ExtendedAttribute<'_>) -> Value {
+    fn node(name: &Identifier<'_>, rhs: Value, args: Value) -> Value {
+        json!({
+            "type": "extended-attribute",
+            "name": name.as_str(),
+            "rhs": rhs,
+            "arguments": args,
+        })
+    }
+    fn rhs(kind: &str, value: Value) -> Value {
+        json!({ "type": kind, "value": value })
+    }
+    fn list<T>(items: &[T], f: impl Fn(&T) -> String) -> Value {
+        items
+            .iter()
+            .map(|item| json!({ "value": f(item) }))
+            .collect()
+    }
+
+    match attribute {
+        ExtendedAttribute::ArgList(attr) => {
+            node(&attr.identifier, Value::Null, arguments(&attr.args.body))
+        }
+        ExtendedAttribute::NamedArgList(attr) => node(
+            &attr.lhs_identifier,
+            rhs("identifier", attr.rhs_identifier.as_str().into()),
+            arguments(&attr.args.body),
+        ),
+        ExtendedAttribute::IdentList(attr) => node(
+            &attr.identifier,
+            rhs(
+                "identifier-list",
+                list(&attr.list.body.list, |id| id.as_str().to_string()),
+            ),
+            json!([]),
+        ),
+        ExtendedAttribute::Ident(attr) => node(
+            &attr.lhs_identifier,
+            rhs("identifier", attr.rhs.as_str().into()),
+            json!([]),
+        ),
+        ExtendedAttribute::Wildcard(attr) => {
+            node(&attr.lhs_identifier, rhs("*", "*".into()), json!([]))
+        }
+        ExtendedAttribute::String(attr) => node(
+            &attr.lhs_identifier,
+            rhs("string", format!("\"{}\"", attr.rhs.as_str()).into()),
+            json!([]),
+        ),
+        ExtendedAttribute::StringList(attr) => node(
+            &attr.identifier,
+            rhs(
+                "string-list",
+                list(&attr.list.body.list, |lit| format!("\"{}\"", lit.as_str())),
+            ),
+            json!([]),
+        ),
+        ExtendedAttribute::Float(attr) => node(
+            &attr.lhs_identifier,
+            rhs("decimal", float_lit(&attr.rhs).into()),
+            json!([]),
+        ),
+        ExtendedAttribute::FloatList(attr) => node(
+            &attr.identifier,
+            rhs("decimal-list", list(&attr.list.body.list, float_lit)),
+            json!([]),
+        ),
+        ExtendedAttribute::Integer(attr) => node(
+            &attr.lhs_identifier,
+            rhs("integer", integer_lit(&attr.rhs).into()),
+            json!([]),
+        ),
+        ExtendedAttribute::IntegerList(attr) => node(
+            &attr.identifier,
+            rhs("integer-list", list(&attr.list.body.list, integer_lit)),
+            json!([]),
+        ),
+        ExtendedAttribute::NoArgs(attr) => node(&attr.0, Value::Null, json!([])),
+    }
+}
+
+fn float_lit(lit: &FloatLit<'_>) -> String {
+    match lit {
+        FloatLit::Value(value) => value.as_str().to_string(),
+        FloatLit::NegInfinity(term) => term.value().to_string(),
+        FloatLit::Infinity(term) => term.value().to_string(),
+        FloatLit::NaN(term) => term.value().to_string(),
+    }
+}
+
+fn integer_lit(lit: &IntegerLit<'_>) -> String {
+    match lit {
+        IntegerLit::Dec(lit) => lit.as_str().to_string(),
+        IntegerLit::Hex(lit) => lit.as_str().to_string(),
+        IntegerLit::Oct(lit) => lit.as_str().to_string(),
+    }
+}
+
+fn const_value(value: &ConstValue<'_>) -> Value {
+    match value {
+        ConstValue::Boolean(lit) => json!({ "type": "boolean", "value": lit.value() }),
+        ConstValue::Float(lit) => float_value(lit),
+        ConstValue::Integer(lit) => json!({ "type": "number", "value": integer_lit(lit) }),
+    }
+}
+
+fn float_value(lit: &FloatLit<'_>) -> Value {
+    match lit {
+        FloatLit::Value(value) => json!({ "type": "number", "value": value.as_str() }),
+        FloatLit::NegInfinity(_) => json!({ "type": "Infinity", "negative": true }),
+        FloatLit::Infinity(_) => json!({ "type": "Infinity", "negative": false }),
+        FloatLit::NaN(_) => json!({ "type": "NaN" }),
+    }
+}
+
+fn default(default: Option<&Default<'_>>) -> Value {
+    let Some(default) = default else {
+        return Value::Null;
+    };
+    match &default.value {
+        DefaultValue::Boolean(lit) => json!({ "type": "boolean", "value": lit.value() }),
+        DefaultValue::EmptyArray(_) => json!({ "type": "sequence", "value": [] }),
+        DefaultValue::EmptyDictionary(_) => json!({ "type": "dictionary" }),
+        DefaultValue::Float(lit) => float_value(lit),
+        DefaultValue::Integer(lit) => json!({ "type": "number", "value": integer_lit(lit) }),
+        DefaultValue::Null(_) => json!({ "type": "null" }),
+        DefaultValue::String(lit) => json!({ "type": "string", "value": lit.as_str() }),
+    }
+}
+
+fn type_node(
+    context: Option<&str>,
+    attributes: &Option<ExtendedAttributeList<'_>>,
+    generic: &str,
+    nullable: bool,
+    union: bool,
+    idl_type: Value,
+) -> Value {
+    json!({
+        "type": context,
+        "extAttrs": ext_attrs(attributes),
+        "generic": generic,
+        "nullable": nullable,
+        "union": union,
+        "idlType": idl_type,
+    })
+}
+
+fn attributed_type(type_: &AttributedType<'_>, context: Option<&str>) -> Value {
+    self::type_(&type_.type_, context, &type_.attributes)
+}
+
+fn type_(
+    type_: &Type<'_>,
+    context: Option<&str>,
+    attributes: &Option<ExtendedAttributeList<'_>>,
+) -> Value {
+    match type_ {
+        Type::Single(SingleType::Any(_)) => {
+            type_node(context, attributes, "", false, false, "any".into())
+        }
+        Type::Single(SingleType::Promise(promise)) => type_node(
+            context,
+            attributes,
+            "Promise",
+            false,
+            false,
+            json!([self::type_(&promise.generics.body, context, &None)]),
+        ),
+        Type::Single(SingleType::Distinguishable(type_)) => {
+            distinguishable_type(type_, context, attributes)
+        }
+        Type::Union(union) => union_type(union, context, attributes),
+    }
+}
+
+fn union_type(
+    union: &MayBeNull<UnionType<'_>>,
+    context: Option<&str>,
+    attributes: &Option<ExtendedAttributeList<'_>>,
+) -> Value {
+    let members = union
+        .type_
+        .body
+        .list
+        .iter()
+        .map(|member| match member {
+            UnionMemberType::Single(member) => {
+                distinguishable_type(&member.type_, context, &member.attributes)
+            }
+            UnionMemberType::Union(member) => union_type(member, context, &None),
+        })
+        .collect();
+    type_node(
+        context,
+        attributes,
+        "",
+        union.q_mark.is_some(),
+        true,
+        members,
+    )
+}
+
+fn distinguishable_type(
+    type_: &DistinguishableType<'_>,
+    context: Option<&str>,
+    attributes: &Option<ExtendedAttributeList<'_>>,
+) -> Value {
+    let simple = |nullable: bool, name: String| {
+        type_node(context, attributes, "", nullable, false, name.into())
+    };
+    let generic = |nullable: bool, generic: &str, types: Vec<Value>| {
+        type_node(context, attributes, generic, nullable, false, types.into())
+    };
+    macro_rules! term {
+        ($type_:expr) => {
+            simple($type_.q_mark.is_some(), $type_.type_.value().to_string())
+        };
+    }
+
+    match type_ {
+        DistinguishableType::Integer(type_) => {
+            simple(type_.q_mark.is_some(), integer_type(&type_.type_))
+        }
+        DistinguishableType::FloatingPoint(type_) => {
+            simple(type_.q_mark.is_some(), floating_point_type(&type_.type_))
+        }
+        DistinguishableType::Boolean(type_) => term!(type_),
+        DistinguishableType::Byte(type_) => term!(type_),
+        DistinguishableType::Octet(type_) => term!(type_),
+        DistinguishableType::Bigint(type_) => term!(type_),
+        DistinguishableType::ByteString(type_) => term!(type_),
+        DistinguishableType::DOMString(type_) => term!(type_),
+        DistinguishableType::USVString(type_) => term!(type_),
+        DistinguishableType::Object(type_) => term!(type_),
+        DistinguishableType::Symbol(type_) => term!(type_),
+        DistinguishableType::ArrayBuffer(type_) => term!(type_),
+        DistinguishableType::DataView(type_) => term!(type_),
+        DistinguishableType::Int8Array(type_) => term!(type_),
+        DistinguishableType::Int16Array(type_) => term!(type_),
+        DistinguishableType::Int32Array(type_) => term!(type_),
+        DistinguishableType::Uint8Array(type_) => term!(type_),
+        DistinguishableType::Uint16Array(type_) => term!(type_),
+        DistinguishableType::Uint32Array(type_) => term!(type_),
+        DistinguishableType::Uint8ClampedArray(type_) => term!(type_),
+        DistinguishableType::BigInt64Array(type_) => term!(type_),
+        DistinguishableType::BigUint64Array(type_) => term!(type_),
+        DistinguishableType::Float32Array(type_) => term!(type_),
+        DistinguishableType::Float64Array(type_) => term!(type_),
+        DistinguishableType::Undefined(type_) => term!(type_),
+        DistinguishableType::Identifier(type_) => {
+            let name = type_.type_.as_str();
+            let name = if is_escaped_builtin(name) {
+                name
+            } else {
+                unescape(&type_.type_)
+            };
+            simple(type_.q_mark.is_some(), name.to_string())
+        }
+        DistinguishableType::Sequence(type_) => generic(
+            type_.q_mark.is_some(),
+            "sequence",
+            vec![attributed_type(&type_.type_.generics.body, context)],
+        ),
+        DistinguishableType::FrozenArrayType(type_) => generic(
+            type_.q_mark.is_some(),
+            "FrozenArray",
+            vec![attributed_type(&type_.type_.generics.body, context)],
+        ),
+        DistinguishableType::ObservableArrayType(type_) => generic(
+            type_.q_mark.is_some(),
+            "ObservableArray",
+            vec![attributed_type(&type_.type_.generics.body, context)],
+        ),
+        DistinguishableType::RecordType(type_) => {
+            let (key, _, value) = &type_.type_.generics.body;
+            let key = match key {
+                RecordKeyType::Byte(term) => term.value(),
+                RecordKeyType::DOM(term) => term.value(),
+                RecordKeyType::USV(term) => term.value(),
+            };
+            generic(
+                type_.q_mark.is_some(),
+                "record",
+                vec![
+                    type_node(context, &None, "", false, false, key.into()),
+                    attributed_type(value, context),
+                ],
+            )
+        }
+    }
+}
+
+fn integer_type(type_: &IntegerType) -> String {
+    let (unsigned, name) = match type_ {
+        IntegerType::LongLong(type_) => (type_.unsigned.is_some(), "long long"),
+        IntegerType::Long(type_) => (type_.unsigned.is_some(), "long"),
+        IntegerType::Short(type_) => (type_.unsigned.is_some(), "short"),
+    };
+    if unsigned {
+        format!("unsigned {name}")
+    } else {
+        name.to_string()
+    }
+}
+
+fn floating_point_type(type_: &FloatingPointType) -> String {
+    let (unrestricted, name) = match type_ {
+        FloatingPointType::Float(type_) => (type_.unrestricted.is_some(), "float"),
+        FloatingPointType::Double(type_) => (type_.unrestricted.is_some(), "double"),
+    };
+    if unrestricted {
+        format!("unrestricted {name}")
+    } else {
+        name.to_string()
+    }
+}
+
+// Import
+
+/// The type names written as keywords, which are not escaped
+const BUILTIN_TYPES: &[&str] = &[
+    "any",
+    "undefined",
+    "boolean",
+    "byte",
+    "octet",
+    "bigint",
+    "short",
+    "unsigned short",
+    "long",
+    "unsigned long",
+    "long long",
+    "unsigned long long",
+    "float",
+    "unrestricted float",
+    "double",
+    "unrestricted double",
+    "ByteString",
+    "DOMString",
+    "USVString",
+    "object",
+    "symbol",
+    "ArrayBuffer",
+    "DataView",
+    "Int8Array",
+    "Int16Array",
+    "Int32Array",
+    "Uint8Array",
+    "Uint16Array",
+    "Uint32Array",
+    "Uint8ClampedArray",
+    "BigInt64Array",
+    "BigUint64Array",
+    "Float32Array",
+    "Float64Array",
+];
+
+/// The generic types, written before their type arguments
+const GENERIC_TYPES: &[&str] = &[
+    "sequence",
+    "FrozenArray",
+    "ObservableArray",
+    "Promise",
+    "record",
+];
+
+/// The keywords that may start an attribute or an operation
+const SPECIALS: &[&str] = &[
+    "static",
+    "stringifier",
+    "inherit",
+    "getter",
+    "setter",
+    "deleter",
+];
+
+/// Whether a type name is the escaped form of a builtin type, e.g. `_any`, which is exported as
+/// it is so that it does not read as the builtin type
+fn is_escaped_builtin(name: &str) -> bool {
+    name.strip_prefix('_')
+        .is_some_and(|name| BUILTIN_TYPES.contains(&name))
+}
+
+/// Escapes a name that would otherwise be read as a keyword or lose its leading underscore
+fn escape(name: &str) -> Result<String, ImportError> {
+    let escaped = if name.starts_with('_') || Keyword::match_word(name).is_some() {
+        format!("_{name}")
+    } else {
+        name.to_string()
+    };
+    identifier(&escaped)?;
+    Ok(escaped)
+}
+
+/// Checks that the text is a single token, without trivia, that `is_kind` accepts
+fn token(text: &str, is_kind: impl Fn(&Terminal<'_>) -> bool) -> Result<&str, &str> {
+    match Lexer::new(text).next() {
+        Some(Ok(token))
+            if token.trivia.is_empty() && token.text == text && is_kind(&token.value) =>
+        {
+            Ok(text)
+        }
+        _ => Err(text),
+    }
+}
+
+fn identifier(name: &str) -> Result<&str, ImportError> {
+    token(name, |value| matches!(value, Terminal::Identifier(_)))
+        .map_err(|name| ImportError::shape(format!("Invalid identifier `{name}`")))
+}
+
+fn number(value: &str) -> Result<&str, ImportError> {
+    token(value, |value| {
+        matches!(value, Terminal::Integer(_) | Terminal::Decimal(_))
+    })
+    .map_err(|value| ImportError::shape(format!("Invalid number `{value}`")))
+}
+
+/// Checks the value of a string, which WebIDL has no way to escape a `"` in
+fn string(value: &str) -> Result<&str, ImportError> {
+    if value.contains('"') {
+        return Err(ImportError::shape(format!("Invalid string `{value}`")));
+    }
+    Ok(value)
+}
+
+/// Checks a value on the right hand side of an extended attribute
+fn ext_attr_value(value: &str) -> Result<&str, ImportError> {
+    token(value, |value| match value {
+        Terminal::Keyword(keyword) => matches!(
+            keyword,
+            Keyword::Wildcard(_) | Keyword::NegInfinity(_) | Keyword::Infinity(_) | Keyword::NaN(_)
+        ),
+        Terminal::Identifier(_)
+        | Terminal::Integer(_)
+        | Terminal::Decimal(_)
+        | Terminal::String(_) => true,
+        _ => false,
+    })
+    .map_err(|value| ImportError::shape(format!("Invalid extended attribute value `{value}`")))
+}
+
+fn field_of<'v>(node: &'v Value, key: &str) -> Result<&'v Value, ImportError> {
+    node.get(key).ok_or_else(|| {
+        ImportError::shape(format!(
+            "Missing `{key}` in {} node",
+            node.get("type").and_then(Value::as_str).unwrap_or("a")
+        ))
+    })
+}
+
+fn str_of<'v>(node: &'v Value, key: &str) -> Result<&'v str, ImportError> {
+    field_of(node, key)?
+        .as_str()
+        .ok_or_else(|| ImportError::shape(format!("Expected `{key}` to be a string")))
+}
+
+fn array_of<'v>(node: &'v Value, key: &str) -> Result<&'v [Value], ImportError> {
+    match node.get(key) {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(Value::Array(items)) => Ok(items),
+        Some(_) => Err(ImportError::shape(format!(
+            "Expected `{key}` to be an array"
+        ))),
+    }
+}
+
+fn bool_of(node: &Value, key: &str) -> Result<bool, ImportError> {
+    match node.get(key) {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(value)) => Ok(*value),
+        Some(_) => Err(ImportError::shape(format!(
+            "Expected `{key}` to be a boolean"
+        ))),
+    }
+}
+
+fn write_definition(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    let kind = str_of(node, "type")?;
+    if kind == "eof" {
+        return Ok(());
+    }
+    write_ext_attrs(out, node)?;
+    match kind {
+        "interface" | "interface mixin" | "callback interface" | "namespace" | "dictionary" => {
+            if bool_of(node, "partial")? {
+                out.push_str("partial ");
+            }
+            out.push_str(kind);
+            out.push(' ');
+            out.push_str(&escape(str_of(node, "name")?)?);
+            if let Some(inheritance) = node.get("inheritance").and_then(Value::as_str) {
+                out.push_str(" : ");
+                out.push_str(&escape(inheritance)?);
+            }
+            out.push_str(" {\n");
+            for member in array_of(node, "members")? {
+                out.push_str("    ");
+                write_member(out, member)?;
+                out.push('\n');
+            }
+            out.push_str("};\n");
+        }
+        "callback" => {
+            out.push_str("callback ");
+            out.push_str(&escape(str_of(node, "name")?)?);
+            out.push_str(" = ");
+            write_type(out, field_of(node, "idlType")?)?;
+            write_arguments(out, node)?;
+            out.push_str(";\n");
+        }
+        "enum" => {
+            out.push_str("enum ");
+            out.push_str(&escape(str_of(node, "name")?)?);
+            out.push_str(" {");
+            for (i, value) in array_of(node, "values")?.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(" \"");
+                out.push_str(string(str_of(value, "value")?)?);
+                out.push('"');
+            }
+            out.push_str(" };\n");
+        }
+        "typedef" => {
+            out.push_str("typedef ");
+            write_type(out, field_of(node, "idlType")?)?;
+            out.push(' ');
+            out.push_str(&escape(str_of(node, "name")?)?);
+            out.push_str(";\n");
+        }
+        "includes" => {
+            out.push_str(&escape(str_of(node, "target")?)?);
+            out.push_str(" includes ");
+            out.push_str(&escape(str_of(node, "includes")?)?);
+            out.push_str(";\n");
+        }
+        _ => {
+            return Err(ImportError::shape(format!(
+                "Unknown definition type `{kind}`"
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn write_member(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    let kind = str_of(node, "type")?;
+    write_ext_attrs(out, node)?;
+    match kind {
+        "attribute" => {
+            write_special(out, node)?;
+            if bool_of(node, "readonly")? {
+                out.push_str("readonly ");
+            }
+            out.push_str("attribute ");
+            write_type(out, field_of(node, "idlType")?)?;
+            out.push(' ');
+            out.push_str(&escape(str_of(node, "name")?)?);
+        }
+        "operation" => {
+            write_special(out, node)?;
+            match node.get("idlType") {
+                None | Some(Value::Null) => {
+                    // `stringifier;`
+                    if out.ends_with(' ') {
+                        out.pop();
+                    }
+                }
+                Some(type_) => {
+                    write_type(out, type_)?;
+                    let name = str_of(node, "name")?;
+                    if !name.is_empty() {
+                        out.push(' ');
+                        out.push_str(&escape(name)?);
+                    }
+                    write_arguments(out, node)?;
+                }
+            }
+        }
+        "constructor" => {
+            out.push_str("constructor");
+            write_arguments(out, node)?;
+        }
+        "const" => {
+            out.push_str("const ");
+            write_type(out, field_of(node, "idlType")?)?;
+            out.push(' ');
+            out.push_str(&escape(str_of(node, "name")?)?);
+            out.push_str(" = ");
+            write_value(out, field_of(node, "value")?)?;
+        }
+        "iterable" | "async_iterable" | "maplike" | "setlike" => {
+            if bool_of(node, "readonly")? {
+                out.push_str("readonly ");
+            }
+            out.push_str(match kind {
+                "async_iterable" => "async iterable",
+                _ => kind,
+            });
+            write_generics(out, node)?;
+            if !array_of(node, "arguments")?.is_empty() {
+                write_arguments(out, node)?;
+            }
+        }
+        "field" => {
+            if bool_of(node, "required")? {
+                out.push_str("required ");
+            }
+            write_type(out, field_of(node, "idlType")?)?;
+            out.push(' ');
+            out.push_str(&escape(str_of(node, "name")?)?);
+            write_default(out, node)?;
+        }
+        _ => return Err(ImportError::shape(format!("Unknown member type `{kind}`"))),
+    }
+    out.push(';');
+    Ok(())
+}
+
+fn write_special(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    match node.get("special").and_then(Value::as_str) {
+        None | Some("") => {}
+        Some(special) if SPECIALS.contains(&special) => {
+            out.push_str(special);
+            out.push(' ');
+        }
+        Some(special) => return Err(ImportError::shape(format!("Unknown special `{special}`"))),
+    }
+    Ok(())
+}
+
+fn write_ext_attrs(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    let attributes = array_of(node, "extAttrs")?;
+    if attributes.is_empty() {
+        return Ok(());
+    }
+    out.push('[');
+    for (i, attribute) in attributes.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(identifier(str_of(attribute, "name")?)?);
+        match attribute.get("rhs") {
+            None | Some(Value::Null) => {}
+            Some(rhs) => {
+                out.push('=');
+                match field_of(rhs, "value")? {
+                    Value::String(value) => out.push_str(ext_attr_value(value)?),
+                    Value::Array(items) => {
+                        out.push('(');
+                        for (i, item) in items.iter().enumerate() {
+                            if i > 0 {
+                                out.push_str(", ");
+                            }
+                            out.push_str(ext_attr_value(str_of(item, "value")?)?);
+                        }
+                        out.push(')');
+                    }
+                    _ => {
+                        return Err(ImportError::shape(
+                            "Expected an extended attribute value to be a string or a list",
+                        ))
+                    }
+                }
+            }
+        }
+        if !array_of(attribute, "arguments")?.is_empty() {
+            write_arguments(out, attribute)?;
+        }
+    }
+    out.push_str("] ");
+    Ok(())
+}
+
+fn write_arguments(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    out.push('(');
+    for (i, argument) in array_of(node, "arguments")?.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_ext_attrs(out, argument)?;
+        if bool_of(argument, "optional")? {
+            out.push_str("optional ");
+        }
+        write_type(out, field_of(argument, "idlType")?)?;
+        if bool_of(argument, "variadic")? {
+            out.push_str("...");
+        }
+        out.push(' ');
+        out.push_str(&escape(str_of(argument, "name")?)?);
+        write_default(out, argument)?;
+    }
+    out.push(')');
+    Ok(())
+}
+
+fn write_default(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    match node.get("default") {
+        None | Some(Value::Null) => Ok(()),
+        Some(value) => {
+            out.push_str(" = ");
+            write_value(out, value)
+        }
+    }
+}
+
+fn write_value(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    match str_of(node, "type")? {
+        "number" => out.push_str(number(str_of(node, "value")?)?),
+        "boolean" => out.push_str(if bool_of(node, "value")? {
+            "true"
+        } else {
+            "false"
+        }),
+        "string" => {
+            out.push('"');
+            out.push_str(string(str_of(node, "value")?)?);
+            out.push('"');
+        }
+        "null" => out.push_str("null"),
+        "sequence" => out.push_str("[]"),
+        "dictionary" => out.push_str("{}"),
+        "Infinity" if bool_of(node, "negative")? => out.push_str("-Infinity"),
+        "Infinity" => out.push_str("Infinity"),
+        "NaN" => out.push_str("NaN"),
+        kind => return Err(ImportError::shape(format!("Unknown value type `{kind}`"))),
+    }
+    Ok(())
+}
+
+fn write_generics(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    let types = match field_of(node, "idlType")? {
+        Value::Array(types) => types,
+        _ => return Err(ImportError::shape("Expected `idlType` to be an array")),
+    };
+    out.push('<');
+    for (i, type_) in types.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_type(out, type_)?;
+    }
+    out.push('>');
+    Ok(())
+}
+
+fn write_type(out: &mut String, node: &Value) -> Result<(), ImportError> {
+    write_ext_attrs(out, node)?;
+    if bool_of(node, "union")? {
+        let Value::Array(members) = field_of(node, "idlType")? else {
+            return Err(ImportError::shape(
+                "Expected the `idlType` of a union to be an array",
+            ));
+        };
+        out.push('(');
+        for (i, member) in members.iter().enumerate() {
+            if i > 0 {
+                out.push_str(" or ");
+            }
+            write_type(out, member)?;
+        }
+        out.push(')');
+    } else {
+        match node.get("generic").and_then(Value::as_str) {
+            None | Some("") => {
+                let name = str_of(node, "idlType")?;
+                if BUILTIN_TYPES.contains(&name) {
+                    out.push_str(name);
+                } else if is_escaped_builtin(name) {
+                    out.push_str(identifier(name)?);
+                } else {
+                    out.push_str(&escape(name)?);
+                }
+            }
+            Some(generic) if GENERIC_TYPES.contains(&generic) => {
+                out.push_str(generic);
+                write_generics(out, node)?;
+            }
+            Some(generic) => {
+                return Err(ImportError::shape(format!(
+                    "Unknown generic type `{generic}`"
+                )))
+            }
+        }
+    }
+    if bool_of(node, "nullable")? {
+        out.push('?');
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn export(source: &str) -> Value {
+        to_json(&crate::parse(source).unwrap())
+    }
+
+    #[test]
+    fn should_export_interface() {
+        let json = export(
+            r#"
+            [Exposed=Window]
+            interface Node : EventTarget {
+                readonly attribute unsigned short nodeType;
+                Node cloneNode(optional boolean deep = false);
+            };
+            "#,
+        );
+
+        assert_eq!(
+            json,
+            json!([
+                {
+                    "type": "interface",
+                    "name": "Node",
+                    "inheritance": "EventTarget",
+                    "members": [
+                        {
+                            "type": "attribute",
+                            "name": "nodeType",
+                            "idlType": {
+                                "type": "attribute-type",
+                                "extAttrs": [],
+                                "generic": "",
+                                "nullable": false,
+                                "union": false,
+                                "idlType": "unsigned short",
+                            },
+                            "extAttrs": [],
+                            "special": "",
+                            "readonly": true,
+                        },
+                        {
+                            "type": "operation",
+                            "name": "cloneNode",
+                            "idlType": {
+                                "type": "return-type",
+                                "extAttrs": [],
+                                "generic": "",
+                                "nullable": false,
+                                "union": false,
+                                "idlType": "Node",
+                            },
+                            "arguments": [{
+                                "type": "argument",
+                                "name": "deep",
+                                "extAttrs": [],
+                                "idlType": {
+                                    "type": "argument-type",
+                                    "extAttrs": [],
+                                    "generic": "",
+                                    "nullable": false,
+                                    "union": false,
+                                    "idlType": "boolean",
+                                },
+                                "default": { "type": "boolean", "value": false },
+                                "optional": true,
+                                "variadic": false,
+                            }],
+                            "extAttrs": [],
+                            "special": "",
+                        },
+                    ],
+                    "extAttrs": [{
+                        "type": "extended-attribute",
+                        "name": "Exposed",
+                        "rhs": { "type": "identifier", "value": "Window" },
+                        "arguments": [],
+                    }],
+                    "partial": false,
+                },
+                { "type": "eof", "value": "" },
+            ])
+        );
+    }
+
+    #[test]
+    fn should_export_types() {
+        let json = export("typedef (sequence<[Clamp] long>? or record<DOMString, _any>) T;");
+
+        assert_eq!(
+            json[0]["idlType"],
+            json!({
+                "type": "typedef-type",
+                "extAttrs": [],
+                "generic": "",
+                "nullable": false,
+                "union": true,
+                "idlType": [
+                    {
+                        "type": "typedef-type",
+                        "extAttrs": [],
+                        "generic": "sequence",
+                        "nullable": true,
+                        "union": false,
+                        "idlType": [{
+                            "type": "typedef-type",
+                            "extAttrs": [{
+                                "type": "extended-attribute",
+                                "name": "Clamp",
+                                "rhs": null,
+                                "arguments": [],
+                            }],
+                            "generic": "",
+                            "nullable": false,
+                            "union": false,
+                            "idlType": "long",
+                        }],
+                    },
+                    {
+                        "type": "typedef-type",
+                        "extAttrs": [],
+                        "generic": "record",
+                        "nullable": false,
+                        "union": false,
+                        "idlType": [
+                            {
+                                "type": "typedef-type",
+                                "extAttrs": [],
+                                "generic": "",
+                                "nullable": false,
+                                "union": false,
+                                "idlType": "DOMString",
+                            },
+                            {
+                                "type": "typedef-type",
+                                "extAttrs": [],
+                                "generic": "",
+                                "nullable": false,
+                                "union": false,
+                                "idlType": "_any",
+                            },
+                        ],
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn should_import_escaped_names() {
+        let definitions = crate::parse(
+            r#"
+            interface _interface {
+                attribute long _required;
+                const float _NaN = -Infinity;
+                stringifier;
+            };
+            "#,
+        )
+        .unwrap();
+        let json = to_json(&definitions);
+        assert_eq!(json[0]["name"], "interface");
+
+        assert_eq!(from_json(&json).unwrap(), definitions);
+    }
+
+    #[test]
+    fn should_import_escaped_builtin_types() {
+        let definitions = crate::parse("typedef _any T; typedef sequence<_object> U;").unwrap();
+        let json = to_json(&definitions);
+        assert_eq!(json[0]["idlType"]["idlType"], "_any");
+
+        assert_eq!(from_json(&json).unwrap(), definitions);
+        assert_eq!(
+            to_webidl(&json).unwrap(),
+            "typedef _any T;\ntypedef sequence<_object> U;\n"
+        );
+    }
+
+    #[test]
+    fn should_not_import_other_tokens() {
+        let shape = |json: Value| match from_json(&json) {
+            Err(ImportError::Shape(message)) => message,
+            result => panic!("Expected a shape error, got {result:?}"),
+        };
+
+        let json = json!([{ "type": "interface", "name": "A { }; interface B", "members": [] }]);
+        assert_eq!(shape(json), "Invalid identifier `A { }; interface B`");
+
+        let json = json!([{
+            "type": "enum",
+            "name": "E",
+            "values": [{ "type": "enum-value", "value": "a\", \"b" }],
+        }]);
+        assert_eq!(shape(json), "Invalid string `a\", \"b`");
+
+        let json = json!([{
+            "type": "interface",
+            "name": "A",
+            "extAttrs": [{
+                "type": "extended-attribute",
+                "name": "Exposed",
+                "rhs": { "type": "identifier", "value": "Window] interface B {}; [C" },
+            }],
+            "members": [],
+        }]);
+        assert_eq!(
+            shape(json),
+            "Invalid extended attribute value `Window] interface B {}; [C`"
+        );
+
+        let type_ = |generic: &str, name: &str| {
+            json!([{
+                "type": "typedef",
+                "name": "T",
+                "idlType": { "generic": generic, "idlType": name },
+            }])
+        };
+        assert_eq!(shape(type_("", "long;")), "Invalid identifier `long;`");
+        assert_eq!(shape(type_("", "")), "Invalid identifier ``");
+        assert_eq!(shape(type_("Map", "long")), "Unknown generic type `Map`");
+    }
+
+    #[test]
+    fn should_not_import_unknown_nodes() {
+        let json = json!([{ "type": "module", "name": "M" }]);
+        assert_eq!(
+            from_json(&json),
+            Err(ImportError::Shape(
+                "Unknown definition type `module`".into()
+            ))
+        );
+
+        let json = json!([{ "type": "typedef", "name": "T" }]);
+        assert_eq!(
+            from_json(&json),
+            Err(ImportError::Shape(
+                "Missing `idlType` in typedef node".into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn weedle(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_weedle"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("weedle-cli-{}-{name}", std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn should_check_valid_files() {
+    let output = weedle(&["check", "tests/defs/dom.webidl", "tests/defs/html.webidl"]);
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn should_report_every_syntax_error() {
+    let path = temp_file(
+        "syntax.webidl",
+        "interface A {\n  readonly attribute Storage;\n  attribute long;\n};\n",
+    );
+    let output = weedle(&["check", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error: Missing name").count(), 2);
+    assert!(stderr.contains(":2:29\n"));
+}
+
+#[test]
+fn should_report_validation_errors() {
+    let path = temp_file("validate.webidl", "enum E { \"a\", \"a\" };\n");
+    let output = weedle(&["check", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: "));
+}
+
+#[test]
+fn should_check_with_the_dialect() {
+    let path = temp_file("gecko.webidl", "Node implements EventTarget;\n");
+    let file = path.to_str().unwrap();
+
+    assert_eq!(weedle(&["check", file]).status.code(), Some(1));
+    assert!(weedle(&["check", "--dialect", "gecko", file])
+        .status
+        .success());
+
+    let output = weedle(&["check", "--dialect", "spidermonkey", file]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unknown dialect `spidermonkey`"));
+}
+
+#[test]
+fn should_format_in_place() {
+    let path = temp_file(
+        "fmt.webidl",
+        "// Comment\ninterface   A{attribute long x;};\n",
+    );
+    let file = path.to_str().unwrap();
+
+    let output = weedle(&["fmt", "--check", file]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{file}\n")
+    );
+
+    assert!(weedle(&["fmt", file]).status.success());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "// Comment\ninterface A {\n  attribute long x;\n};\n"
+    );
+    assert!(weedle(&["fmt", "--check", file]).status.success());
+}
+
+#[test]
+fn should_count_definitions_and_members() {
+    let output = weedle(&["stats", "tests/defs/enum.webidl"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Definitions\n  enum "));
+    assert!(stdout.contains("\n  enum value                   6\n"));
+}
+
+#[test]
+fn should_dump_debug() {
+    let output = weedle(&["dump", "tests/defs/enum.webidl"]);
+
+    assert!(output.status.success());
+    let baseline = std::fs::read_to_string("tests/baselines/defs/enum.txt").unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), baseline);
+}
+
+#[test]
+fn should_dump_json() {
+    let output = weedle(&["dump", "--json", "tests/defs/enum.webidl"]);
+
+    assert!(output.status.success());
+    let baseline = std::fs::read_to_string("tests/baselines/json/enum.json").unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), baseline);
+}
+
+#[test]
+fn should_highlight_html() {
+    let path = temp_file("highlight.webidl", "enum E { \"<a>\" };\n");
+    let output = weedle(&["highlight", "--html", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "<span class=\"webidl-keyword\">enum</span> \
+         <span class=\"webidl-identifier\">E</span> \
+         <span class=\"webidl-punctuation\">{</span> \
+         <span class=\"webidl-string\">&quot;&lt;a&gt;&quot;</span> \
+         <span class=\"webidl-punctuation\">};</span>\n"
+    );
+}
+
+#[test]
+fn should_reject_unknown_commands() {
+    let output = weedle(&["lint", "tests/defs/enum.webidl"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = weedle(&["fmt", "--json", "tests/defs/enum.webidl"]);
+    assert_eq!(output.status.code(), Some(2));
+}
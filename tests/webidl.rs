@@ -13,11 +13,85 @@ fn should_parse(resource: &str) {
     assert_eq!(format!("{result:#?}\n"), baseline);
 }
 
+#[cfg(feature = "serde")]
+#[test_resources("tests/defs/*.webidl")]
+fn should_serialize_to_json(resource: &str) {
+    let content = std::fs::read_to_string(resource).unwrap();
+    let result = weedle::parse(&content).unwrap();
+
+    let resource_path = std::path::Path::new(resource);
+    let stem = resource_path.file_stem().unwrap().to_str().unwrap();
+    let baseline_path =
+        std::path::Path::new("./tests/baselines/json/").join(format!("{stem}.json"));
+    let baseline = std::fs::read_to_string(baseline_path).unwrap();
+
+    let json = serde_json::to_string_pretty(&result).unwrap();
+    assert_eq!(format!("{json}\n"), baseline);
+
+    let deserialized: weedle::Definitions = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, result);
+}
+
+#[cfg(feature = "webidl2")]
+#[test_resources("tests/defs/*.webidl")]
+fn should_round_trip_webidl2_json(resource: &str) {
+    use weedle::webidl2::{from_json, to_json};
+
+    let content = std::fs::read_to_string(resource).unwrap();
+    let json = to_json(&weedle::parse(&content).unwrap());
+
+    let imported = from_json(&json).unwrap();
+    assert_eq!(to_json(&imported), json);
+}
+
+#[cfg(feature = "webidl2")]
+#[test_resources("tests/webidl2/*.json")]
+fn should_match_webidl2_js(resource: &str) {
+    use weedle::webidl2::{from_json, to_json};
+
+    let resource_path = std::path::Path::new(resource);
+    let stem = resource_path.file_stem().unwrap().to_str().unwrap();
+    let source_path = std::path::Path::new("./tests/defs/").join(format!("{stem}.webidl"));
+    let content = std::fs::read_to_string(source_path).unwrap();
+    let expected: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(resource).unwrap()).unwrap();
+
+    assert_eq!(to_json(&weedle::parse(&content).unwrap()), expected);
+    assert_eq!(to_json(&from_json(&expected).unwrap()), expected);
+}
+
+#[test_resources("tests/defs/*.webidl")]
+fn should_print_lossless(resource: &str) {
+    let content = std::fs::read_to_string(resource).unwrap();
+    let file = weedle::lossless::parse(&content).unwrap();
+
+    assert_eq!(file.to_string(), content);
+}
+
+#[test_resources("tests/defs/*.webidl")]
+fn should_format(resource: &str) {
+    use weedle::fmt::{format, format_source_file, FormatOptions};
+
+    let content = std::fs::read_to_string(resource).unwrap();
+    let options = FormatOptions::default();
+
+    let parsed = weedle::parse(&content).unwrap();
+    let formatted = format(&parsed, &options);
+    assert_eq!(weedle::parse(&formatted).unwrap(), parsed);
+    assert_eq!(
+        format(&weedle::parse(&formatted).unwrap(), &options),
+        formatted
+    );
+
+    let file = weedle::lossless::parse(&content).unwrap();
+    let formatted = format_source_file(&file, &options);
+    let reparsed = weedle::lossless::parse(&formatted).unwrap();
+    assert_eq!(reparsed.definitions, parsed);
+    assert_eq!(format_source_file(&reparsed, &options), formatted);
+}
+
 #[test_resources("tests/invalids/*.webidl")]
 fn should_not_parse(resource: &str) {
-    use nom::error::convert_error;
-    use nom::Err::*;
-
     let content = std::fs::read_to_string(resource).unwrap();
     let err = weedle::parse(&content).unwrap_err();
 
@@ -27,11 +101,11 @@ fn should_not_parse(resource: &str) {
         std::path::Path::new("./tests/baselines/invalids/").join(format!("{stem}.txt"));
     let baseline = std::fs::read_to_string(baseline_path).unwrap();
 
-    let message = match err {
-        Error(e) | Failure(e) => convert_error(&content[..], e),
-        Incomplete(_) => "Unexpected incomplete error".to_owned(),
-    };
-    assert_eq!(message, baseline);
+    assert_ne!(err.kind(), weedle::ErrorKind::UnexpectedToken);
+
+    let file_name = resource_path.file_name().unwrap().to_str().unwrap();
+    let rendered = weedle::diagnostic::Diagnostic::from(&err).render(file_name, &content);
+    assert_eq!(rendered, baseline);
 }
 
 #[test]
@@ -50,6 +124,35 @@ fn should_parse_html_webidl() {
     assert_eq!(parsed.len(), 325);
 }
 
+#[test]
+fn should_recover_html_webidl() {
+    let content = std::fs::read_to_string("./tests/defs/html.webidl").unwrap();
+    let broken = content
+        .replacen(
+            "readonly attribute unsigned long length;",
+            "readonly attribute unsigned long length",
+            1,
+        )
+        .replacen(
+            "readonly attribute ValidityState validity;",
+            "readonly attribute ValidityState;",
+            1,
+        )
+        .replacen(
+            "readonly attribute DOMStringList ancestorOrigins;",
+            "readonly attribute DOMStringList ancestorOrigins",
+            1,
+        );
+    let (parsed, errors) = weedle::parse_recovering(&broken);
+
+    let lines: Vec<_> = errors.iter().map(|err| err.location().line).collect();
+    assert_eq!(lines, [5, 392, 1542]);
+    assert_eq!(parsed.len(), 325);
+
+    let (file, _) = weedle::lossless::parse_recovering(&broken);
+    assert_eq!(file.to_string(), broken);
+}
+
 #[test]
 fn should_parse_mediacapture_streams_webidl() {
     let content = std::fs::read_to_string("./tests/defs/mediacapture-streams.webidl").unwrap();
@@ -129,3 +232,105 @@ fn interface_constructor() {
         _ => unreachable!(),
     }
 }
+
+#[test]
+fn should_extract_doc_comments() {
+    for (resource, last_line, line_count) in [
+        (
+            "./tests/defs/documentation.webidl",
+            r#"<p><img src="foo.png" alt="Valid"/></p>"#,
+            31,
+        ),
+        (
+            "./tests/defs/documentation-dos.webidl",
+            "<p>This is <br/> valid.</p>",
+            30,
+        ),
+    ] {
+        let content = std::fs::read_to_string(resource).unwrap();
+        let file = weedle::lossless::parse(&content).unwrap();
+
+        let doc = file.doc_comment(&file.definitions[0]).unwrap();
+        let lines: Vec<_> = doc.lines().collect();
+        assert_eq!(lines[0], "\\brief Testing documentation features");
+        assert_eq!(lines[1], "");
+        assert_eq!(lines[2], "This is a");
+        assert_eq!(lines.last(), Some(&last_line));
+        assert_eq!(lines.len(), line_count);
+    }
+}
+
+#[test]
+fn should_resolve_dom_and_html_webidl() {
+    let dom = std::fs::read_to_string("./tests/defs/dom.webidl").unwrap();
+    let html = std::fs::read_to_string("./tests/defs/html.webidl").unwrap();
+    let sources = [weedle::parse(&dom).unwrap(), weedle::parse(&html).unwrap()];
+
+    let (model, errors) = weedle::resolve::resolve(&sources);
+
+    // Defined by the SVG and CSSOM specifications
+    let messages: Vec<_> = errors.iter().map(|err| err.to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Unknown interface `SVGElement`",
+            "Unknown mixin `LinkStyle`",
+            "Unknown mixin `LinkStyle`",
+            "Partial definition of `NavigatorID` has no main definition",
+        ]
+    );
+
+    let document = &model.interfaces["Document"];
+    assert!(document.partials.len() > 1);
+    assert!(document
+        .includes
+        .iter()
+        .any(|mixin| mixin.0 == "DocumentOrShadowRoot"));
+}
+
+#[test]
+fn should_validate_dom_and_html_webidl() {
+    let dom = std::fs::read_to_string("./tests/defs/dom.webidl").unwrap();
+    let html = std::fs::read_to_string("./tests/defs/html.webidl").unwrap();
+    let mut definitions = weedle::parse(&dom).unwrap();
+    definitions.extend(weedle::parse(&html).unwrap());
+
+    let diagnostics = weedle::validate::validate(&definitions);
+    let messages: Vec<_> = diagnostics.iter().map(|d| &d.message).collect();
+    assert_eq!(messages, Vec::<&String>::new());
+}
+
+#[test]
+fn should_expand_html_typedefs() {
+    use weedle::symbols::{ResolvedType, Symbol, SymbolErrorKind, SymbolTable};
+
+    let dom = std::fs::read_to_string("./tests/defs/dom.webidl").unwrap();
+    let html = std::fs::read_to_string("./tests/defs/html.webidl").unwrap();
+    let mut definitions = weedle::parse(&dom).unwrap();
+    definitions.extend(weedle::parse(&html).unwrap());
+    let symbols = SymbolTable::new(&definitions);
+
+    // Names defined by other specifications, like SVG and the File API, are unknown
+    let errors = symbols.check(&definitions);
+    assert!(!errors.is_empty());
+    assert!(errors
+        .iter()
+        .all(|err| err.kind == SymbolErrorKind::UnknownName));
+
+    let Some(Symbol::Typedef(source)) = symbols.get("ImageBitmapSource") else {
+        panic!("ImageBitmapSource is a typedef");
+    };
+    assert_eq!(
+        symbols.expand_lenient(&source.type_.type_),
+        Ok(ResolvedType::Union(vec![
+            ResolvedType::Interface("HTMLImageElement".into()),
+            ResolvedType::Unknown("SVGImageElement".into()),
+            ResolvedType::Interface("HTMLVideoElement".into()),
+            ResolvedType::Interface("HTMLCanvasElement".into()),
+            ResolvedType::Interface("ImageBitmap".into()),
+            ResolvedType::Interface("OffscreenCanvas".into()),
+            ResolvedType::Unknown("Blob".into()),
+            ResolvedType::Interface("ImageData".into()),
+        ]))
+    );
+}
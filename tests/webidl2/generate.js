@@ -0,0 +1,20 @@
+// Regenerates the webidl2.js syntax trees of this directory from the sources of the same name
+// in tests/defs:
+//
+//     npm install webidl2@24
+//     node tests/webidl2/generate.js
+//
+// Add a fixture by creating an empty `<name>.json` next to this file.
+
+const fs = require("fs");
+const path = require("path");
+const { parse } = require("webidl2");
+
+for (const file of fs.readdirSync(__dirname)) {
+  if (!file.endsWith(".json")) {
+    continue;
+  }
+  const source = path.join(__dirname, "..", "defs", file.replace(/\.json$/, ".webidl"));
+  const tree = parse(fs.readFileSync(source, "utf8"), { concrete: true });
+  fs.writeFileSync(path.join(__dirname, file), `${JSON.stringify(tree, null, 4)}\n`);
+}